@@ -105,18 +105,15 @@ impl AnyCollider for CircleCollider {
                 index: 0,
                 normal1,
                 normal2,
-                contacts: vec![ContactData {
-                    feature_id1: PackedFeatureId::face(0),
-                    feature_id2: PackedFeatureId::face(0),
+                // Impulses are computed by the constraint solver
+                contacts: vec![ContactData::new(
                     point1,
                     point2,
                     normal1,
                     normal2,
-                    penetration: sum_radius - distance_squared.sqrt(),
-                    // Impulses are computed by the constraint solver
-                    normal_impulse: 0.0,
-                    tangent_impulse: 0.0,
-                }],
+                    sum_radius - distance_squared.sqrt(),
+                )
+                .with_feature_ids(PackedFeatureId::face(0), PackedFeatureId::face(0))],
             }]
         } else {
             vec![]
@@ -21,7 +21,8 @@ pub struct BroadPhasePlugin;
 impl Plugin for BroadPhasePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<BroadCollisionPairs>()
-            .init_resource::<AabbIntervals>();
+            .init_resource::<AabbIntervals>()
+            .init_resource::<JointCollisionPairs>();
 
         app.configure_sets(
             PhysicsSchedule,
@@ -182,6 +183,7 @@ fn collect_collision_pairs(
     intervals: ResMut<AabbIntervals>,
     mut broad_collision_pairs: ResMut<BroadCollisionPairs>,
     mut aabb_intersection_query: Query<&mut AabbIntersections>,
+    joint_collision_pairs: Res<JointCollisionPairs>,
 ) {
     for mut intersections in &mut aabb_intersection_query {
         intersections.clear();
@@ -191,6 +193,7 @@ fn collect_collision_pairs(
         intervals,
         &mut broad_collision_pairs.0,
         &mut aabb_intersection_query,
+        &joint_collision_pairs,
     );
 }
 
@@ -201,6 +204,7 @@ fn sweep_and_prune(
     mut intervals: ResMut<AabbIntervals>,
     broad_collision_pairs: &mut Vec<(Entity, Entity)>,
     aabb_intersection_query: &mut Query<&mut AabbIntersections>,
+    joint_collision_pairs: &JointCollisionPairs,
 ) {
     // Sort bodies along the x-axis using insertion sort, a sorting algorithm great for sorting nearly sorted lists.
     insertion_sort(&mut intervals.0, |a, b| a.2.min.x > b.2.min.x);
@@ -237,6 +241,11 @@ fn sweep_and_prune(
                 continue;
             }
 
+            // No collisions between bodies connected by a joint with collisions disabled
+            if joint_collision_pairs.contains(parent1.get(), parent2.get()) {
+                continue;
+            }
+
             if *ent1 < *ent2 {
                 broad_collision_pairs.push((*ent1, *ent2));
             } else {
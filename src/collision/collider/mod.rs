@@ -428,6 +428,171 @@ impl Default for ColliderAabb {
 #[doc(alias = "ContactSkin")]
 pub struct CollisionMargin(pub Scalar);
 
+/// A component that gives the surface of a [`Collider`] a velocity relative to the collider itself,
+/// without the collider actually moving. Friction drags touching bodies along with the surface,
+/// which is useful for things like conveyor belts, escalators and moving walkways.
+///
+/// The velocity is in the local space of the collider, so it rotates with the collider.
+/// The `linear` part moves the whole surface, while the `angular` part rotates it around
+/// the origin of the collider, like a turntable.
+///
+/// The surface velocity only has an effect if the contact has [`Friction`].
+///
+/// # Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // Spawn a static conveyor belt that moves bodies along its local X axis at 2 m/s.
+///     commands.spawn((
+///         RigidBody::Static,
+#[cfg_attr(feature = "2d", doc = "        Collider::rectangle(10.0, 0.5),")]
+#[cfg_attr(feature = "3d", doc = "        Collider::cuboid(10.0, 0.5, 2.0),")]
+#[cfg_attr(
+    feature = "2d",
+    doc = "        SurfaceVelocity::new(Vec2::new(2.0, 0.0)),"
+)]
+#[cfg_attr(
+    feature = "3d",
+    doc = "        SurfaceVelocity::new(Vec3::new(2.0, 0.0, 0.0)),"
+)]
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, Default, PartialEq)]
+pub struct SurfaceVelocity {
+    /// The linear velocity of the surface in the local space of the collider.
+    pub linear: Vector,
+    /// The angular velocity of the surface around the origin of the collider in radians per second.
+    #[cfg(feature = "2d")]
+    pub angular: Scalar,
+    /// The angular velocity of the surface around the origin of the collider
+    /// as a rotation axis in local space multiplied by the angular speed in radians per second.
+    #[cfg(feature = "3d")]
+    pub angular: Vector,
+}
+
+impl SurfaceVelocity {
+    /// Zero surface velocity.
+    pub const ZERO: Self = Self {
+        linear: Vector::ZERO,
+        #[cfg(feature = "2d")]
+        angular: 0.0,
+        #[cfg(feature = "3d")]
+        angular: Vector::ZERO,
+    };
+
+    /// Creates a new [`SurfaceVelocity`] with the given linear velocity in the local space of the collider.
+    pub const fn new(linear: Vector) -> Self {
+        Self {
+            linear,
+            ..Self::ZERO
+        }
+    }
+
+    /// Sets the angular velocity of the surface around the origin of the collider.
+    #[cfg(feature = "2d")]
+    pub const fn with_angular(self, angular: Scalar) -> Self {
+        Self { angular, ..self }
+    }
+
+    /// Sets the angular velocity of the surface around the origin of the collider.
+    #[cfg(feature = "3d")]
+    pub const fn with_angular(self, angular: Vector) -> Self {
+        Self { angular, ..self }
+    }
+
+    /// Computes the velocity of the surface at the given point in the local space of the collider.
+    pub fn velocity_at_point(&self, point: Vector) -> Vector {
+        #[cfg(feature = "2d")]
+        {
+            self.linear + self.angular * point.perp()
+        }
+        #[cfg(feature = "3d")]
+        {
+            self.linear + self.angular.cross(point)
+        }
+    }
+}
+
+/// A component that overrides the softness of contacts for a [`Collider`],
+/// which can be used for things like squishy pads, rubber bumpers and stiff metal parts.
+///
+/// By default, the contact softness is determined by the [`SolverConfig`] for all contacts.
+/// With [`ContactSoftness`], contacts involving the collider instead behave like a damped spring
+/// with the given `damping_ratio` and `frequency` in Hertz. A lower frequency makes contacts softer,
+/// letting bodies sink into each other more before being pushed apart, while a higher damping ratio
+/// reduces bouncing.
+///
+/// If both colliders in a contact have a [`ContactSoftness`], they are combined as if
+/// the springs were in series, so the softer collider dominates. See [`ContactSoftness::combine`].
+///
+/// If a rigid body with a [`ContactSoftness`] has colliders as child entities,
+/// and those colliders don't have their own [`ContactSoftness`] components,
+/// the colliders will use the rigid body's [`ContactSoftness`].
+///
+/// # Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // Spawn a squishy pad that bodies sink into.
+///     commands.spawn((
+///         RigidBody::Static,
+#[cfg_attr(feature = "2d", doc = "        Collider::rectangle(4.0, 0.5),")]
+#[cfg_attr(feature = "3d", doc = "        Collider::cuboid(4.0, 0.5, 4.0),")]
+///         ContactSoftness::new(0.5, 5.0),
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, PartialEq)]
+pub struct ContactSoftness {
+    /// The damping ratio of the contact spring. Controls the amount of oscillation.
+    pub damping_ratio: Scalar,
+    /// The frequency of the contact spring in Hertz. Controls the stiffness of contacts.
+    pub frequency: Scalar,
+}
+
+impl ContactSoftness {
+    /// Creates a new [`ContactSoftness`] with the given damping ratio and frequency in Hertz.
+    pub const fn new(damping_ratio: Scalar, frequency: Scalar) -> Self {
+        Self {
+            damping_ratio,
+            frequency,
+        }
+    }
+
+    /// Combines the contact softness of two colliders.
+    ///
+    /// The stiffness of a spring is proportional to the square of its frequency,
+    /// so the frequencies are combined like the stiffnesses of two springs in series.
+    /// The damping ratios are averaged.
+    pub fn combine(self, other: Self) -> Self {
+        let (f1, f2) = (self.frequency, other.frequency);
+        Self {
+            damping_ratio: (self.damping_ratio + other.damping_ratio) * 0.5,
+            frequency: f1 * f2 * (f1 * f1 + f2 * f2).sqrt().recip_or_zero(),
+        }
+    }
+
+    /// Returns the [`SoftnessParameters`] corresponding to the contact softness.
+    pub fn parameters(self) -> SoftnessParameters {
+        SoftnessParameters::new(self.damping_ratio, self.frequency)
+    }
+}
+
 /// A component that stores the entities that are colliding with an entity.
 ///
 /// This component is automatically added for all entities with a [`Collider`],
@@ -21,7 +21,10 @@ pub struct ColliderQuery<C: AnyCollider> {
     pub is_rb: Has<RigidBody>,
     pub is_sensor: Has<Sensor>,
     pub friction: Option<&'static Friction>,
+    pub anisotropic_friction: Option<&'static AnisotropicFriction>,
     pub restitution: Option<&'static Restitution>,
+    pub surface_velocity: Option<&'static SurfaceVelocity>,
+    pub contact_softness: Option<&'static ContactSoftness>,
     pub shape: &'static C,
 }
 
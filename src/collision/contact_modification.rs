@@ -0,0 +1,147 @@
+//! Modifies contacts before contact constraints are generated.
+//!
+//! See [`ContactModificationHooks`].
+
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+use bevy::{
+    ecs::{
+        intern::Interned,
+        schedule::ScheduleLabel,
+        system::{ReadOnlySystemParam, StaticSystemParam, SystemParamItem},
+    },
+    prelude::*,
+};
+
+/// A trait for modifying the [`Contacts`] between two entities after they have been computed
+/// by the narrow phase, but before [contact constraints](dynamics::solver::contact::ContactConstraint)
+/// are generated for them.
+///
+/// The hooks can modify the properties of individual [contact points](ContactData):
+///
+/// - `enabled`: Disables the contact point for the solver.
+/// - `friction` and `restitution`: Override the combined [`Friction`] and [`Restitution`] coefficients.
+/// - `tangent_velocity`: Sets a target relative velocity along the contact surface, like for conveyor belts.
+/// - `normal_impulse_scale`: Scales the response along the contact normal.
+///
+/// The properties are reset every time contacts are computed, so the hooks must
+/// apply their modifications every frame.
+///
+/// The trait is implemented for a [`SystemParam`](bevy::ecs::system::SystemParam), which allows
+/// the hooks to access ECS data like queries and resources. The system parameter must be read-only.
+/// To use the hooks, add a [`ContactModificationPlugin`] with the type of the system parameter.
+///
+/// The hooks run in [`NarrowPhaseSet::ModifyContacts`](narrow_phase::NarrowPhaseSet::ModifyContacts),
+/// after the [`PostProcessCollisions`] schedule.
+///
+/// # Example
+///
+/// A conveyor belt that moves bodies along its local X axis.
+///
+/// ```no_run
+#[cfg_attr(feature = "2d", doc = "use avian2d::{math::*, prelude::*};")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::{math::*, prelude::*};")]
+/// use bevy::{ecs::system::SystemParam, prelude::*};
+///
+/// #[derive(Component)]
+/// struct ConveyorBelt {
+///     speed: Scalar,
+/// }
+///
+/// #[derive(SystemParam)]
+/// struct ConveyorHooks<'w, 's> {
+///     belts: Query<'w, 's, (&'static ConveyorBelt, &'static Rotation)>,
+/// }
+///
+/// impl ContactModificationHooks for ConveyorHooks<'_, '_> {
+///     fn modify_contacts(&self, contacts: &mut Contacts) {
+///         // The target velocity is the velocity of the second entity's surface
+///         // relative to the first entity's surface.
+///         let (belt, rotation, sign) = if let Ok((belt, rotation)) = self.belts.get(contacts.entity1) {
+///             (belt, rotation, 1.0)
+///         } else if let Ok((belt, rotation)) = self.belts.get(contacts.entity2) {
+///             (belt, rotation, -1.0)
+///         } else {
+///             return;
+///         };
+///
+///         let velocity = sign * belt.speed * (*rotation * Vector::X);
+///         for contact in contacts.manifolds.iter_mut().flat_map(|m| m.contacts.iter_mut()) {
+///             contact.tangent_velocity = velocity;
+///         }
+///     }
+/// }
+///
+/// fn main() {
+///     App::new().add_plugins((
+///         DefaultPlugins,
+///         PhysicsPlugins::default(),
+///         ContactModificationPlugin::<ConveyorHooks>::default(),
+///     ));
+/// }
+/// ```
+pub trait ContactModificationHooks: ReadOnlySystemParam + Send + Sync {
+    /// Modifies the contacts between two entities.
+    ///
+    /// This is called for each pair of entities in [`Collisions`] before contact constraints are generated.
+    fn modify_contacts(&self, contacts: &mut Contacts);
+}
+
+/// A plugin that runs the given [`ContactModificationHooks`] for each pair of entities in [`Collisions`]
+/// before contact constraints are generated.
+pub struct ContactModificationPlugin<H: ContactModificationHooks + 'static>
+where
+    for<'w, 's> SystemParamItem<'w, 's, H>: ContactModificationHooks,
+{
+    schedule: Interned<dyn ScheduleLabel>,
+    _phantom: PhantomData<H>,
+}
+
+impl<H: ContactModificationHooks + 'static> ContactModificationPlugin<H>
+where
+    for<'w, 's> SystemParamItem<'w, 's, H>: ContactModificationHooks,
+{
+    /// Creates a [`ContactModificationPlugin`] with the schedule used for running its systems.
+    ///
+    /// The default schedule is [`PhysicsSchedule`].
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<H: ContactModificationHooks + 'static> Default for ContactModificationPlugin<H>
+where
+    for<'w, 's> SystemParamItem<'w, 's, H>: ContactModificationHooks,
+{
+    fn default() -> Self {
+        Self::new(PhysicsSchedule)
+    }
+}
+
+impl<H: ContactModificationHooks + 'static> Plugin for ContactModificationPlugin<H>
+where
+    for<'w, 's> SystemParamItem<'w, 's, H>: ContactModificationHooks,
+{
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            modify_contacts::<H>.in_set(narrow_phase::NarrowPhaseSet::ModifyContacts),
+        );
+    }
+}
+
+/// Runs the [`ContactModificationHooks`] for each pair of entities in [`Collisions`].
+fn modify_contacts<H: ContactModificationHooks>(
+    hooks: StaticSystemParam<H>,
+    mut collisions: ResMut<Collisions>,
+) where
+    for<'w, 's> SystemParamItem<'w, 's, H>: ContactModificationHooks,
+{
+    for contacts in collisions.get_internal_mut().values_mut() {
+        hooks.modify_contacts(contacts);
+    }
+}
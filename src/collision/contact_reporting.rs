@@ -15,6 +15,7 @@ use bevy::prelude::*;
 /// - [`Collision`]
 /// - [`CollisionStarted`]
 /// - [`CollisionEnded`]
+/// - [`ContactForceEvent`] (only for colliders with a [`ContactForceEventThreshold`])
 ///
 /// You can listen to them with normal event readers:
 ///
@@ -46,13 +47,16 @@ impl Plugin for ContactReportingPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<Collision>()
             .add_event::<CollisionStarted>()
-            .add_event::<CollisionEnded>();
+            .add_event::<CollisionEnded>()
+            .add_event::<ContactForceEvent>();
 
         let physics_schedule = app
             .get_schedule_mut(PhysicsSchedule)
             .expect("add PhysicsSchedule first");
 
-        physics_schedule.add_systems(report_contacts.in_set(PhysicsStepSet::ReportContacts));
+        physics_schedule.add_systems(
+            (report_contacts, report_contact_forces).in_set(PhysicsStepSet::ReportContacts),
+        );
     }
 }
 
@@ -149,6 +153,87 @@ pub struct CollisionStarted(pub Entity, pub Entity);
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct CollisionEnded(pub Entity, pub Entity);
 
+/// A [collision event](ContactReportingPlugin#collision-events) that is sent when the total force
+/// applied by the contacts between two colliders exceeds the [`ContactForceEventThreshold`]
+/// of either collider.
+///
+/// Unlike [`Collision`], this event is only sent for colliders that have a [`ContactForceEventThreshold`],
+/// which makes it suitable for things like impact sounds and damage.
+///
+/// ## Example
+///
+/// ```no_run
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn main() {
+///     App::new()
+///         .add_plugins((DefaultPlugins, PhysicsPlugins::default()))
+///         .add_systems(Startup, setup)
+///         .add_systems(Update, print_impacts)
+///         .run();
+/// }
+///
+/// fn setup(mut commands: Commands) {
+///     commands.spawn((
+///         RigidBody::Dynamic,
+#[cfg_attr(feature = "2d", doc = "        Collider::circle(0.5),")]
+#[cfg_attr(feature = "3d", doc = "        Collider::sphere(0.5),")]
+///         ContactForceEventThreshold(100.0),
+///     ));
+/// }
+///
+/// fn print_impacts(mut contact_force_event_reader: EventReader<ContactForceEvent>) {
+///     for event in contact_force_event_reader.read() {
+///         println!(
+///             "Entities {:?} and {:?} collided with a force of {}",
+///             event.entity1,
+///             event.entity2,
+///             event.total_force_magnitude,
+///         );
+///     }
+/// }
+/// ```
+#[derive(Event, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContactForceEvent {
+    /// The first collider entity in the contact.
+    pub entity1: Entity,
+    /// The second collider entity in the contact.
+    pub entity2: Entity,
+    /// The entity of the first body involved in the contact.
+    pub body_entity1: Option<Entity>,
+    /// The entity of the second body involved in the contact.
+    pub body_entity2: Option<Entity>,
+    /// The sum of the world-space contact forces applied to the second collider by the first collider.
+    pub total_force: Vector,
+    /// The sum of the magnitudes of the contact forces.
+    ///
+    /// This is the value compared against the [`ContactForceEventThreshold`].
+    pub total_force_magnitude: Scalar,
+    /// The world-space direction of the largest contact force applied to the second collider by the first collider.
+    pub max_force_direction: Vector,
+    /// The magnitude of the largest contact force.
+    pub max_force_magnitude: Scalar,
+}
+
+/// A component that enables [`ContactForceEvent`]s for a [collider](Collider).
+///
+/// A [`ContactForceEvent`] is sent when the sum of the magnitudes of the contact forces
+/// between this collider and another collider exceeds the threshold. The threshold is a force,
+/// so for example, a body with a mass of `1.0` resting on the ground under standard gravity
+/// applies a force of roughly `9.81`.
+///
+/// If both colliders have a threshold, the smaller threshold is used.
+#[derive(
+    Reflect, Clone, Copy, Component, Debug, Default, Deref, DerefMut, PartialEq, PartialOrd,
+)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, Default, PartialEq)]
+pub struct ContactForceEventThreshold(pub Scalar);
+
 /// Sends collision events and updates [`CollidingEntities`].
 pub fn report_contacts(
     mut colliders: Query<&mut CollidingEntities>,
@@ -188,3 +273,70 @@ pub fn report_contacts(
         }
     }
 }
+
+/// Sends [`ContactForceEvent`]s for contacts whose total force exceeds
+/// the [`ContactForceEventThreshold`] of either collider.
+pub fn report_contact_forces(
+    colliders: Query<(&Rotation, Option<&ContactForceEventThreshold>)>,
+    collisions: Res<Collisions>,
+    substep_time: Res<Time<Substeps>>,
+    mut contact_force_ev_writer: EventWriter<ContactForceEvent>,
+) {
+    let substep_delta_secs = substep_time.delta_seconds_f64() as Scalar;
+
+    if substep_delta_secs == 0.0 {
+        return;
+    }
+
+    for contacts in collisions.get_internal().values() {
+        if !contacts.during_current_frame || contacts.is_sensor {
+            continue;
+        }
+
+        let Ok([(rotation1, threshold1), (_, threshold2)]) =
+            colliders.get_many([contacts.entity1, contacts.entity2])
+        else {
+            continue;
+        };
+
+        // Use the smaller threshold if both colliders have one.
+        let threshold = match (threshold1, threshold2) {
+            (Some(threshold1), Some(threshold2)) => threshold1.0.min(threshold2.0),
+            (Some(threshold), None) | (None, Some(threshold)) => threshold.0,
+            (None, None) => continue,
+        };
+
+        let mut event = ContactForceEvent {
+            entity1: contacts.entity1,
+            entity2: contacts.entity2,
+            body_entity1: contacts.body_entity1,
+            body_entity2: contacts.body_entity2,
+            total_force: Vector::ZERO,
+            total_force_magnitude: 0.0,
+            max_force_direction: Vector::ZERO,
+            max_force_magnitude: 0.0,
+        };
+
+        // The contact impulses are applied over each substep, so the forces are computed
+        // by dividing the impulses by the substep delta time.
+        for manifold in contacts.manifolds.iter() {
+            let normal = manifold.global_normal1(rotation1);
+
+            for contact in manifold.contacts.iter() {
+                let force_magnitude = contact.normal_force(substep_delta_secs);
+
+                event.total_force += force_magnitude * normal;
+                event.total_force_magnitude += force_magnitude;
+
+                if force_magnitude > event.max_force_magnitude {
+                    event.max_force_direction = normal;
+                    event.max_force_magnitude = force_magnitude;
+                }
+            }
+        }
+
+        if event.total_force_magnitude > threshold {
+            contact_force_ev_writer.send(event);
+        }
+    }
+}
@@ -11,11 +11,14 @@
 //! - [`NarrowPhasePlugin`]: Computes [`Contacts`] for each pair in [`BroadCollisionPairs`], adding them to [`Collisions`].
 //! - [`ContactReportingPlugin`] (optional): Sends collision events and updates [`CollidingEntities`] based on [`Collisions`].
 //!
+//! Contacts can be modified before the solver uses them with [`ContactModificationHooks`].
+//!
 //! Spatial queries are handled separately by the [`SpatialQueryPlugin`].
 //!
 //! You can also find several utility methods for computing contacts in [`contact_query`].
 
 pub mod broad_phase;
+pub mod contact_modification;
 #[cfg(all(
     feature = "default-collider",
     any(feature = "parry-f32", feature = "parry-f64")
@@ -467,8 +470,12 @@ impl SingleContact {
 }
 
 /// Data related to a contact between two bodies.
+///
+/// New fields may be added to the contact data over time, so it can't be constructed
+/// with a struct expression outside of this crate. Use [`ContactData::new`] instead.
 #[derive(Clone, Copy, Debug, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct ContactData {
     /// Contact point on the first entity in local coordinates.
     pub point1: Vector,
@@ -502,6 +509,62 @@ pub struct ContactData {
     /// The contact feature ID on the first shape. This indicates the ID of
     /// the vertex, edge, or face of the contact, if one can be determined.
     pub feature_id2: PackedFeatureId,
+    /// If `false`, the contact point is ignored by the contact solver.
+    ///
+    /// This can be changed using [`ContactModificationHooks`].
+    ///
+    /// Default: `true`
+    #[cfg_attr(feature = "serialize", serde(default = "default_contact_enabled"))]
+    pub enabled: bool,
+    /// Overrides the combined coefficient of [`Friction`] used for the contact point.
+    ///
+    /// This can be changed using [`ContactModificationHooks`].
+    ///
+    /// Default: `None`
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub friction: Option<Scalar>,
+    /// Overrides the combined coefficient of [`Restitution`] used for the contact point.
+    ///
+    /// This can be changed using [`ContactModificationHooks`].
+    ///
+    /// Default: `None`
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub restitution: Option<Scalar>,
+    /// The target relative velocity of the surfaces along the contact tangent in world space.
+    ///
+    /// This is the velocity of the second entity's surface relative to the surface of the first entity,
+    /// which can be used for things like conveyor belts. The component along the contact normal is ignored.
+    ///
+    /// This can be changed using [`ContactModificationHooks`].
+    ///
+    /// Default: `Vector::ZERO`
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub tangent_velocity: Vector,
+    /// A scaling factor for the impulses applied along the contact normal.
+    ///
+    /// Values between `0.0` and `1.0` make the contact respond more weakly, and `0.0` disables
+    /// the response along the normal altogether. Because friction is bounded by the normal impulse,
+    /// it is scaled accordingly.
+    ///
+    /// This can be changed using [`ContactModificationHooks`].
+    ///
+    /// Default: `1.0`
+    #[cfg_attr(
+        feature = "serialize",
+        serde(default = "default_contact_normal_impulse_scale")
+    )]
+    pub normal_impulse_scale: Scalar,
+}
+
+// Defaults for contact data serialized before the fields were added, matching `ContactData::new`.
+#[cfg(feature = "serialize")]
+fn default_contact_enabled() -> bool {
+    true
+}
+
+#[cfg(feature = "serialize")]
+fn default_contact_normal_impulse_scale() -> Scalar {
+    1.0
 }
 
 impl ContactData {
@@ -526,6 +589,11 @@ impl ContactData {
             tangent_impulse: default(),
             feature_id1: PackedFeatureId::UNKNOWN,
             feature_id2: PackedFeatureId::UNKNOWN,
+            enabled: true,
+            friction: None,
+            restitution: None,
+            tangent_velocity: Vector::ZERO,
+            normal_impulse_scale: 1.0,
         }
     }
 
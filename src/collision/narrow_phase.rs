@@ -86,6 +86,7 @@ impl<C: AnyCollider> Plugin for NarrowPhasePlugin<C> {
                 NarrowPhaseSet::First,
                 NarrowPhaseSet::CollectCollisions,
                 NarrowPhaseSet::PostProcess,
+                NarrowPhaseSet::ModifyContacts,
                 NarrowPhaseSet::GenerateConstraints,
                 NarrowPhaseSet::Last,
             )
@@ -112,7 +113,9 @@ impl<C: AnyCollider> Plugin for NarrowPhasePlugin<C> {
                 self.schedule,
                 (
                     // Reset collision states.
+                    // This must run after bodies have been put to sleep for the current step.
                     reset_collision_states
+                        .in_set(PhysicsStepSet::NarrowPhase)
                         .after(NarrowPhaseSet::First)
                         .before(NarrowPhaseSet::CollectCollisions),
                     // Remove ended collisions after contact reporting
@@ -249,6 +252,9 @@ pub enum NarrowPhaseSet {
     /// If you want to modify or remove collisions after [`NarrowPhaseSet::CollectCollisions`], you can
     /// add custom systems to this set, or to [`PostProcessCollisions`].
     PostProcess,
+    /// Runs [`ContactModificationHooks`] added with a [`ContactModificationPlugin`] to modify
+    /// the properties of individual contact points before constraints are generated.
+    ModifyContacts,
     /// Generates [`ContactConstraint`]s and adds them to [`ContactConstraints`].
     GenerateConstraints,
     /// Runs at the end of the narrow phase. Empty by default.
@@ -271,8 +277,10 @@ fn generate_constraints<C: AnyCollider>(
     mut constraints: ResMut<ContactConstraints>,
     contact_softness: Res<ContactSoftnessCoefficients>,
     time: Res<Time>,
+    substep_time: Res<Time<Substeps>>,
 ) {
     let delta_secs = time.delta_seconds_adjusted();
+    let substep_delta_secs = substep_time.delta_seconds_f64() as Scalar;
 
     // TODO: Parallelize.
     for contacts in narrow_phase.collisions.get_internal().values() {
@@ -289,9 +297,16 @@ fn generate_constraints<C: AnyCollider>(
         let body2_bundle = collider2
             .parent
             .and_then(|p| narrow_phase.body_query.get(p.get()).ok());
-        if let (Some((body1, rb_collision_margin1)), Some((body2, rb_collision_margin2))) = (
-            body1_bundle.map(|(body, rb_collision_margin1, _)| (body, rb_collision_margin1)),
-            body2_bundle.map(|(body, rb_collision_margin2, _)| (body, rb_collision_margin2)),
+        if let (
+            Some((body1, rb_collision_margin1, rb_contact_softness1)),
+            Some((body2, rb_collision_margin2, rb_contact_softness2)),
+        ) = (
+            body1_bundle.map(|(body, collision_margin, _, contact_softness)| {
+                (body, collision_margin, contact_softness)
+            }),
+            body2_bundle.map(|(body, collision_margin, _, contact_softness)| {
+                (body, collision_margin, contact_softness)
+            }),
         ) {
             // At least one of the bodies must be dynamic for contact constraints
             // to be generated.
@@ -314,6 +329,24 @@ fn generate_constraints<C: AnyCollider>(
                 .map_or(0.0, |margin| margin.0);
             let collision_margin_sum = collision_margin1 + collision_margin2;
 
+            // Use the collider's own contact softness if specified, and fall back to the body's
+            // contact softness. If neither collider has a contact softness, the global softness is used.
+            let contact_softness1 = collider1.contact_softness.or(rb_contact_softness1);
+            let contact_softness2 = collider2.contact_softness.or(rb_contact_softness2);
+            let contact_softness = match (contact_softness1, contact_softness2) {
+                (Some(softness1), Some(softness2)) => Some(softness1.combine(*softness2)),
+                (softness1, softness2) => softness1.or(softness2).copied(),
+            }
+            .map_or(*contact_softness, |softness| {
+                let coefficients = softness
+                    .parameters()
+                    .compute_coefficients(substep_delta_secs);
+                ContactSoftnessCoefficients {
+                    dynamic: coefficients,
+                    non_dynamic: coefficients,
+                }
+            });
+
             // Generate contact constraints for the computed contacts
             // and add them to `constraints`.
             narrow_phase.generate_constraints(
@@ -324,7 +357,7 @@ fn generate_constraints<C: AnyCollider>(
                 &collider1,
                 &collider2,
                 collision_margin_sum,
-                *contact_softness,
+                contact_softness,
                 delta_secs,
             );
         }
@@ -347,6 +380,7 @@ pub struct NarrowPhase<'w, 's, C: AnyCollider> {
             RigidBodyQueryReadOnly,
             Option<&'static CollisionMargin>,
             Option<&'static SpeculativeMargin>,
+            Option<&'static ContactSoftness>,
         ),
     >,
     /// Contacts found by the narrow phase.
@@ -435,7 +469,7 @@ impl<'w, 's, C: AnyCollider> NarrowPhase<'w, 's, C> {
         // if the collider doesn't have them specified.
         let (mut lin_vel1, rb_collision_margin1, rb_speculative_margin1) = body1_bundle
             .as_ref()
-            .map(|(body, collision_margin, speculative_margin)| {
+            .map(|(body, collision_margin, speculative_margin, _)| {
                 (
                     body.linear_velocity.0,
                     *collision_margin,
@@ -445,7 +479,7 @@ impl<'w, 's, C: AnyCollider> NarrowPhase<'w, 's, C> {
             .unwrap_or_default();
         let (mut lin_vel2, rb_collision_margin2, rb_speculative_margin2) = body2_bundle
             .as_ref()
-            .map(|(body, collision_margin, speculative_margin)| {
+            .map(|(body, collision_margin, speculative_margin, _)| {
                 (
                     body.linear_velocity.0,
                     *collision_margin,
@@ -643,15 +677,28 @@ impl<'w, 's, C: AnyCollider> NarrowPhase<'w, 's, C> {
 
         // Get combined friction and restitution coefficients of the colliders
         // or the bodies they are attached to.
-        let friction = collider1
-            .friction
-            .unwrap_or(body1.friction)
-            .combine(*collider2.friction.unwrap_or(body2.friction));
+        let friction1 = *collider1.friction.unwrap_or(body1.friction);
+        let friction2 = *collider2.friction.unwrap_or(body2.friction);
+        let mut friction = friction1.combine(friction2);
         let restitution = collider1
             .restitution
             .unwrap_or(body1.restitution)
             .combine(*collider2.restitution.unwrap_or(body2.restitution));
 
+        // If either collider has anisotropic friction, combine its primary and secondary coefficients
+        // with the friction of the other collider. The primary direction is transformed to world space.
+        let anisotropic_friction = if let Some(anisotropic) = collider1.anisotropic_friction {
+            friction = anisotropic.primary_friction(friction1).combine(friction2);
+            let secondary = anisotropic.secondary_friction(friction1).combine(friction2);
+            Some((*collider1.rotation * anisotropic.direction, secondary))
+        } else if let Some(anisotropic) = collider2.anisotropic_friction {
+            friction = friction1.combine(anisotropic.primary_friction(friction2));
+            let secondary = friction1.combine(anisotropic.secondary_friction(friction2));
+            Some((*collider2.rotation * anisotropic.direction, secondary))
+        } else {
+            None
+        };
+
         let contact_softness = if !body1.rb.is_dynamic() || !body2.rb.is_dynamic() {
             contact_softness.non_dynamic
         } else {
@@ -673,7 +720,10 @@ impl<'w, 's, C: AnyCollider> NarrowPhase<'w, 's, C> {
                 // TODO: Shouldn't this be the effective speculative margin?
                 *self.default_speculative_margin,
                 friction,
+                anisotropic_friction,
                 restitution,
+                collider1.surface_velocity.copied(),
+                collider2.surface_velocity.copied(),
                 contact_softness,
                 self.config.match_contacts,
                 delta_secs,
@@ -126,6 +126,9 @@ impl Plugin for PhysicsDebugPlugin {
                     debug_render_joints::<PrismaticJoint>,
                     debug_render_joints::<DistanceJoint>,
                     debug_render_joints::<RevoluteJoint>,
+                    debug_render_joints::<GenericJoint>,
+                    debug_render_joints::<PulleyJoint>,
+                    debug_render_joints::<WheelJoint>,
                     #[cfg(feature = "3d")]
                     debug_render_joints::<SphericalJoint>,
                     debug_render_raycasts,
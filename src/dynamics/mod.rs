@@ -73,8 +73,16 @@ pub mod prelude {
         ccd::{CcdPlugin, SpeculativeMargin, SweepMode, SweptCcd},
         integrator::{Gravity, IntegratorPlugin},
         rigid_body::*,
-        sleeping::{DeactivationTime, SleepingPlugin, SleepingThreshold},
-        solver::{joints::*, PhysicsLengthUnit, SolverPlugin, SolverSet},
+        sleeping::{
+            islands::{Island, IslandId, PhysicsIslands},
+            DeactivationTime, SleepingPlugin, SleepingThreshold,
+        },
+        solver::{
+            articulations::{ArticulationLink, LinkJointType},
+            joints::*,
+            softness_parameters::SoftnessParameters,
+            PhysicsLengthUnit, SolverPlugin, SolverSet,
+        },
     };
 }
 
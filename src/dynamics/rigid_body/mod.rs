@@ -4,11 +4,13 @@
 mod forces;
 mod locked_axes;
 mod mass_properties;
+mod physics_material;
 mod world_query;
 
 pub use forces::{ExternalAngularImpulse, ExternalForce, ExternalImpulse, ExternalTorque};
 pub use locked_axes::LockedAxes;
 pub use mass_properties::*;
+pub use physics_material::*;
 pub use world_query::*;
 
 #[cfg(feature = "2d")]
@@ -269,10 +271,11 @@ impl RigidBody {
 /// Indicates that a [rigid body](RigidBody) is not simulated by the physics engine until woken up again.
 /// This is done to improve performance and to help prevent small jitter that is typically present in collisions.
 ///
-/// Bodies are marked as sleeping when their linear and angular velocity is below the [`SleepingThreshold`] for a time
-/// indicated by [`DeactivationTime`]. A sleeping body is woken up when an active body interacts with it through
-/// collisions or other constraints, or when gravity changes, or when the body's
-/// position, rotation, velocity, or external forces are modified.
+/// Bodies are marked as sleeping when the linear and angular velocities of all bodies in their [island](PhysicsIslands)
+/// are below the [`SleepingThreshold`] for a time indicated by [`DeactivationTime`]. A sleeping body is woken up
+/// when an active body interacts with it through collisions or other constraints, or when gravity changes,
+/// or when the body's position, rotation, velocity, or external forces are modified.
+/// Waking up a body also wakes up the other bodies in its island.
 ///
 /// Sleeping can be disabled for specific entities with the [`SleepingDisabled`] component,
 /// or for all entities by setting the [`SleepingThreshold`] to a negative value.
@@ -506,6 +509,7 @@ pub enum CoefficientCombine {
 #[doc(alias = "Elasticity")]
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(default))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 #[reflect(Debug, Component, PartialEq)]
 pub struct Restitution {
@@ -619,6 +623,14 @@ impl From<Scalar> for Restitution {
 /// Friction::new(0.4).with_static_coefficient(0.6)
 /// ```
 ///
+/// Add rolling and spinning friction to slow down rolling and spinning bodies like balls and spinning tops:
+///
+/// ```ignore
+/// Friction::new(0.4)
+///     .with_rolling_coefficient(0.02)
+///     .with_spinning_coefficient(0.05)
+/// ```
+///
 /// Configure how the friction coefficients of two [`Friction`] components are combined with [`CoefficientCombine`]:
 ///
 /// ```ignore
@@ -642,6 +654,7 @@ impl From<Scalar> for Restitution {
 /// ```
 #[derive(Reflect, Clone, Copy, Component, Debug, PartialEq, PartialOrd)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(default))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 #[reflect(Debug, Component, PartialEq)]
 pub struct Friction {
@@ -649,6 +662,26 @@ pub struct Friction {
     pub dynamic_coefficient: Scalar,
     /// Coefficient of static friction.
     pub static_coefficient: Scalar,
+    /// Coefficient of rolling friction, also known as rolling resistance.
+    ///
+    /// Rolling friction resists the relative rotation of the bodies around axes
+    /// perpendicular to the contact normal, which makes rolling bodies like balls and wheels slow down.
+    /// The maximum rolling resistance torque is the coefficient multiplied by the normal force,
+    /// so the coefficient has the unit of length.
+    ///
+    /// Default: `0.0`
+    pub rolling_coefficient: Scalar,
+    /// Coefficient of spinning friction, also known as torsional friction.
+    ///
+    /// Spinning friction resists the relative rotation of the bodies around the contact normal,
+    /// which makes spinning bodies like tops and coins slow down.
+    /// The maximum spinning friction torque is the coefficient multiplied by the normal force,
+    /// so the coefficient has the unit of length.
+    ///
+    /// Spinning friction only exists in 3D, because in 2D, bodies can not rotate around the contact normal.
+    ///
+    /// Default: `0.0`
+    pub spinning_coefficient: Scalar,
     /// The coefficient combine rule used when two bodies collide.
     pub combine_rule: CoefficientCombine,
 }
@@ -658,6 +691,8 @@ impl Friction {
     pub const ZERO: Self = Self {
         dynamic_coefficient: 0.0,
         static_coefficient: 0.0,
+        rolling_coefficient: 0.0,
+        spinning_coefficient: 0.0,
         combine_rule: CoefficientCombine::Average,
     };
 
@@ -694,12 +729,30 @@ impl Friction {
         }
     }
 
+    /// Sets the coefficient of rolling friction.
+    pub fn with_rolling_coefficient(&self, coefficient: Scalar) -> Self {
+        Self {
+            rolling_coefficient: coefficient,
+            ..*self
+        }
+    }
+
+    /// Sets the coefficient of spinning friction.
+    pub fn with_spinning_coefficient(&self, coefficient: Scalar) -> Self {
+        Self {
+            spinning_coefficient: coefficient,
+            ..*self
+        }
+    }
+
     /// Combines the properties of two `Friction` components.
     pub fn combine(&self, other: Self) -> Self {
         // Choose rule with higher priority
         let rule = self.combine_rule.max(other.combine_rule);
         let (dynamic1, dynamic2) = (self.dynamic_coefficient, other.dynamic_coefficient);
         let (static1, static2) = (self.static_coefficient, other.static_coefficient);
+        let (rolling1, rolling2) = (self.rolling_coefficient, other.rolling_coefficient);
+        let (spinning1, spinning2) = (self.spinning_coefficient, other.spinning_coefficient);
 
         Self {
             dynamic_coefficient: match rule {
@@ -714,6 +767,18 @@ impl Friction {
                 CoefficientCombine::Multiply => static1 * static2,
                 CoefficientCombine::Max => static1.max(static2),
             },
+            rolling_coefficient: match rule {
+                CoefficientCombine::Average => (rolling1 + rolling2) * 0.5,
+                CoefficientCombine::Min => rolling1.min(rolling2),
+                CoefficientCombine::Multiply => rolling1 * rolling2,
+                CoefficientCombine::Max => rolling1.max(rolling2),
+            },
+            spinning_coefficient: match rule {
+                CoefficientCombine::Average => (spinning1 + spinning2) * 0.5,
+                CoefficientCombine::Min => spinning1.min(spinning2),
+                CoefficientCombine::Multiply => spinning1 * spinning2,
+                CoefficientCombine::Max => spinning1.max(spinning2),
+            },
             combine_rule: rule,
         }
     }
@@ -724,6 +789,8 @@ impl Default for Friction {
         Self {
             dynamic_coefficient: 0.3,
             static_coefficient: 0.3,
+            rolling_coefficient: 0.0,
+            spinning_coefficient: 0.0,
             combine_rule: CoefficientCombine::default(),
         }
     }
@@ -739,6 +806,99 @@ impl From<Scalar> for Friction {
     }
 }
 
+/// Makes the dynamic [`Friction`] of a [collider](Collider) depend on the direction of sliding.
+///
+/// The `primary_coefficient` is used for sliding along the local `direction` of the collider,
+/// and the `secondary_coefficient` is used for sliding perpendicular to it. For other directions,
+/// the coefficients are interpolated elliptically.
+/// This can be used for things like ice skates, skis, tank treads and wheels.
+///
+/// The coefficients are combined with the [`Friction`] of the other collider using
+/// the [`CoefficientCombine`] rule of the [`Friction`] of this collider.
+/// If both colliders have anisotropic friction, only the anisotropic friction of the first collider is used.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     // A ski that slides easily forward, but not sideways.
+///     commands.spawn((
+///         RigidBody::Dynamic,
+#[cfg_attr(feature = "2d", doc = "        Collider::rectangle(0.1, 2.0),")]
+#[cfg_attr(feature = "3d", doc = "        Collider::cuboid(0.1, 0.05, 2.0),")]
+#[cfg_attr(
+    feature = "2d",
+    doc = "        AnisotropicFriction::new(Vec2::Y, 0.05, 0.8),"
+)]
+#[cfg_attr(
+    feature = "3d",
+    doc = "        AnisotropicFriction::new(Vec3::Z, 0.05, 0.8),"
+)]
+///     ));
+/// }
+/// ```
+#[derive(Reflect, Clone, Copy, Component, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, PartialEq)]
+pub struct AnisotropicFriction {
+    /// The local direction of the primary friction coefficient. Should be normalized.
+    pub direction: Vector,
+    /// The coefficient of friction for sliding along the `direction`.
+    pub primary_coefficient: Scalar,
+    /// The coefficient of friction for sliding perpendicular to the `direction`.
+    pub secondary_coefficient: Scalar,
+}
+
+impl AnisotropicFriction {
+    /// Creates a new [`AnisotropicFriction`] component with the given local direction
+    /// and the coefficients of friction along and perpendicular to it.
+    pub fn new(
+        direction: Vector,
+        primary_coefficient: Scalar,
+        secondary_coefficient: Scalar,
+    ) -> Self {
+        Self {
+            direction: direction.normalize_or_zero(),
+            primary_coefficient,
+            secondary_coefficient,
+        }
+    }
+
+    /// Returns the [`Friction`] along the primary direction, using the [`CoefficientCombine`] rule of the given `friction`.
+    pub fn primary_friction(&self, friction: Friction) -> Friction {
+        friction
+            .with_dynamic_coefficient(self.primary_coefficient)
+            .with_static_coefficient(self.primary_coefficient)
+    }
+
+    /// Returns the [`Friction`] along the secondary direction, using the [`CoefficientCombine`] rule of the given `friction`.
+    pub fn secondary_friction(&self, friction: Friction) -> Friction {
+        friction
+            .with_dynamic_coefficient(self.secondary_coefficient)
+            .with_static_coefficient(self.secondary_coefficient)
+    }
+
+    /// Computes the coefficient of friction along a direction, given the cosine of the angle
+    /// between the direction and the primary direction.
+    ///
+    /// The coefficients are interpolated elliptically, so the result is the `primary` coefficient
+    /// along the primary direction and the `secondary` coefficient perpendicular to it.
+    pub fn interpolate_coefficient(
+        primary: Scalar,
+        secondary: Scalar,
+        cos_angle: Scalar,
+    ) -> Scalar {
+        let cos_squared = (cos_angle * cos_angle).min(1.0);
+        let sin_squared = 1.0 - cos_squared;
+        (primary * primary * cos_squared + secondary * secondary * sin_squared).sqrt()
+    }
+}
+
 /// Automatically slows down a dynamic [rigid body](RigidBody), decreasing its
 /// [linear velocity](LinearVelocity) each frame. This can be used to simulate air resistance.
 ///
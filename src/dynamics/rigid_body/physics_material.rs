@@ -0,0 +1,336 @@
+//! [`PhysicsMaterial`] assets that bundle the [`Friction`], [`Restitution`] and [`ColliderDensity`]
+//! of a surface, like ice, rubber or wood.
+//!
+//! See [`PhysicsMaterialPlugin`].
+
+use crate::{prelude::*, prepare::PrepareSet};
+use bevy::{
+    ecs::{intern::Interned, schedule::ScheduleLabel},
+    prelude::*,
+    utils::HashSet,
+};
+
+/// A plugin for [`PhysicsMaterial`] assets. Colliders and rigid bodies can reference a material
+/// through a `Handle<PhysicsMaterial>` component, and the plugin keeps their [`Friction`], [`Restitution`]
+/// and [`ColliderDensity`] in sync with the material, also when the asset is modified or reloaded.
+///
+/// With the `serialize` feature, the plugin also registers a [`PhysicsMaterialLoader`]
+/// for loading materials from RON files with the `.physics_material.ron` extension.
+///
+/// The plugin requires Bevy's `AssetPlugin`. Otherwise, the plugin does nothing.
+///
+/// The systems run in [`PrepareSet::PreInit`].
+pub struct PhysicsMaterialPlugin {
+    schedule: Interned<dyn ScheduleLabel>,
+}
+
+impl PhysicsMaterialPlugin {
+    /// Creates a [`PhysicsMaterialPlugin`] with the schedule that is used for running the [`PhysicsSchedule`].
+    ///
+    /// The default schedule is `PostUpdate`.
+    pub fn new(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for PhysicsMaterialPlugin {
+    fn default() -> Self {
+        Self::new(PostUpdate)
+    }
+}
+
+impl Plugin for PhysicsMaterialPlugin {
+    fn build(&self, _app: &mut App) {}
+
+    // The asset is registered here instead of in `build`,
+    // so that the `AssetPlugin` can also be added after this plugin.
+    fn finish(&self, app: &mut App) {
+        if !app.is_plugin_added::<AssetPlugin>() {
+            warn!("`PhysicsMaterialPlugin` requires Bevy's `AssetPlugin` to function. If you don't need physics material assets, consider disabling this plugin.");
+            return;
+        }
+
+        app.init_asset::<PhysicsMaterial>()
+            .register_asset_reflect::<PhysicsMaterial>();
+
+        #[cfg(feature = "serialize")]
+        app.init_asset_loader::<PhysicsMaterialLoader>();
+
+        app.add_systems(
+            self.schedule,
+            update_physics_materials.in_set(PrepareSet::PreInit),
+        );
+    }
+}
+
+/// An asset that bundles the [`Friction`], [`Restitution`] and [`ColliderDensity`] of a surface,
+/// along with their [`CoefficientCombine`] rules.
+///
+/// Materials can be defined once and shared by many colliders and rigid bodies
+/// by adding a `Handle<PhysicsMaterial>` component to them. The [`PhysicsMaterialPlugin`]
+/// inserts the components of the material for entities with a handle, overwriting any existing values.
+/// The components are updated whenever the handle is changed or the asset is modified or reloaded.
+///
+/// ## Example
+///
+/// ```no_run
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands, mut materials: ResMut<Assets<PhysicsMaterial>>) {
+///     let ice = materials.add(
+///         PhysicsMaterial::default()
+///             .with_friction(Friction::new(0.05).with_combine_rule(CoefficientCombine::Min))
+///             .with_restitution(Restitution::ZERO)
+///             .with_density(0.9),
+///     );
+///
+///     commands.spawn((
+///         RigidBody::Dynamic,
+#[cfg_attr(feature = "2d", doc = "        Collider::rectangle(1.0, 1.0),")]
+#[cfg_attr(feature = "3d", doc = "        Collider::cuboid(1.0, 1.0, 1.0),")]
+///         ice,
+///     ));
+/// }
+/// ```
+///
+/// ## Loading materials from files
+///
+/// With the `serialize` feature, materials can be loaded from RON files with the `.physics_material.ron`
+/// extension using the `AssetServer`. Changes to the files are applied to the entities using the material
+/// when the asset is reloaded, for example with Bevy's `file_watcher` feature.
+///
+/// All fields are optional, and missing fields use their default values.
+///
+/// ```ron
+/// // assets/materials/rubber.physics_material.ron
+/// (
+///     friction: (
+///         dynamic_coefficient: 0.8,
+///         static_coefficient: 1.0,
+///         combine_rule: Max,
+///     ),
+///     restitution: (
+///         coefficient: 0.8,
+///         combine_rule: Max,
+///     ),
+///     density: 1.1,
+/// )
+/// ```
+///
+/// ```no_run
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands, assets: Res<AssetServer>) {
+///     let rubber: Handle<PhysicsMaterial> = assets.load("materials/rubber.physics_material.ron");
+///
+///     commands.spawn((
+///         RigidBody::Dynamic,
+#[cfg_attr(feature = "2d", doc = "        Collider::circle(0.5),")]
+#[cfg_attr(feature = "3d", doc = "        Collider::sphere(0.5),")]
+///         rubber,
+///     ));
+/// }
+/// ```
+#[derive(Asset, Reflect, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", serde(default))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Default, PartialEq)]
+pub struct PhysicsMaterial {
+    /// The friction of the material.
+    pub friction: Friction,
+    /// The restitution of the material.
+    pub restitution: Restitution,
+    /// The density of the material, used as the [`ColliderDensity`].
+    pub density: Scalar,
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self {
+            friction: Friction::default(),
+            restitution: Restitution::default(),
+            density: ColliderDensity::default().0,
+        }
+    }
+}
+
+impl PhysicsMaterial {
+    /// Creates a new [`PhysicsMaterial`] with the given friction, restitution and density.
+    pub fn new(friction: Friction, restitution: Restitution, density: Scalar) -> Self {
+        Self {
+            friction,
+            restitution,
+            density,
+        }
+    }
+
+    /// Sets the [`Friction`] of the material.
+    pub fn with_friction(&self, friction: Friction) -> Self {
+        Self { friction, ..*self }
+    }
+
+    /// Sets the [`Restitution`] of the material.
+    pub fn with_restitution(&self, restitution: Restitution) -> Self {
+        Self {
+            restitution,
+            ..*self
+        }
+    }
+
+    /// Sets the density of the material.
+    pub fn with_density(&self, density: Scalar) -> Self {
+        Self { density, ..*self }
+    }
+}
+
+/// Inserts the [`Friction`], [`Restitution`] and [`ColliderDensity`] of [`PhysicsMaterial`]s
+/// for entities whose material handle has changed, or whose material has been loaded or modified.
+fn update_physics_materials(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<PhysicsMaterial>>,
+    materials: Res<Assets<PhysicsMaterial>>,
+    changed_handles: Query<(Entity, &Handle<PhysicsMaterial>), Changed<Handle<PhysicsMaterial>>>,
+    handles: Query<(Entity, &Handle<PhysicsMaterial>)>,
+) {
+    let updated_materials: HashSet<AssetId<PhysicsMaterial>> = asset_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    let mut insert_material = |entity: Entity, handle: &Handle<PhysicsMaterial>| {
+        if let Some(material) = materials.get(handle) {
+            commands.entity(entity).try_insert((
+                material.friction,
+                material.restitution,
+                ColliderDensity(material.density),
+            ));
+        }
+    };
+
+    if updated_materials.is_empty() {
+        for (entity, handle) in &changed_handles {
+            insert_material(entity, handle);
+        }
+    } else {
+        for (entity, handle) in &handles {
+            if updated_materials.contains(&handle.id()) || changed_handles.contains(entity) {
+                insert_material(entity, handle);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+pub use loader::*;
+
+#[cfg(feature = "serialize")]
+mod loader {
+    use super::PhysicsMaterial;
+    use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+
+    /// An [`AssetLoader`] for [`PhysicsMaterial`] assets stored as RON files
+    /// with the `.physics_material.ron` extension.
+    ///
+    /// See [`PhysicsMaterial`] for an example of the file format.
+    #[derive(Default)]
+    pub struct PhysicsMaterialLoader;
+
+    /// An error that can occur when loading a [`PhysicsMaterial`] with the [`PhysicsMaterialLoader`].
+    #[derive(Debug)]
+    pub enum PhysicsMaterialLoaderError {
+        /// The file could not be read.
+        Io(std::io::Error),
+        /// The file is not a valid RON representation of a [`PhysicsMaterial`].
+        Ron(ron::error::SpannedError),
+    }
+
+    impl std::fmt::Display for PhysicsMaterialLoaderError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Self::Io(error) => write!(f, "could not read physics material: {error}"),
+                Self::Ron(error) => write!(f, "could not parse physics material: {error}"),
+            }
+        }
+    }
+
+    impl std::error::Error for PhysicsMaterialLoaderError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                Self::Io(error) => Some(error),
+                Self::Ron(error) => Some(error),
+            }
+        }
+    }
+
+    impl From<std::io::Error> for PhysicsMaterialLoaderError {
+        fn from(error: std::io::Error) -> Self {
+            Self::Io(error)
+        }
+    }
+
+    impl From<ron::error::SpannedError> for PhysicsMaterialLoaderError {
+        fn from(error: ron::error::SpannedError) -> Self {
+            Self::Ron(error)
+        }
+    }
+
+    impl AssetLoader for PhysicsMaterialLoader {
+        type Asset = PhysicsMaterial;
+        type Settings = ();
+        type Error = PhysicsMaterialLoaderError;
+
+        async fn load<'a>(
+            &'a self,
+            reader: &'a mut Reader<'_>,
+            _settings: &'a (),
+            _load_context: &'a mut LoadContext<'_>,
+        ) -> Result<PhysicsMaterial, PhysicsMaterialLoaderError> {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes(&bytes)?)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["physics_material.ron"]
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use crate::prelude::*;
+
+        #[test]
+        fn physics_material_is_parsed_from_ron() {
+            let material: PhysicsMaterial = ron::de::from_str(
+                "(
+                    friction: (dynamic_coefficient: 0.05, static_coefficient: 0.1, combine_rule: Min),
+                    density: 0.9,
+                )",
+            )
+            .unwrap();
+
+            assert_eq!(
+                material,
+                PhysicsMaterial::default()
+                    .with_friction(
+                        Friction::new(0.05)
+                            .with_static_coefficient(0.1)
+                            .with_combine_rule(CoefficientCombine::Min)
+                    )
+                    .with_density(0.9)
+            );
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! [`PhysicsIslands`] resource for grouping interacting bodies into simulation islands.
+
+use crate::prelude::*;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::collections::VecDeque;
+
+/// A unique identifier for an [`Island`] in the [`PhysicsIslands`] resource.
+///
+/// The identifier of an island stays the same when other islands are merged into it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IslandId(pub u32);
+
+/// A simulation island, a set of [dynamic](RigidBody::Dynamic) bodies that are connected to each other
+/// through contacts or [joints](dynamics::solver::joints), directly or through other bodies in the island.
+///
+/// Islands are stored in the [`PhysicsIslands`] resource.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Island {
+    pub(crate) bodies: Vec<Entity>,
+    pub(crate) is_sleeping: bool,
+    /// `true` if a contact or joint between bodies in the island has been removed,
+    /// which means that the island might need to be split.
+    pub(crate) constraint_removed: bool,
+}
+
+impl Island {
+    /// Returns the bodies in the island.
+    pub fn bodies(&self) -> &[Entity] {
+        &self.bodies
+    }
+
+    /// Returns `true` if the bodies in the island are [`Sleeping`].
+    pub fn is_sleeping(&self) -> bool {
+        self.is_sleeping
+    }
+}
+
+/// Persistent simulation islands built from the contact graph and [joints](dynamics::solver::joints).
+///
+/// Every [dynamic](RigidBody::Dynamic) body belongs to exactly one [`Island`]. When two bodies start touching
+/// or are connected by a joint, their islands are merged. Static and kinematic bodies do not belong to islands
+/// and don't connect the islands of the bodies touching them, so for example all bodies resting on the ground
+/// are not in the same island.
+///
+/// An island is only put to sleep when all of its bodies have been below the [`SleepingThreshold`]
+/// for the [`DeactivationTime`], and waking up any body in a sleeping island wakes up the whole island.
+/// This prevents bodies in a stack from falling asleep while another body in the stack is still moving.
+///
+/// When contacts or joints between bodies in an island are removed, the island is split
+/// into separate islands once some of its bodies are ready to sleep.
+///
+/// The islands are updated by the [`SleepingPlugin`] in [`PhysicsStepSet::Sleeping`], after contacts have been computed.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn print_islands(islands: Res<PhysicsIslands>) {
+///     for (id, island) in islands.iter() {
+///         info!(
+///             "Island {:?} has {} bodies and is sleeping: {}",
+///             id,
+///             island.bodies().len(),
+///             island.is_sleeping(),
+///         );
+///     }
+/// }
+/// ```
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PhysicsIslands {
+    pub(crate) islands: HashMap<IslandId, Island>,
+    body_islands: HashMap<Entity, IslandId>,
+    /// The pairs of bodies connected by contacts or joints during the previous update.
+    edges: HashSet<(Entity, Entity)>,
+    next_id: u32,
+}
+
+impl PhysicsIslands {
+    /// Returns the island with the given [`IslandId`], or `None` if it does not exist.
+    pub fn get(&self, id: IslandId) -> Option<&Island> {
+        self.islands.get(&id)
+    }
+
+    /// Returns the [`IslandId`] of the island that the given body belongs to,
+    /// or `None` if the body is not a dynamic body.
+    pub fn island_id(&self, body: Entity) -> Option<IslandId> {
+        self.body_islands.get(&body).copied()
+    }
+
+    /// Returns the island that the given body belongs to,
+    /// or `None` if the body is not a dynamic body.
+    pub fn island_of(&self, body: Entity) -> Option<&Island> {
+        self.island_id(body).and_then(|id| self.get(id))
+    }
+
+    /// Returns `true` if the given bodies belong to the same island.
+    pub fn in_same_island(&self, body1: Entity, body2: Entity) -> bool {
+        self.island_id(body1)
+            .is_some_and(|id| self.island_id(body2) == Some(id))
+    }
+
+    /// Returns an iterator over all islands and their identifiers.
+    pub fn iter(&self) -> impl Iterator<Item = (IslandId, &Island)> {
+        self.islands.iter().map(|(id, island)| (*id, island))
+    }
+
+    /// Returns the number of islands.
+    pub fn len(&self) -> usize {
+        self.islands.len()
+    }
+
+    /// Returns `true` if there are no islands.
+    pub fn is_empty(&self) -> bool {
+        self.islands.is_empty()
+    }
+
+    /// Creates a new island containing the given bodies and returns its identifier.
+    fn create_island(&mut self, bodies: Vec<Entity>, is_sleeping: bool) -> IslandId {
+        let id = IslandId(self.next_id);
+        self.next_id = self.next_id.wrapping_add(1);
+
+        for &body in &bodies {
+            self.body_islands.insert(body, id);
+        }
+        self.islands.insert(
+            id,
+            Island {
+                bodies,
+                is_sleeping,
+                constraint_removed: false,
+            },
+        );
+
+        id
+    }
+
+    /// Removes the given body from its island, removing the island if it becomes empty.
+    fn remove_body(&mut self, body: Entity) {
+        let Some(id) = self.body_islands.remove(&body) else {
+            return;
+        };
+        let Some(island) = self.islands.get_mut(&id) else {
+            return;
+        };
+
+        island.bodies.retain(|&entity| entity != body);
+        island.constraint_removed = true;
+
+        if island.bodies.is_empty() {
+            self.islands.remove(&id);
+        }
+    }
+
+    /// Merges the islands of the given bodies. The larger island absorbs the smaller one.
+    fn link(&mut self, body1: Entity, body2: Entity) {
+        let (Some(mut id1), Some(mut id2)) = (self.island_id(body1), self.island_id(body2)) else {
+            return;
+        };
+
+        if id1 == id2 {
+            return;
+        }
+
+        if self.islands[&id1].bodies.len() < self.islands[&id2].bodies.len() {
+            std::mem::swap(&mut id1, &mut id2);
+        }
+
+        let Some(absorbed) = self.islands.remove(&id2) else {
+            return;
+        };
+        for &body in &absorbed.bodies {
+            self.body_islands.insert(body, id1);
+        }
+
+        let island = self.islands.get_mut(&id1).unwrap();
+        island.bodies.extend(absorbed.bodies);
+        island.is_sleeping &= absorbed.is_sleeping;
+        island.constraint_removed |= absorbed.constraint_removed;
+    }
+
+    /// Splits the given island into islands of bodies that are still connected to each other.
+    ///
+    /// The first connected set of bodies keeps the identifier of the original island.
+    pub(crate) fn split_island(&mut self, id: IslandId) {
+        let Some(island) = self.islands.get_mut(&id) else {
+            return;
+        };
+        island.constraint_removed = false;
+
+        let bodies: HashSet<Entity> = island.bodies.iter().copied().collect();
+        let mut adjacency: HashMap<Entity, Vec<Entity>> = HashMap::default();
+        for &(body1, body2) in &self.edges {
+            if bodies.contains(&body1) && bodies.contains(&body2) {
+                adjacency.entry(body1).or_default().push(body2);
+                adjacency.entry(body2).or_default().push(body1);
+            }
+        }
+
+        let mut visited = HashSet::with_capacity(bodies.len());
+        let mut components = Vec::new();
+
+        for &root in &island.bodies {
+            if !visited.insert(root) {
+                continue;
+            }
+
+            let mut component = vec![];
+            let mut queue = VecDeque::from([root]);
+            while let Some(body) = queue.pop_front() {
+                component.push(body);
+                for &other in adjacency.get(&body).into_iter().flatten() {
+                    if visited.insert(other) {
+                        queue.push_back(other);
+                    }
+                }
+            }
+            components.push(component);
+        }
+
+        if components.len() <= 1 {
+            return;
+        }
+
+        let is_sleeping = island.is_sleeping;
+        let mut components = components.into_iter();
+        island.bodies = components.next().unwrap();
+
+        for component in components {
+            self.create_island(component, is_sleeping);
+        }
+    }
+}
+
+/// Updates the [`PhysicsIslands`] using the current contacts and the [`JointGraph`].
+///
+/// New dynamic bodies are added to their own islands, and islands are merged
+/// when bodies in them start touching or are connected by a joint.
+pub fn update_islands(
+    mut islands: ResMut<PhysicsIslands>,
+    bodies: Query<(Entity, &RigidBody, Has<Sleeping>)>,
+    collisions: Res<Collisions>,
+    joint_graph: Res<JointGraph>,
+    mut edges: Local<HashSet<(Entity, Entity)>>,
+) {
+    let islands = &mut *islands;
+
+    // Remove bodies that have been despawned or are no longer dynamic.
+    let removed_bodies: Vec<Entity> = islands
+        .body_islands
+        .keys()
+        .copied()
+        .filter(|&entity| !bodies.get(entity).is_ok_and(|(_, rb, _)| rb.is_dynamic()))
+        .collect();
+    for body in removed_bodies {
+        islands.remove_body(body);
+    }
+
+    // Add new dynamic bodies to their own islands.
+    for (entity, rb, is_sleeping) in &bodies {
+        if rb.is_dynamic() && !islands.body_islands.contains_key(&entity) {
+            islands.create_island(vec![entity], is_sleeping);
+        }
+    }
+
+    let is_dynamic = |entity: Entity| bodies.get(entity).is_ok_and(|(_, rb, _)| rb.is_dynamic());
+
+    // Collect the pairs of dynamic bodies that are touching or connected by a joint.
+    edges.clear();
+    for contacts in collisions.get_internal().values() {
+        if !contacts.during_current_frame
+            || contacts.is_sensor
+            || contacts.manifolds.iter().all(|m| m.contacts.is_empty())
+        {
+            continue;
+        }
+        let body1 = contacts.body_entity1.unwrap_or(contacts.entity1);
+        let body2 = contacts.body_entity2.unwrap_or(contacts.entity2);
+        if body1 != body2 && is_dynamic(body1) && is_dynamic(body2) {
+            edges.insert(if body1 < body2 {
+                (body1, body2)
+            } else {
+                (body2, body1)
+            });
+        }
+    }
+    for joint in joint_graph.joints() {
+        let Some((body1, body2)) = joint_graph.bodies(joint) else {
+            continue;
+        };
+        if body1 != body2 && is_dynamic(body1) && is_dynamic(body2) {
+            edges.insert(if body1 < body2 {
+                (body1, body2)
+            } else {
+                (body2, body1)
+            });
+        }
+    }
+
+    // Merge the islands of connected bodies.
+    for &(body1, body2) in edges.iter() {
+        islands.link(body1, body2);
+    }
+
+    // Mark islands whose contacts or joints have been removed so that they can be split later.
+    for &(body1, _) in islands.edges.difference(&edges) {
+        if let Some(id) = islands.body_islands.get(&body1) {
+            if let Some(island) = islands.islands.get_mut(id) {
+                island.constraint_removed = true;
+            }
+        }
+    }
+
+    std::mem::swap(&mut islands.edges, &mut *edges);
+}
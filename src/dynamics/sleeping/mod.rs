@@ -2,6 +2,8 @@
 //!
 //! See [`SleepingPlugin`].
 
+pub mod islands;
+
 use crate::prelude::*;
 use bevy::{
     ecs::{component::Tick, system::SystemChangeTick},
@@ -10,22 +12,26 @@ use bevy::{
 
 /// Manages sleeping and waking for bodies, automatically deactivating them to save computational resources.
 ///
-/// Bodies are marked as [`Sleeping`] when their linear and angular velocities are below the [`SleepingThreshold`]
-/// for a duration indicated by [`DeactivationTime`].
+/// Bodies are grouped into simulation [islands](PhysicsIslands) of bodies that interact through contacts or joints.
+/// An island is marked as [`Sleeping`] when the linear and angular velocities of all of its bodies
+/// have been below the [`SleepingThreshold`] for a duration indicated by [`DeactivationTime`].
 ///
 /// Bodies are woken up when an active body or constraint interacts with them, or when gravity changes,
 /// or when the body's position, rotation, velocity, or external forces are changed.
+/// Waking up a body wakes up its whole island.
 ///
 /// This plugin does *not* handle constraints waking up bodies. That is done by the [solver](dynamics::solver).
 ///
-/// The sleeping systems run in [`PhysicsStepSet::Sleeping`].
+/// The [`PhysicsIslands`] are updated in [`PhysicsStepSet::Sleeping`], and bodies are put to sleep
+/// or woken up at the start of the next physics step.
 pub struct SleepingPlugin;
 
 impl Plugin for SleepingPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SleepingThreshold>()
             .init_resource::<DeactivationTime>()
-            .init_resource::<LastPhysicsTick>();
+            .init_resource::<LastPhysicsTick>()
+            .init_resource::<PhysicsIslands>();
 
         let physics_schedule = app
             .get_schedule_mut(PhysicsSchedule)
@@ -35,7 +41,9 @@ impl Plugin for SleepingPlugin {
         physics_schedule.add_systems(
             (
                 wake_on_changed,
+                wake_on_target_changed,
                 wake_all_sleeping_bodies.run_if(resource_changed::<Gravity>),
+                wake_islands,
                 mark_sleeping_bodies,
             )
                 .chain()
@@ -46,6 +54,8 @@ impl Plugin for SleepingPlugin {
         physics_schedule
             .add_systems(wake_on_collision_ended.in_set(PhysicsStepSet::ReportContacts));
 
+        physics_schedule.add_systems(islands::update_islands.in_set(PhysicsStepSet::Sleeping));
+
         physics_schedule.add_systems(
             (|mut last_physics_tick: ResMut<LastPhysicsTick>,
               system_change_tick: SystemChangeTick| {
@@ -105,72 +115,126 @@ impl Default for DeactivationTime {
     }
 }
 
-/// Adds the [`Sleeping`] component to bodies whose linear and anigular velocities have been
-/// under the [`SleepingThreshold`] for a duration indicated by [`DeactivationTime`].
+/// Adds the [`Sleeping`] component to the bodies of [islands](PhysicsIslands) whose bodies' linear and angular
+/// velocities have all been under the [`SleepingThreshold`] for a duration indicated by [`DeactivationTime`].
+///
+/// Islands whose contacts or joints have been removed are split first if some of their bodies could sleep,
+/// so that bodies that are no longer connected don't keep each other awake.
 #[allow(clippy::type_complexity)]
 pub fn mark_sleeping_bodies(
     mut commands: Commands,
     mut query: Query<
         (
             Entity,
-            &RigidBody,
             &mut LinearVelocity,
             &mut AngularVelocity,
             &mut TimeSleeping,
-            &CollidingEntities,
+            Has<SleepingDisabled>,
         ),
-        (Without<Sleeping>, Without<SleepingDisabled>),
+        Without<Sleeping>,
     >,
-    rb_query: Query<&RigidBody>,
+    mut islands: ResMut<PhysicsIslands>,
     deactivation_time: Res<DeactivationTime>,
     sleep_threshold: Res<SleepingThreshold>,
     length_unit: Res<PhysicsLengthUnit>,
     time: Res<Time>,
+    mut islands_to_split: Local<Vec<IslandId>>,
 ) {
     let length_unit_sq = length_unit.powi(2);
     let delta_secs = time.delta_seconds_adjusted();
 
-    for (entity, rb, mut lin_vel, mut ang_vel, mut time_sleeping, colliding_entities) in &mut query
-    {
-        // Only dynamic bodies can sleep, and only if they are not
-        // in contact with other dynamic bodies.
-        //
-        // Contacts with other types of bodies will be allowed once
-        // sleeping/waking is implemented with simulation islands.
-        if !rb.is_dynamic()
-            || rb_query
-                .iter_many(colliding_entities.iter())
-                .any(|rb| rb.is_dynamic())
-        {
+    // Negative thresholds indicate that sleeping is disabled.
+    let lin_sleeping_threshold_sq =
+        length_unit_sq * sleep_threshold.linear * sleep_threshold.linear.abs();
+    let ang_sleeping_threshold_sq = sleep_threshold.angular * sleep_threshold.angular.abs();
+
+    islands_to_split.clear();
+
+    for (id, island) in islands.islands.iter_mut() {
+        if island.is_sleeping {
             continue;
         }
 
-        let lin_vel_sq = lin_vel.length_squared();
+        // The shortest and longest time that the bodies in the island have remained still.
+        let mut min_time_sleeping = Scalar::MAX;
+        let mut max_time_sleeping: Scalar = 0.0;
 
-        #[cfg(feature = "2d")]
-        let ang_vel_sq = ang_vel.0.powi(2);
-        #[cfg(feature = "3d")]
-        let ang_vel_sq = ang_vel.0.dot(ang_vel.0);
+        let mut bodies = query.iter_many_mut(&island.bodies);
+        while let Some((_, lin_vel, ang_vel, mut time_sleeping, sleeping_disabled)) =
+            bodies.fetch_next()
+        {
+            let lin_vel_sq = lin_vel.length_squared();
 
-        // Negative thresholds indicate that sleeping is disabled.
-        let lin_sleeping_threshold_sq =
-            length_unit_sq * sleep_threshold.linear * sleep_threshold.linear.abs();
-        let ang_sleeping_threshold_sq = sleep_threshold.angular * sleep_threshold.angular.abs();
+            #[cfg(feature = "2d")]
+            let ang_vel_sq = ang_vel.0.powi(2);
+            #[cfg(feature = "3d")]
+            let ang_vel_sq = ang_vel.0.dot(ang_vel.0);
 
-        // If linear and angular velocity are below the sleeping threshold,
-        // add delta time to the time sleeping, i.e. the time that the body has remained still.
-        if lin_vel_sq < lin_sleeping_threshold_sq && ang_vel_sq < ang_sleeping_threshold_sq {
-            time_sleeping.0 += delta_secs;
-        } else {
-            time_sleeping.0 = 0.0;
+            // If linear and angular velocity are below the sleeping threshold,
+            // add delta time to the time sleeping, i.e. the time that the body has remained still.
+            if !sleeping_disabled
+                && lin_vel_sq < lin_sleeping_threshold_sq
+                && ang_vel_sq < ang_sleeping_threshold_sq
+            {
+                time_sleeping.0 += delta_secs;
+            } else {
+                time_sleeping.0 = 0.0;
+            }
+
+            min_time_sleeping = min_time_sleeping.min(time_sleeping.0);
+            max_time_sleeping = max_time_sleeping.max(time_sleeping.0);
+        }
+
+        if min_time_sleeping > deactivation_time.0 {
+            // All bodies in the island have been still for long enough.
+            // Set them to sleep and reset velocities.
+            island.is_sleeping = true;
+
+            let mut bodies = query.iter_many_mut(&island.bodies);
+            while let Some((entity, mut lin_vel, mut ang_vel, ..)) = bodies.fetch_next() {
+                commands.entity(entity).try_insert(Sleeping);
+                *lin_vel = LinearVelocity::ZERO;
+                *ang_vel = AngularVelocity::ZERO;
+            }
+        } else if island.constraint_removed && max_time_sleeping > deactivation_time.0 {
+            // Some bodies could sleep, but the island might be kept awake by bodies
+            // that are no longer connected to them.
+            islands_to_split.push(*id);
+        }
+    }
+
+    for id in islands_to_split.drain(..) {
+        islands.split_island(id);
+    }
+}
+
+/// Wakes up all bodies in [islands](PhysicsIslands) that contain both sleeping and awake bodies,
+/// for example when a body in a sleeping island was woken up by a collision or by the user.
+pub fn wake_islands(
+    mut commands: Commands,
+    mut islands: ResMut<PhysicsIslands>,
+    mut bodies: Query<(Entity, Has<Sleeping>, &mut TimeSleeping)>,
+) {
+    for island in islands.islands.values_mut() {
+        let mut any_sleeping = false;
+        let mut any_awake = false;
+
+        for (_, is_sleeping, _) in bodies.iter_many(&island.bodies) {
+            any_sleeping |= is_sleeping;
+            any_awake |= !is_sleeping;
         }
 
-        // If the body has been still for long enough, set it to sleep and reset velocities.
-        if time_sleeping.0 > deactivation_time.0 {
-            commands.entity(entity).try_insert(Sleeping);
-            *lin_vel = LinearVelocity::ZERO;
-            *ang_vel = AngularVelocity::ZERO;
+        if any_sleeping && any_awake {
+            let mut island_bodies = bodies.iter_many_mut(&island.bodies);
+            while let Some((entity, is_sleeping, mut time_sleeping)) = island_bodies.fetch_next() {
+                if is_sleeping {
+                    commands.entity(entity).remove::<Sleeping>();
+                }
+                time_sleeping.0 = 0.0;
+            }
         }
+
+        island.is_sleeping = any_sleeping && !any_awake;
     }
 }
 
@@ -241,6 +305,28 @@ pub(crate) fn wake_on_changed(
     }
 }
 
+/// Removes the [`Sleeping`] component from bodies attached to a [`TargetJoint`]
+/// when the joint is changed by the user, for example when its target is moved.
+fn wake_on_target_changed(
+    mut commands: Commands,
+    joints: Query<Ref<TargetJoint>, Changed<TargetJoint>>,
+    mut bodies: Query<&mut TimeSleeping, With<Sleeping>>,
+    last_physics_tick: Res<LastPhysicsTick>,
+    system_tick: SystemChangeTick,
+) {
+    let this_run = system_tick.this_run();
+
+    for joint in &joints {
+        let entity = joint.entity;
+        if is_changed_after_tick(joint, last_physics_tick.0, this_run) {
+            if let Ok(mut time_sleeping) = bodies.get_mut(entity) {
+                commands.entity(entity).remove::<Sleeping>();
+                time_sleeping.0 = 0.0;
+            }
+        }
+    }
+}
+
 fn is_changed_after_tick<C: Component>(component_ref: Ref<C>, tick: Tick, this_run: Tick) -> bool {
     let last_changed = component_ref.last_changed();
     component_ref.is_changed() && last_changed.is_newer_than(tick, this_run)
@@ -265,10 +351,16 @@ fn wake_on_collision_ended(
     moved_bodies: Query<Ref<Position>, (Changed<Position>, Without<Sleeping>)>,
     colliders: Query<(&ColliderParent, Ref<ColliderTransform>)>,
     collisions: Res<Collisions>,
-    mut sleeping: Query<(Entity, &mut TimeSleeping)>,
+    mut sleeping: Query<(Entity, &mut TimeSleeping, Has<Sleeping>)>,
 ) {
-    // Wake up bodies when a body they're colliding with moves.
-    for (entity, mut time_sleeping) in &mut sleeping {
+    // Wake up sleeping bodies when a body they're colliding with moves.
+    for (entity, mut time_sleeping, is_sleeping) in &mut sleeping {
+        // Awake bodies in contact with each other belong to the same island,
+        // so they should not keep each other awake here.
+        if !is_sleeping {
+            continue;
+        }
+
         // Here we could use CollidingEntities, but it'd be empty if the ContactReportingPlugin was disabled.
         let mut colliding_entities = collisions.collisions_with_entity(entity).map(|c| {
             if entity == c.entity1 {
@@ -293,11 +385,11 @@ fn wake_on_collision_ended(
         if contacts.during_current_frame || !contacts.during_previous_frame {
             continue;
         }
-        if let Ok((_, mut time_sleeping)) = sleeping.get_mut(contacts.entity1) {
+        if let Ok((_, mut time_sleeping, _)) = sleeping.get_mut(contacts.entity1) {
             commands.entity(contacts.entity1).remove::<Sleeping>();
             time_sleeping.0 = 0.0;
         }
-        if let Ok((_, mut time_sleeping)) = sleeping.get_mut(contacts.entity2) {
+        if let Ok((_, mut time_sleeping, _)) = sleeping.get_mut(contacts.entity2) {
             commands.entity(contacts.entity2).remove::<Sleeping>();
             time_sleeping.0 = 0.0;
         }
@@ -0,0 +1,921 @@
+//! **Articulations** are trees of bodies connected by joints that are simulated in *reduced coordinates*.
+//!
+//! [Joints](super::joints) are constraints between otherwise free bodies, and the solver pulls the bodies
+//! back together when they drift apart. Long chains and heavy bodies attached to light ones
+//! can make joints stretch and jitter. An articulation instead describes the configuration of the tree
+//! using joint coordinates, like the angle of a revolute joint, and the links can only move in ways
+//! that the joints allow. This means that the joints never separate, regardless of mass ratios or
+//! the length of the chain, which makes articulations well suited for things like robotic arms and cranes.
+//!
+//! Articulations are simulated using Featherstone's *Articulated Body Algorithm*,
+//! which computes the joint accelerations of the whole tree in linear time.
+//!
+//! ## Creating articulations
+//!
+//! An articulation consists of a root body and a number of *links*. The root is a static or kinematic
+//! [rigid body](RigidBody), and links are dynamic rigid bodies with an [`ArticulationLink`] component
+//! that references their parent, which is either the root or another link.
+//!
+//! ```
+#![cfg_attr(feature = "2d", doc = "use avian2d::{math::*, prelude::*};")]
+#![cfg_attr(feature = "3d", doc = "use avian3d::{math::*, prelude::*};")]
+//! use bevy::prelude::*;
+//!
+//! fn setup(mut commands: Commands) {
+//!     let base = commands.spawn(RigidBody::Static).id();
+//!
+//!     // A two-link arm with a motorized shoulder
+//!     let upper_arm = commands
+//!         .spawn((
+//!             RigidBody::Dynamic,
+#![cfg_attr(feature = "2d", doc = "            Collider::rectangle(1.0, 0.2),")]
+#![cfg_attr(feature = "3d", doc = "            Collider::cuboid(1.0, 0.2, 0.2),")]
+//!             ArticulationLink::revolute(base)
+//!                 .with_local_anchor_2(Vector::X * -0.5)
+//!                 .with_motor(JointMotor::new_position(1.0, 200.0, 20.0)),
+//!         ))
+//!         .id();
+//!     commands.spawn((
+//!         RigidBody::Dynamic,
+#![cfg_attr(feature = "2d", doc = "        Collider::rectangle(1.0, 0.2),")]
+#![cfg_attr(feature = "3d", doc = "        Collider::cuboid(1.0, 0.2, 0.2),")]
+//!         ArticulationLink::revolute(upper_arm)
+//!             .with_local_anchor_1(Vector::X * 0.5)
+//!             .with_local_anchor_2(Vector::X * -0.5)
+//!             .with_angle_limits(-1.5, 1.5),
+//!     ));
+//! }
+//! ```
+//!
+//! The pose of the links when the articulation is first simulated is used as the rest pose,
+//! and the joint coordinates are measured relative to it.
+//!
+//! ## Link types
+//!
+//! | Link type                       | Allowed 2D DOF | Allowed 3D DOF |
+//! | ------------------------------- | -------------- | -------------- |
+//! | [`LinkJointType::Revolute`]     | 1 Rotation     | 1 Rotation     |
+//! | [`LinkJointType::Prismatic`]    | 1 Translation  | 1 Translation  |
+#![cfg_attr(
+    feature = "3d",
+    doc = "| [`LinkJointType::Spherical`]    | -              | 3 Rotations    |"
+)]
+//!
+//! Revolute and prismatic links can be driven by a [`JointMotor`] and restricted by [angle](AngleLimit)
+//! or [distance](DistanceLimit) limits.
+#![cfg_attr(
+    feature = "3d",
+    doc = "Spherical links can be limited to a maximum rotation angle from the rest pose, but they don't support motors."
+)]
+//!
+//! ## Interaction with other bodies
+//!
+//! Links keep their [`Position`], [`Rotation`], velocities and colliders, so they take part in contacts
+//! and spatial queries like any other body. Gravity, external forces and contact impulses applied to the links
+//! during a substep are converted into forces acting on the articulation, and the new poses and velocities
+//! of the links are written back to the bodies at the end of each substep.
+//!
+//! Collisions between a link and its parent are disabled by default.
+//! This can be changed with [`ArticulationLink::with_collisions_disabled`].
+//!
+//! ## Limitations
+//!
+//! - The root is not affected by the articulation. A dynamic root is treated like a kinematic body.
+//! - The contact solver treats links as free bodies, so contacts against heavy articulations can be slightly soft.
+//! - Loops are not supported. Use [joints](super::joints) to close loops between links.
+
+mod spatial;
+
+use crate::prelude::*;
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+    utils::HashMap,
+};
+use spatial::{SpatialMatrix, SpatialVector};
+
+/// The number of iterations used for resolving joint limits.
+const LIMIT_ITERATIONS: usize = 4;
+
+/// The type of joint connecting an [`ArticulationLink`] to its parent.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, PartialEq)]
+pub enum LinkJointType {
+    /// Allows rotation around the joint axis. The joint coordinate is an angle in radians.
+    #[default]
+    Revolute,
+    /// Allows translation along the joint axis. The joint coordinate is a distance.
+    Prismatic,
+    /// Allows free rotation around the anchor point.
+    #[cfg(feature = "3d")]
+    Spherical,
+}
+
+impl LinkJointType {
+    /// Returns the number of degrees of freedom allowed by the joint.
+    pub const fn degrees_of_freedom(self) -> usize {
+        match self {
+            Self::Revolute | Self::Prismatic => 1,
+            #[cfg(feature = "3d")]
+            Self::Spherical => 3,
+        }
+    }
+}
+
+/// A link in an [articulation](self), connected to a `parent` body by a joint.
+///
+/// The entity with the component must be a dynamic [rigid body](RigidBody). The `parent` is either another link
+/// or the root of the articulation, which is a static or kinematic body without an `ArticulationLink`.
+///
+/// The joint coordinates are stored in `position` and `velocity`
+#[cfg_attr(
+    feature = "3d",
+    doc = "for revolute and prismatic links, and in `spherical_rotation` and `spherical_velocity` for spherical links"
+)]
+/// and are updated by the solver. They can also be modified to move the link directly.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, MapEntities, PartialEq)]
+pub struct ArticulationLink {
+    /// The parent of the link, which is either another link or the root of the articulation.
+    pub parent: Entity,
+    /// The type of joint connecting the link to its parent.
+    pub joint_type: LinkJointType,
+    /// Attachment point on the parent body.
+    pub local_anchor1: Vector,
+    /// Attachment point on the link.
+    pub local_anchor2: Vector,
+    /// A unit vector in the local space of the parent that the link rotates around or translates along.
+    ///
+    /// In 2D, revolute links always rotate around the Z axis, and the axis is only used by prismatic links.
+    pub axis: Vector,
+    /// If `true`, the link doesn't collide with its parent.
+    pub collisions_disabled: bool,
+    /// The extents of the allowed rotation of a revolute link.
+    #[cfg_attr(
+        feature = "3d",
+        doc = "\n\nFor spherical links, `max` is the maximum rotation angle from the rest pose."
+    )]
+    pub angle_limit: Option<AngleLimit>,
+    /// The extents of the allowed translation of a prismatic link.
+    pub distance_limit: Option<DistanceLimit>,
+    /// A motor that drives the joint coordinate of a revolute or prismatic link.
+    pub motor: Option<JointMotor>,
+    /// The joint coordinate of a revolute or prismatic link.
+    ///
+    /// For revolute links, this is an angle in radians.
+    pub position: Scalar,
+    /// The rate of change of the joint coordinate of a revolute or prismatic link.
+    pub velocity: Scalar,
+    /// The rotation of a spherical link relative to its rest pose, in the local space of the parent.
+    #[cfg(feature = "3d")]
+    pub spherical_rotation: Quaternion,
+    /// The angular velocity of a spherical link relative to its parent, in the local space of the parent.
+    #[cfg(feature = "3d")]
+    pub spherical_velocity: Vector,
+    /// The rotation of the link relative to its parent in the rest pose.
+    /// Initialized when the link is first simulated.
+    rest_rotation: Option<Rotation>,
+    /// The linear velocity written to the body by the solver in the previous substep.
+    previous_linear_velocity: Vector,
+    /// The angular velocity written to the body by the solver in the previous substep.
+    previous_angular_velocity: AngularVelocity,
+}
+
+impl ArticulationLink {
+    /// Creates a link connected to the `parent` by a revolute joint.
+    ///
+    /// In 3D, the link rotates around the Z axis by default.
+    pub fn revolute(parent: Entity) -> Self {
+        Self {
+            #[cfg(feature = "2d")]
+            axis: Vector::X,
+            #[cfg(feature = "3d")]
+            axis: Vector::Z,
+            ..Self::new(parent, LinkJointType::Revolute)
+        }
+    }
+
+    /// Creates a link connected to the `parent` by a prismatic joint.
+    ///
+    /// The link translates along the X axis by default.
+    pub fn prismatic(parent: Entity) -> Self {
+        Self::new(parent, LinkJointType::Prismatic)
+    }
+
+    /// Creates a link connected to the `parent` by a spherical joint.
+    #[cfg(feature = "3d")]
+    pub fn spherical(parent: Entity) -> Self {
+        Self::new(parent, LinkJointType::Spherical)
+    }
+
+    fn new(parent: Entity, joint_type: LinkJointType) -> Self {
+        Self {
+            parent,
+            joint_type,
+            local_anchor1: Vector::ZERO,
+            local_anchor2: Vector::ZERO,
+            axis: Vector::X,
+            collisions_disabled: true,
+            angle_limit: None,
+            distance_limit: None,
+            motor: None,
+            position: 0.0,
+            velocity: 0.0,
+            #[cfg(feature = "3d")]
+            spherical_rotation: Quaternion::IDENTITY,
+            #[cfg(feature = "3d")]
+            spherical_velocity: Vector::ZERO,
+            rest_rotation: None,
+            previous_linear_velocity: Vector::ZERO,
+            previous_angular_velocity: AngularVelocity::ZERO,
+        }
+    }
+
+    /// Sets the attachment point on the parent body.
+    pub fn with_local_anchor_1(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor1: anchor,
+            ..self
+        }
+    }
+
+    /// Sets the attachment point on the link.
+    pub fn with_local_anchor_2(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor2: anchor,
+            ..self
+        }
+    }
+
+    /// Sets the axis that the link rotates around or translates along, in the local space of the parent.
+    pub fn with_axis(self, axis: Vector) -> Self {
+        Self {
+            axis: axis.normalize(),
+            ..self
+        }
+    }
+
+    /// Sets whether the link collides with its parent.
+    pub fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
+    /// Sets the limits of the allowed rotation of a revolute link.
+    #[cfg_attr(
+        feature = "3d",
+        doc = "\n\nFor spherical links, `max` is the maximum rotation angle from the rest pose, and `min` is ignored."
+    )]
+    pub fn with_angle_limits(self, min: Scalar, max: Scalar) -> Self {
+        Self {
+            angle_limit: Some(AngleLimit::new(min, max)),
+            ..self
+        }
+    }
+
+    /// Sets the limits of the allowed translation of a prismatic link.
+    pub fn with_distance_limits(self, min: Scalar, max: Scalar) -> Self {
+        Self {
+            distance_limit: Some(DistanceLimit::new(min, max)),
+            ..self
+        }
+    }
+
+    /// Sets the motor that drives the joint coordinate of a revolute or prismatic link.
+    pub fn with_motor(self, motor: JointMotor) -> Self {
+        Self {
+            motor: Some(motor),
+            ..self
+        }
+    }
+
+    /// Returns the rotation of the link relative to its rest pose, caused by the joint coordinates.
+    fn joint_rotation(&self) -> Rotation {
+        match self.joint_type {
+            #[cfg(feature = "2d")]
+            LinkJointType::Revolute => Rotation::radians(self.position),
+            #[cfg(feature = "3d")]
+            LinkJointType::Revolute => {
+                Rotation(Quaternion::from_axis_angle(self.axis, self.position))
+            }
+            LinkJointType::Prismatic => Rotation::default(),
+            #[cfg(feature = "3d")]
+            LinkJointType::Spherical => Rotation(self.spherical_rotation),
+        }
+    }
+
+    /// Returns the velocities of the joint coordinates.
+    fn joint_velocity(&self) -> Vector3 {
+        match self.joint_type {
+            LinkJointType::Revolute | LinkJointType::Prismatic => Vector3::X * self.velocity,
+            #[cfg(feature = "3d")]
+            LinkJointType::Spherical => self.spherical_velocity,
+        }
+    }
+}
+
+impl MapEntities for ArticulationLink {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.parent = entity_mapper.map_entity(self.parent);
+    }
+}
+
+/// The pose and velocity of the root of an articulation.
+struct RootState {
+    origin: Vector,
+    rotation: Rotation,
+    velocity: SpatialVector,
+}
+
+/// The state of a link during a substep.
+///
+/// All spatial quantities are expressed at the center of mass of the root, using world-space axes.
+struct LinkData {
+    entity: Entity,
+    link: ArticulationLink,
+    /// The index of the parent link, or `None` if the parent is the root.
+    parent: Option<usize>,
+    mass: Scalar,
+    local_inertia: Inertia,
+    local_center_of_mass: Vector,
+    origin: Vector,
+    rotation: Rotation,
+    center_of_mass: Vector,
+    joint_velocity: Vector3,
+    /// The spatial velocities caused by a unit velocity of each joint coordinate.
+    subspace: [SpatialVector; 3],
+    velocity: SpatialVector,
+    external_impulse: SpatialVector,
+    bias_acceleration: SpatialVector,
+    articulated_inertia: SpatialMatrix,
+    bias_force: SpatialVector,
+    /// The articulated inertia multiplied by each column of the `subspace`.
+    inertia_subspace: [SpatialVector; 3],
+    /// The inverse of the articulated inertia projected onto the joint coordinates.
+    inverse_joint_inertia: Matrix3,
+    joint_force: Vector3,
+    acceleration: SpatialVector,
+    limit_impulse: Vector3,
+    impulse_force: SpatialVector,
+    joint_impulse: Vector3,
+    velocity_change: SpatialVector,
+}
+
+impl LinkData {
+    /// Returns the spatial inertia of the link at the given reference point.
+    fn spatial_inertia(&self, reference: Vector) -> SpatialMatrix {
+        #[cfg(feature = "2d")]
+        let inertia = self.local_inertia.0;
+        #[cfg(feature = "3d")]
+        let inertia = self.local_inertia.rotated(&self.rotation).0;
+        SpatialMatrix::rigid_body(self.mass, inertia, self.center_of_mass - reference)
+    }
+
+    /// Computes the force of the motor as an implicit spring-damper, using the
+    /// articulated inertia `joint_inertia` of the joint as the effective mass.
+    fn motor_force(&self, joint_inertia: Scalar, delta_secs: Scalar) -> Vector3 {
+        let Some(motor) = self.link.motor else {
+            return Vector3::ZERO;
+        };
+        if self.link.joint_type.degrees_of_freedom() != 1 || joint_inertia <= Scalar::EPSILON {
+            return Vector3::ZERO;
+        }
+
+        let position = self.link.position;
+        let velocity = self.joint_velocity.x;
+        let impulse = delta_secs
+            * (motor.stiffness * (motor.target_position - position - delta_secs * velocity)
+                + motor.damping * (motor.target_velocity - velocity))
+            / (1.0 + delta_secs * (delta_secs * motor.stiffness + motor.damping) / joint_inertia);
+        let max_impulse = motor.max_force * delta_secs;
+
+        Vector3::X * impulse.clamp(-max_impulse, max_impulse) / delta_secs
+    }
+
+    /// Computes the joint impulse required to keep the joint coordinates within the limits
+    /// at the end of the substep.
+    fn compute_limit_impulse(&self, delta_secs: Scalar) -> Vector3 {
+        let limit = match self.link.joint_type {
+            LinkJointType::Revolute => self.link.angle_limit.map(|limit| (limit.min, limit.max)),
+            LinkJointType::Prismatic => {
+                self.link.distance_limit.map(|limit| (limit.min, limit.max))
+            }
+            #[cfg(feature = "3d")]
+            LinkJointType::Spherical => return self.compute_spherical_limit_impulse(delta_secs),
+        };
+        let Some((min, max)) = limit else {
+            return Vector3::ZERO;
+        };
+
+        let position = self.link.position;
+        let velocity = self.joint_velocity.x;
+        let predicted_position = position + velocity * delta_secs;
+
+        let target_velocity = if predicted_position < min {
+            (min - position) / delta_secs
+        } else if predicted_position > max {
+            (max - position) / delta_secs
+        } else {
+            return Vector3::ZERO;
+        };
+
+        Vector3::X * (target_velocity - velocity) / self.inverse_joint_inertia.x_axis.x
+    }
+
+    #[cfg(feature = "3d")]
+    fn compute_spherical_limit_impulse(&self, delta_secs: Scalar) -> Vector3 {
+        let Some(limit) = self.link.angle_limit else {
+            return Vector3::ZERO;
+        };
+
+        let (mut axis, mut angle) = self.link.spherical_rotation.to_axis_angle();
+        if angle > PI {
+            axis = -axis;
+            angle = TAU - angle;
+        }
+
+        let velocity = axis.dot(self.joint_velocity);
+        if angle + velocity * delta_secs <= limit.max {
+            return Vector3::ZERO;
+        }
+
+        let target_velocity = (limit.max - angle) / delta_secs;
+        let inverse_inertia = axis.dot(self.inverse_joint_inertia * axis);
+        if inverse_inertia <= Scalar::EPSILON {
+            return Vector3::ZERO;
+        }
+
+        axis * (target_velocity - velocity) / inverse_inertia
+    }
+}
+
+/// Computes the sum of the given `columns` weighted by the components of `weights`.
+fn combine(columns: &[SpatialVector; 3], weights: Vector3) -> SpatialVector {
+    columns[0] * weights.x + columns[1] * weights.y + columns[2] * weights.z
+}
+
+/// Computes the dot product of each of the given `columns` and the vector `v`.
+fn project(columns: &[SpatialVector; 3], v: SpatialVector) -> Vector3 {
+    Vector3::new(columns[0].dot(v), columns[1].dot(v), columns[2].dot(v))
+}
+
+#[cfg(feature = "2d")]
+fn mul_rotations(a: Rotation, b: Rotation) -> Rotation {
+    a * b
+}
+
+#[cfg(feature = "3d")]
+fn mul_rotations(a: Rotation, b: Rotation) -> Rotation {
+    Rotation(a.0 * b.0)
+}
+
+/// Computes the motion subspace of the joint of the `link`, given the rotation of the parent
+/// and the position of the joint anchor relative to the reference point.
+#[allow(unused_variables)]
+fn motion_subspace(
+    link: &ArticulationLink,
+    parent_rotation: Rotation,
+    pivot: Vector,
+) -> [SpatialVector; 3] {
+    let mut columns = [SpatialVector::ZERO; 3];
+    match link.joint_type {
+        #[cfg(feature = "2d")]
+        LinkJointType::Revolute => columns[0] = SpatialVector::new(1.0, -pivot.perp()),
+        #[cfg(feature = "3d")]
+        LinkJointType::Revolute => {
+            let axis = parent_rotation * link.axis;
+            columns[0] = SpatialVector::new(axis, pivot.cross(axis));
+        }
+        LinkJointType::Prismatic => {
+            columns[0] = SpatialVector::new(Default::default(), parent_rotation * link.axis);
+        }
+        #[cfg(feature = "3d")]
+        LinkJointType::Spherical => {
+            for (column, local_axis) in columns.iter_mut().zip([Vector::X, Vector::Y, Vector::Z]) {
+                let axis = parent_rotation * local_axis;
+                *column = SpatialVector::new(axis, pivot.cross(axis));
+            }
+        }
+    }
+    columns
+}
+
+/// Computes the poses and spatial velocities of the links from the joint coordinates.
+fn update_kinematics(links: &mut [LinkData], root: &RootState, reference: Vector) {
+    for i in 0..links.len() {
+        let (parent_origin, parent_rotation, parent_velocity) = match links[i].parent {
+            Some(parent) => (
+                links[parent].origin,
+                links[parent].rotation,
+                links[parent].velocity,
+            ),
+            None => (root.origin, root.rotation, root.velocity),
+        };
+
+        let data = &mut links[i];
+        let link = &data.link;
+
+        let mut anchor1 = link.local_anchor1;
+        if link.joint_type == LinkJointType::Prismatic {
+            anchor1 += link.axis * link.position;
+        }
+        let pivot = parent_origin + parent_rotation * anchor1;
+        let rotation = mul_rotations(
+            mul_rotations(parent_rotation, link.joint_rotation()),
+            link.rest_rotation.unwrap_or_default(),
+        );
+
+        data.subspace = motion_subspace(link, parent_rotation, pivot - reference);
+        data.origin = pivot - rotation * link.local_anchor2;
+        data.rotation = rotation;
+        data.center_of_mass = data.origin + rotation * data.local_center_of_mass;
+        data.velocity = parent_velocity + combine(&data.subspace, data.joint_velocity);
+    }
+}
+
+/// Computes the joint accelerations using the Articulated Body Algorithm and integrates the joint velocities.
+fn solve_joint_accelerations(links: &mut [LinkData], reference: Vector, delta_secs: Scalar) {
+    // Compute the velocity-dependent bias terms and the forces acting on each link in isolation.
+    for data in links.iter_mut() {
+        let inertia = data.spatial_inertia(reference);
+        let joint_motion = combine(&data.subspace, data.joint_velocity);
+        data.bias_acceleration = data.velocity.cross_motion(joint_motion);
+        data.articulated_inertia = inertia;
+        data.bias_force = data.velocity.cross_force(inertia * data.velocity)
+            - data.external_impulse * (1.0 / delta_secs);
+    }
+
+    // Accumulate the articulated inertias and bias forces from the leaves towards the root.
+    for i in (0..links.len()).rev() {
+        let data = &mut links[i];
+        let dof = data.link.joint_type.degrees_of_freedom();
+
+        data.inertia_subspace = data
+            .subspace
+            .map(|column| data.articulated_inertia * column);
+
+        // Unused degrees of freedom are padded with the identity.
+        let mut joint_inertia = Matrix3::IDENTITY;
+        for j in 0..dof {
+            for k in 0..dof {
+                joint_inertia.col_mut(k)[j] = data.subspace[j].dot(data.inertia_subspace[k]);
+            }
+        }
+        data.inverse_joint_inertia = joint_inertia.inverse();
+
+        let motor_force = data.motor_force(joint_inertia.x_axis.x, delta_secs);
+        data.joint_force = motor_force - project(&data.subspace, data.bias_force);
+
+        let Some(parent) = data.parent else {
+            continue;
+        };
+
+        let mut inertia = data.articulated_inertia;
+        for k in 0..dof {
+            let weighted = combine(&data.inertia_subspace, data.inverse_joint_inertia.col(k));
+            inertia -= SpatialMatrix::outer(weighted, data.inertia_subspace[k]);
+        }
+        let bias_force = data.bias_force
+            + inertia * data.bias_acceleration
+            + combine(
+                &data.inertia_subspace,
+                data.inverse_joint_inertia * data.joint_force,
+            );
+
+        links[parent].articulated_inertia += inertia;
+        links[parent].bias_force += bias_force;
+    }
+
+    // Compute the joint accelerations from the root towards the leaves.
+    for i in 0..links.len() {
+        let parent_acceleration = links[i]
+            .parent
+            .map_or(SpatialVector::ZERO, |parent| links[parent].acceleration);
+
+        let data = &mut links[i];
+        let acceleration = parent_acceleration + data.bias_acceleration;
+        let joint_acceleration = data.inverse_joint_inertia
+            * (data.joint_force - project(&data.inertia_subspace, acceleration));
+
+        data.acceleration = acceleration + combine(&data.subspace, joint_acceleration);
+        data.joint_velocity += joint_acceleration * delta_secs;
+    }
+}
+
+/// Applies the `limit_impulse` of each link to the joint velocities of the articulation.
+fn apply_joint_impulses(links: &mut [LinkData]) {
+    for data in links.iter_mut() {
+        data.impulse_force = SpatialVector::ZERO;
+    }
+
+    for i in (0..links.len()).rev() {
+        let data = &mut links[i];
+        data.joint_impulse = data.limit_impulse - project(&data.subspace, data.impulse_force);
+
+        if let Some(parent) = data.parent {
+            let impulse_force = data.impulse_force
+                + combine(
+                    &data.inertia_subspace,
+                    data.inverse_joint_inertia * data.joint_impulse,
+                );
+            links[parent].impulse_force += impulse_force;
+        }
+    }
+
+    for i in 0..links.len() {
+        let parent_velocity_change = links[i]
+            .parent
+            .map_or(SpatialVector::ZERO, |parent| links[parent].velocity_change);
+
+        let data = &mut links[i];
+        let joint_velocity_change = data.inverse_joint_inertia
+            * (data.joint_impulse - project(&data.inertia_subspace, parent_velocity_change));
+
+        data.velocity_change =
+            parent_velocity_change + combine(&data.subspace, joint_velocity_change);
+        data.joint_velocity += joint_velocity_change;
+    }
+}
+
+/// Keeps the joint coordinates within their limits by applying impulses to the joints.
+fn apply_joint_limits(links: &mut [LinkData], delta_secs: Scalar) {
+    for _ in 0..LIMIT_ITERATIONS {
+        let mut any_violated = false;
+        for data in links.iter_mut() {
+            data.limit_impulse = data.compute_limit_impulse(delta_secs);
+            any_violated |= data.limit_impulse != Vector3::ZERO;
+        }
+
+        if !any_violated {
+            break;
+        }
+
+        apply_joint_impulses(links);
+    }
+}
+
+/// Integrates the joint coordinates using the joint velocities.
+fn integrate_joint_positions(links: &mut [LinkData], delta_secs: Scalar) {
+    for data in links.iter_mut() {
+        let link = &mut data.link;
+        match link.joint_type {
+            LinkJointType::Revolute | LinkJointType::Prismatic => {
+                link.velocity = data.joint_velocity.x;
+                link.position += link.velocity * delta_secs;
+            }
+            #[cfg(feature = "3d")]
+            LinkJointType::Spherical => {
+                let velocity = data.joint_velocity;
+                let rotation = link.spherical_rotation;
+                let delta_rotation =
+                    Quaternion::from_xyzw(velocity.x, velocity.y, velocity.z, 0.0) * rotation;
+                link.spherical_velocity = velocity;
+                link.spherical_rotation =
+                    (rotation + delta_rotation * (0.5 * delta_secs)).normalize();
+            }
+        }
+    }
+}
+
+/// Returns the roots of the articulations and the links of each articulation,
+/// ordered so that parents come before their children.
+fn sort_articulations(
+    links: &Query<(&mut ArticulationLink, RigidBodyQuery)>,
+) -> Vec<(Entity, Vec<Entity>)> {
+    let parents: HashMap<Entity, Entity> = links
+        .iter()
+        .map(|(link, body)| (body.entity, link.parent))
+        .collect();
+
+    let mut sorted = Vec::with_capacity(parents.len());
+    'links: for &entity in parents.keys() {
+        let mut root = entity;
+        let mut depth = 0;
+        while let Some(&parent) = parents.get(&root) {
+            root = parent;
+            depth += 1;
+            if depth > parents.len() {
+                // The links form a loop.
+                continue 'links;
+            }
+        }
+        sorted.push((root, depth, entity));
+    }
+    sorted.sort_unstable();
+
+    let mut articulations: Vec<(Entity, Vec<Entity>)> = vec![];
+    for (root, _, entity) in sorted {
+        match articulations.last_mut() {
+            Some((previous_root, entities)) if *previous_root == root => entities.push(entity),
+            _ => articulations.push((root, vec![entity])),
+        }
+    }
+    articulations
+}
+
+type RootQueryData = (
+    &'static Position,
+    &'static Rotation,
+    &'static PreviousRotation,
+    &'static AccumulatedTranslation,
+    &'static CenterOfMass,
+    &'static LinearVelocity,
+    &'static AngularVelocity,
+);
+
+/// Simulates [articulations](self) using the Articulated Body Algorithm,
+/// and writes the resulting poses and velocities of the links to the bodies.
+pub fn solve_articulations(
+    mut commands: Commands,
+    mut links: Query<(&mut ArticulationLink, RigidBodyQuery)>,
+    roots: Query<RootQueryData, Without<ArticulationLink>>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_seconds_adjusted();
+    if delta_secs <= 0.0 {
+        return;
+    }
+
+    for (root, entities) in sort_articulations(&links) {
+        let Ok((
+            position,
+            rotation,
+            previous_rotation,
+            translation,
+            center_of_mass,
+            lin_vel,
+            ang_vel,
+        )) = roots.get(root)
+        else {
+            continue;
+        };
+
+        // Skip articulations whose links are all sleeping.
+        if entities
+            .iter()
+            .all(|&entity| links.get(entity).map_or(true, |(_, body)| body.is_sleeping))
+        {
+            continue;
+        }
+
+        // Spatial quantities are expressed at the center of mass of the root.
+        let reference = position.0 + previous_rotation.0 * center_of_mass.0 + translation.0;
+        let root = RootState {
+            origin: reference - *rotation * center_of_mass.0,
+            rotation: *rotation,
+            velocity: SpatialVector::new(ang_vel.0, lin_vel.0),
+        };
+
+        let mut data: Vec<LinkData> = Vec::with_capacity(entities.len());
+        let mut indices = HashMap::with_capacity(entities.len());
+
+        for &entity in &entities {
+            let Ok((link, body)) = links.get(entity) else {
+                continue;
+            };
+            let mut link = *link;
+
+            // Use the initial pose of the link as the rest pose.
+            if link.rest_rotation.is_none() {
+                let parent_rotation = links
+                    .get(link.parent)
+                    .map_or(root.rotation, |(_, parent)| *parent.rotation);
+                let joint_rotation = mul_rotations(parent_rotation, link.joint_rotation());
+                link.rest_rotation = Some(mul_rotations(joint_rotation.inverse(), *body.rotation));
+                link.previous_linear_velocity = body.linear_velocity.0;
+                link.previous_angular_velocity = *body.angular_velocity;
+            }
+
+            indices.insert(entity, data.len());
+            data.push(LinkData {
+                entity,
+                parent: indices.get(&link.parent).copied(),
+                joint_velocity: link.joint_velocity(),
+                link,
+                mass: body.mass.0,
+                local_inertia: *body.inertia,
+                local_center_of_mass: body.center_of_mass.0,
+                origin: Vector::ZERO,
+                rotation: Rotation::default(),
+                center_of_mass: Vector::ZERO,
+                subspace: [SpatialVector::ZERO; 3],
+                velocity: SpatialVector::ZERO,
+                external_impulse: SpatialVector::ZERO,
+                bias_acceleration: SpatialVector::ZERO,
+                articulated_inertia: SpatialMatrix::ZERO,
+                bias_force: SpatialVector::ZERO,
+                inertia_subspace: [SpatialVector::ZERO; 3],
+                inverse_joint_inertia: Matrix3::IDENTITY,
+                joint_force: Vector3::ZERO,
+                acceleration: SpatialVector::ZERO,
+                limit_impulse: Vector3::ZERO,
+                impulse_force: SpatialVector::ZERO,
+                joint_impulse: Vector3::ZERO,
+                velocity_change: SpatialVector::ZERO,
+            });
+        }
+
+        update_kinematics(&mut data, &root, reference);
+
+        // The velocity changes caused by gravity, external forces and contacts since the previous substep
+        // are applied to the articulation as external impulses.
+        for link_data in data.iter_mut() {
+            let Ok((_, body)) = links.get(link_data.entity) else {
+                continue;
+            };
+            let link = &link_data.link;
+
+            let linear_change = body.linear_velocity.0 - link.previous_linear_velocity;
+            #[allow(unused_mut)]
+            let mut angular_change = body.angular_velocity.0 - link.previous_angular_velocity.0;
+
+            // The integrator has applied gyroscopic motion to the angular velocity,
+            // but it is already accounted for by the bias forces of the articulation.
+            #[cfg(feature = "3d")]
+            if !body.is_sleeping {
+                angular_change -=
+                    dynamics::integrator::semi_implicit_euler::solve_gyroscopic_torque(
+                        link.previous_angular_velocity.0,
+                        link_data.rotation.0,
+                        link_data.local_inertia,
+                        delta_secs,
+                    );
+            }
+
+            let offset = link_data.center_of_mass - reference;
+            let linear_impulse = link_data.mass * linear_change;
+            #[cfg(feature = "2d")]
+            let angular_impulse =
+                link_data.local_inertia.0 * angular_change + offset.perp_dot(linear_impulse);
+            #[cfg(feature = "3d")]
+            let angular_impulse = link_data.local_inertia.rotated(&link_data.rotation).0
+                * angular_change
+                + offset.cross(linear_impulse);
+
+            link_data.external_impulse = SpatialVector::new(angular_impulse, linear_impulse);
+        }
+
+        solve_joint_accelerations(&mut data, reference, delta_secs);
+        apply_joint_limits(&mut data, delta_secs);
+        integrate_joint_positions(&mut data, delta_secs);
+        update_kinematics(&mut data, &root, reference);
+
+        // Write the new poses and velocities to the bodies.
+        for link_data in data.iter_mut() {
+            let Ok((mut link, mut body)) = links.get_mut(link_data.entity) else {
+                continue;
+            };
+
+            let offset = link_data.center_of_mass - reference;
+            let angular_velocity = link_data.velocity.angular();
+            #[cfg(feature = "2d")]
+            let linear_velocity = link_data.velocity.linear() + angular_velocity * offset.perp();
+            #[cfg(feature = "3d")]
+            let linear_velocity = link_data.velocity.linear() + angular_velocity.cross(offset);
+
+            *body.rotation = link_data.rotation;
+            body.accumulated_translation.0 = link_data.center_of_mass
+                - body.position.0
+                - body.previous_rotation.0 * link_data.local_center_of_mass;
+            body.linear_velocity.0 = linear_velocity;
+            body.angular_velocity.0 = angular_velocity;
+
+            if body.is_sleeping {
+                body.time_sleeping.0 = 0.0;
+                commands.entity(body.entity).remove::<Sleeping>();
+            }
+
+            link_data.link.previous_linear_velocity = linear_velocity;
+            link_data.link.previous_angular_velocity = AngularVelocity(angular_velocity);
+            *link = link_data.link;
+        }
+    }
+}
+
+/// Updates the [`JointCollisionPairs`] for articulation links that were added, changed or removed.
+pub fn update_articulation_collision_pairs(
+    links: Query<(Entity, &ArticulationLink), Changed<ArticulationLink>>,
+    mut removed_links: RemovedComponents<ArticulationLink>,
+    mut collision_pairs: ResMut<JointCollisionPairs>,
+) {
+    for entity in removed_links.read() {
+        collision_pairs.remove(entity);
+    }
+
+    for (entity, link) in &links {
+        if link.collisions_disabled {
+            collision_pairs.insert(entity, link.parent, entity);
+        } else {
+            collision_pairs.remove(entity);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+//! Spatial vector algebra used by the [articulation](super) solver.
+//!
+//! Spatial vectors combine an angular and a linear part into a single vector.
+//! All spatial quantities of an articulation are expressed at a shared reference point
+//! with world-space axes, so they can be added together without transforming them between links.
+
+use crate::prelude::*;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+/// The number of components in a spatial vector.
+#[cfg(feature = "2d")]
+pub(crate) const SPATIAL_DIM: usize = 3;
+/// The number of components in a spatial vector.
+#[cfg(feature = "3d")]
+pub(crate) const SPATIAL_DIM: usize = 6;
+
+#[cfg(feature = "2d")]
+pub(crate) type AngularPart = Scalar;
+#[cfg(feature = "3d")]
+pub(crate) type AngularPart = Vector;
+
+/// A spatial motion or force vector, with the angular part first, followed by the linear part.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct SpatialVector(pub [Scalar; SPATIAL_DIM]);
+
+impl SpatialVector {
+    pub const ZERO: Self = Self([0.0; SPATIAL_DIM]);
+
+    #[cfg(feature = "2d")]
+    pub fn new(angular: Scalar, linear: Vector) -> Self {
+        Self([angular, linear.x, linear.y])
+    }
+
+    #[cfg(feature = "3d")]
+    pub fn new(angular: Vector, linear: Vector) -> Self {
+        Self([
+            angular.x, angular.y, angular.z, linear.x, linear.y, linear.z,
+        ])
+    }
+
+    #[cfg(feature = "2d")]
+    pub fn angular(&self) -> AngularPart {
+        self.0[0]
+    }
+
+    #[cfg(feature = "3d")]
+    pub fn angular(&self) -> AngularPart {
+        Vector::new(self.0[0], self.0[1], self.0[2])
+    }
+
+    #[cfg(feature = "2d")]
+    pub fn linear(&self) -> Vector {
+        Vector::new(self.0[1], self.0[2])
+    }
+
+    #[cfg(feature = "3d")]
+    pub fn linear(&self) -> Vector {
+        Vector::new(self.0[3], self.0[4], self.0[5])
+    }
+
+    pub fn dot(&self, other: Self) -> Scalar {
+        self.0.iter().zip(other.0).map(|(a, b)| a * b).sum()
+    }
+
+    /// Computes the spatial cross product of this motion vector and the given motion vector.
+    pub fn cross_motion(&self, motion: Self) -> Self {
+        let (w, v) = (self.angular(), self.linear());
+        let (mw, mv) = (motion.angular(), motion.linear());
+        #[cfg(feature = "2d")]
+        {
+            Self::new(0.0, w * mv.perp() - mw * v.perp())
+        }
+        #[cfg(feature = "3d")]
+        {
+            Self::new(w.cross(mw), w.cross(mv) + v.cross(mw))
+        }
+    }
+
+    /// Computes the spatial cross product of this motion vector and the given force vector.
+    pub fn cross_force(&self, force: Self) -> Self {
+        let (w, v) = (self.angular(), self.linear());
+        let fv = force.linear();
+        #[cfg(feature = "2d")]
+        {
+            Self::new(v.perp_dot(fv), w * fv.perp())
+        }
+        #[cfg(feature = "3d")]
+        {
+            Self::new(w.cross(force.angular()) + v.cross(fv), w.cross(fv))
+        }
+    }
+}
+
+impl Add for SpatialVector {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for SpatialVector {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a += b);
+    }
+}
+
+impl Sub for SpatialVector {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl SubAssign for SpatialVector {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl Mul<Scalar> for SpatialVector {
+    type Output = Self;
+
+    fn mul(self, rhs: Scalar) -> Self {
+        Self(self.0.map(|a| a * rhs))
+    }
+}
+
+/// A row-major spatial matrix, used for spatial inertias.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct SpatialMatrix(pub [[Scalar; SPATIAL_DIM]; SPATIAL_DIM]);
+
+impl SpatialMatrix {
+    pub const ZERO: Self = Self([[0.0; SPATIAL_DIM]; SPATIAL_DIM]);
+
+    /// Computes the spatial inertia of a rigid body with the given `mass` and world-space
+    /// `inertia` tensor at its center of mass, located at `center_of_mass` relative to the reference point.
+    #[cfg(feature = "2d")]
+    pub fn rigid_body(mass: Scalar, inertia: Scalar, center_of_mass: Vector) -> Self {
+        let c = center_of_mass;
+        Self([
+            [inertia + mass * c.length_squared(), -mass * c.y, mass * c.x],
+            [-mass * c.y, mass, 0.0],
+            [mass * c.x, 0.0, mass],
+        ])
+    }
+
+    /// Computes the spatial inertia of a rigid body with the given `mass` and world-space
+    /// `inertia` tensor at its center of mass, located at `center_of_mass` relative to the reference point.
+    #[cfg(feature = "3d")]
+    pub fn rigid_body(mass: Scalar, inertia: Matrix3, center_of_mass: Vector) -> Self {
+        let c_cross = skew_symmetric_mat3(center_of_mass);
+        let blocks = [
+            [
+                inertia + mass * c_cross * c_cross.transpose(),
+                mass * c_cross,
+            ],
+            [mass * c_cross.transpose(), Matrix3::IDENTITY * mass],
+        ];
+
+        let mut matrix = Self::ZERO;
+        for (row, value) in matrix.0.iter_mut().enumerate() {
+            for (col, value) in value.iter_mut().enumerate() {
+                *value = blocks[row / 3][col / 3].col(col % 3)[row % 3];
+            }
+        }
+        matrix
+    }
+
+    /// Computes the outer product `a * b^T`.
+    pub fn outer(a: SpatialVector, b: SpatialVector) -> Self {
+        Self(a.0.map(|a| b.0.map(|b| a * b)))
+    }
+}
+
+impl Mul<SpatialVector> for SpatialMatrix {
+    type Output = SpatialVector;
+
+    fn mul(self, rhs: SpatialVector) -> SpatialVector {
+        SpatialVector(self.0.map(|row| SpatialVector(row).dot(rhs)))
+    }
+}
+
+impl Add for SpatialMatrix {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for SpatialMatrix {
+    fn add_assign(&mut self, rhs: Self) {
+        for (row, rhs_row) in self.0.iter_mut().zip(rhs.0) {
+            row.iter_mut().zip(rhs_row).for_each(|(a, b)| *a += b);
+        }
+    }
+}
+
+impl Sub for SpatialMatrix {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl SubAssign for SpatialMatrix {
+    fn sub_assign(&mut self, rhs: Self) {
+        for (row, rhs_row) in self.0.iter_mut().zip(rhs.0) {
+            row.iter_mut().zip(rhs_row).for_each(|(a, b)| *a -= b);
+        }
+    }
+}
+
+impl Mul<Scalar> for SpatialMatrix {
+    type Output = Self;
+
+    fn mul(self, rhs: Scalar) -> Self {
+        Self(self.0.map(|row| row.map(|a| a * rhs)))
+    }
+}
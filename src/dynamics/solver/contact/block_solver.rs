@@ -0,0 +1,352 @@
+//! A block solver that solves the normal impulses of all points in a contact manifold simultaneously.
+//!
+//! Solving the points one by one makes the impulses of the points fight each other,
+//! which can make bodies resting on each other rock slightly. The block solver instead
+//! solves the points as a single *linear complementarity problem* (LCP), which finds impulses
+//! that satisfy all of the points at once.
+//!
+//! See [`SolverConfig::use_block_solver`](crate::dynamics::solver::SolverConfig::use_block_solver).
+
+use super::ContactConstraint;
+use crate::prelude::*;
+use bevy::utils::default;
+
+/// The maximum number of points in a contact manifold that can be solved with the block solver.
+///
+/// In 2D, this covers manifolds with two points, such as a box resting on another box.
+#[cfg(feature = "2d")]
+pub const MAX_BLOCK_POINTS: usize = 2;
+
+/// The maximum number of points in a contact manifold that can be solved with the block solver.
+///
+/// In 3D, this covers manifolds with two or three points, such as a cylinder lying on its side.
+///
+/// The points of a manifold share the same normal and lie on the same plane, so their normal constraints
+/// can only remove three degrees of freedom: the relative translation along the normal and the two rotations
+/// around the tangent directions. With four or more points, such as the corners of a box resting on another box,
+/// the points are redundant and the impulses have no unique solution. Distributing them arbitrarily between
+/// the points makes the box rock more than with the sequential solver, so these manifolds are solved sequentially.
+#[cfg(feature = "3d")]
+pub const MAX_BLOCK_POINTS: usize = 3;
+
+type BlockVector = [Scalar; MAX_BLOCK_POINTS];
+type BlockMatrix = [BlockVector; MAX_BLOCK_POINTS];
+
+impl ContactConstraint {
+    /// Solves the normal impulses of all contact points simultaneously, updating the total impulses
+    /// and applying the incremental impulses to the given bodies.
+    ///
+    /// Returns `false` if the manifold has too few or too many points, if the normal response of any point
+    /// is scaled by [`ContactData::normal_impulse_scale`], or if no solution was found.
+    /// In this case, nothing is applied, and the points should be solved sequentially instead.
+    pub(super) fn solve_normal_block(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        delta_secs: Scalar,
+        use_bias: bool,
+        max_overlap_solve_speed: Scalar,
+    ) -> bool {
+        let count = self.points.len();
+
+        if !(2..=MAX_BLOCK_POINTS).contains(&count) {
+            return false;
+        }
+
+        // The scale only applies to the response of each point to its own impulse,
+        // so scaled points can't be coupled consistently with the other points.
+        if self
+            .points
+            .iter()
+            .any(|point| point.normal_impulse_scale != 1.0)
+        {
+            return false;
+        }
+
+        let inv_mass1 = body1.effective_inv_mass();
+        let inv_mass2 = body2.effective_inv_mass();
+        let inv_inertia1 = body1.effective_world_inv_inertia();
+        let inv_inertia2 = body2.effective_world_inv_inertia();
+
+        let is_affected1 = body1.rb.is_dynamic() && body1.dominance() <= body2.dominance();
+        let is_affected2 = body2.rb.is_dynamic() && body2.dominance() <= body1.dominance();
+
+        let delta_translation = body2.accumulated_translation.0 - body1.accumulated_translation.0;
+
+        // The angular parts of the Jacobians of each point.
+        let mut r1_cross_n: [_; MAX_BLOCK_POINTS] = default();
+        let mut r2_cross_n: [_; MAX_BLOCK_POINTS] = default();
+
+        for (i, point) in self.points.iter().enumerate() {
+            r1_cross_n[i] = cross(point.anchor1, self.normal);
+            r2_cross_n[i] = cross(point.anchor2, self.normal);
+        }
+
+        // The effective inverse mass coupling the normal impulses of two points.
+        // See `ContactNormalPart::generate` for the derivation of the diagonal elements.
+        let coupling = |i: usize, j: usize| {
+            let mut k = 0.0;
+            if is_affected1 {
+                k += (inv_mass1 * self.normal).dot(self.normal);
+                #[cfg(feature = "2d")]
+                {
+                    k += inv_inertia1 * r1_cross_n[i] * r1_cross_n[j];
+                }
+                #[cfg(feature = "3d")]
+                {
+                    k += r1_cross_n[i].dot(inv_inertia1 * r1_cross_n[j]);
+                }
+            }
+            if is_affected2 {
+                k += (inv_mass2 * self.normal).dot(self.normal);
+                #[cfg(feature = "2d")]
+                {
+                    k += inv_inertia2 * r2_cross_n[i] * r2_cross_n[j];
+                }
+                #[cfg(feature = "3d")]
+                {
+                    k += r2_cross_n[i].dot(inv_inertia2 * r2_cross_n[j]);
+                }
+            }
+            k
+        };
+
+        // Build the LCP `w = A * x + b`, where `x` is the vector of the new total normal impulses,
+        // and `w` is the vector of the resulting (biased) normal speeds.
+        //
+        // For a single point, the sequential solver computes the incremental impulse as
+        //
+        // Δx = -mass_scale * m * (v + bias) - impulse_scale * x
+        //
+        // where `m` is the effective mass. This is the solution of `(k / mass_scale) * Δx = -(v + bias) - (impulse_scale / mass_scale) * k * x`,
+        // where `k = 1 / m`. The block version uses the same diagonal, but also takes into account
+        // how the impulse of each point affects the speed of the other points.
+        let mut a: BlockMatrix = default();
+        let mut b: BlockVector = default();
+        let mut impulses: BlockVector = default();
+
+        for (i, point) in self.points.iter().enumerate() {
+            let part = &point.normal_part;
+
+            if part.effective_mass <= 0.0 {
+                return false;
+            }
+
+            let r1 = *body1.rotation * point.local_anchor1;
+            let r2 = *body2.rotation * point.local_anchor2;
+
+            // TODO: Consider rotation delta for anchors
+            let delta_separation = delta_translation + (r2 - r1);
+            let separation = delta_separation.dot(self.normal) + point.initial_separation;
+
+            // Relative velocity along the normal at the contact point
+            let relative_velocity =
+                body2.velocity_at_point(point.anchor2) - body1.velocity_at_point(point.anchor1);
+            let normal_speed = relative_velocity.dot(self.normal);
+
+            // Match the three cases of `ContactNormalPart::solve_impulse`.
+            let (bias, mass_scale, impulse_scale) = if separation > 0.0 {
+                (separation / delta_secs, 1.0, 0.0)
+            } else if use_bias {
+                (
+                    (part.softness.bias * separation).max(-max_overlap_solve_speed),
+                    part.softness.mass_scale,
+                    part.softness.impulse_scale,
+                )
+            } else {
+                (0.0, 1.0, 0.0)
+            };
+
+            if mass_scale <= 0.0 {
+                return false;
+            }
+
+            let k = part.effective_mass.recip();
+
+            a[i][i] = k / mass_scale;
+
+            b[i] = normal_speed + bias + impulse_scale / mass_scale * k * part.impulse;
+            impulses[i] = part.impulse;
+        }
+
+        // Couple the points with each other.
+        for (i, row) in a.iter_mut().enumerate().take(count) {
+            for (j, element) in row.iter_mut().enumerate().take(count) {
+                if i != j {
+                    *element = coupling(i, j);
+                }
+            }
+        }
+
+        // The current speeds already include the current impulses, so they are subtracted from `b`
+        // to get a problem in terms of the new total impulses.
+        for i in 0..count {
+            for j in 0..count {
+                b[i] -= a[i][j] * impulses[j];
+            }
+        }
+
+        let Some(new_impulses) = solve_lcp(&a, &b, count) else {
+            return false;
+        };
+
+        for (i, point) in self.points.iter_mut().enumerate() {
+            let impulse_magnitude = new_impulses[i] - point.normal_part.impulse;
+            point.normal_part.impulse = new_impulses[i];
+
+            // Store the maximum impulse for restitution.
+            point.max_normal_impulse = impulse_magnitude.max(point.max_normal_impulse);
+
+            if impulse_magnitude == 0.0 {
+                continue;
+            }
+
+            let impulse = impulse_magnitude * self.normal;
+
+            // Apply the impulse.
+            if is_affected1 {
+                body1.linear_velocity.0 -= impulse * inv_mass1;
+                body1.angular_velocity.0 -= inv_inertia1 * cross(point.anchor1, impulse);
+            }
+            if is_affected2 {
+                body2.linear_velocity.0 += impulse * inv_mass2;
+                body2.angular_velocity.0 += inv_inertia2 * cross(point.anchor2, impulse);
+            }
+        }
+
+        true
+    }
+}
+
+/// Solves the linear complementarity problem (LCP) for the first `count` rows of `a` and `b`.
+///
+/// The goal is to find `x` such that `w = a * x + b`, `x >= 0`, `w >= 0`, and `x_i * w_i = 0` for each row.
+/// For contacts, this means that each point either has a positive impulse and zero normal speed,
+/// or a zero impulse and a separating normal speed.
+///
+/// The problem is solved by total enumeration, trying each set of active points starting from all points
+/// being active, which is the most common case for resting contact. Returns `None` if no valid solution is found.
+fn solve_lcp(a: &BlockMatrix, b: &BlockVector, count: usize) -> Option<BlockVector> {
+    'sets: for active_set in (0..1_u32 << count).rev() {
+        let Some(x) = solve_active_set(a, b, count, active_set) else {
+            continue;
+        };
+
+        if x.iter().any(|&x| x < 0.0) {
+            continue;
+        }
+
+        // The inactive points must not be approaching each other.
+        for i in (0..count).filter(|i| active_set & (1 << i) == 0) {
+            let w: Scalar = (0..count).map(|j| a[i][j] * x[j]).sum::<Scalar>() + b[i];
+            if w < 0.0 {
+                continue 'sets;
+            }
+        }
+
+        return Some(x);
+    }
+
+    None
+}
+
+/// Solves `a * x + b = 0` for the rows and columns of `a` in the given active set,
+/// with the impulses of all other points being zero.
+///
+/// Uses Gaussian elimination with partial pivoting. Returns `None` if the system is singular,
+/// for example when there are redundant contact points.
+fn solve_active_set(
+    a: &BlockMatrix,
+    b: &BlockVector,
+    count: usize,
+    active_set: u32,
+) -> Option<BlockVector> {
+    let mut indices = [0; MAX_BLOCK_POINTS];
+    let mut n = 0;
+    for i in 0..count {
+        if active_set & (1 << i) != 0 {
+            indices[n] = i;
+            n += 1;
+        }
+    }
+
+    // The augmented matrix of the system for the active points.
+    let mut m: BlockMatrix = default();
+    let mut rhs: BlockVector = default();
+    let mut scale: Scalar = 0.0;
+    for (row, &i) in indices[..n].iter().enumerate() {
+        for (col, &j) in indices[..n].iter().enumerate() {
+            m[row][col] = a[i][j];
+        }
+        rhs[row] = -b[i];
+        scale = scale.max(a[i][i].abs());
+    }
+
+    let tolerance = Scalar::EPSILON.sqrt() * scale;
+
+    for col in 0..n {
+        // Find the pivot.
+        let pivot_row =
+            (col..n).max_by(|&r1, &r2| m[r1][col].abs().total_cmp(&m[r2][col].abs()))?;
+        if m[pivot_row][col].abs() <= tolerance {
+            return None;
+        }
+        m.swap(col, pivot_row);
+        rhs.swap(col, pivot_row);
+
+        // Eliminate the column from the rows below.
+        let pivot = m[col];
+        for row in col + 1..n {
+            let factor = m[row][col] / pivot[col];
+            for (element, pivot_element) in m[row][col..n].iter_mut().zip(&pivot[col..n]) {
+                *element -= factor * pivot_element;
+            }
+            rhs[row] -= factor * rhs[col];
+        }
+    }
+
+    // Back substitution.
+    let mut solution: BlockVector = default();
+    for row in (0..n).rev() {
+        let sum: Scalar = (row + 1..n).map(|k| m[row][k] * solution[k]).sum();
+        solution[row] = (rhs[row] - sum) / m[row][row];
+    }
+
+    let mut x: BlockVector = default();
+    for (row, &i) in indices[..n].iter().enumerate() {
+        x[i] = solution[row];
+    }
+
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_vector(values: &[Scalar]) -> BlockVector {
+        let mut vector = BlockVector::default();
+        vector[..values.len()].copy_from_slice(values);
+        vector
+    }
+
+    #[test]
+    fn lcp_finds_active_and_separating_points() {
+        let mut a = BlockMatrix::default();
+        a[0][..2].copy_from_slice(&[2.0, 1.0]);
+        a[1][..2].copy_from_slice(&[1.0, 2.0]);
+
+        // Both points are approaching, so both are active and their speeds are driven to zero.
+        let x = solve_lcp(&a, &block_vector(&[-3.0, -3.0]), 2).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-6 && (x[1] - 1.0).abs() < 1e-6);
+
+        // The second point is separating fast enough to stay separated
+        // even with the impulse of the first point.
+        let x = solve_lcp(&a, &block_vector(&[-2.0, 3.0]), 2).unwrap();
+        assert!((x[0] - 1.0).abs() < 1e-6 && x[1] == 0.0);
+
+        // Both points are separating.
+        let x = solve_lcp(&a, &block_vector(&[1.0, 1.0]), 2).unwrap();
+        assert_eq!(x, BlockVector::default());
+    }
+}
@@ -1,9 +1,13 @@
 //! Constraints and other types used for solving contacts.
 
+mod block_solver;
 mod normal_part;
+mod rolling_part;
 mod tangent_part;
 
+pub use block_solver::MAX_BLOCK_POINTS;
 pub use normal_part::ContactNormalPart;
+pub use rolling_part::ContactRollingPart;
 pub use tangent_part::ContactTangentPart;
 
 use crate::{dynamics::solver::softness_parameters::SoftnessCoefficients, prelude::*};
@@ -51,6 +55,27 @@ pub struct ContactConstraintPoint {
     ///
     /// A negative separation indicates penetration.
     pub initial_separation: Scalar,
+
+    /// The coefficient of dynamic friction used for the contact point.
+    ///
+    /// With [`AnisotropicFriction`], this is the coefficient along the primary friction direction.
+    pub friction: Scalar,
+
+    /// The coefficient of dynamic friction used for the contact point along the second tangent direction.
+    ///
+    /// This is only different from `friction` with [`AnisotropicFriction`].
+    #[cfg(feature = "3d")]
+    pub secondary_friction: Scalar,
+
+    /// The coefficient of restitution used for the contact point.
+    pub restitution: Scalar,
+
+    /// The scale of the response along the contact normal, already applied to the effective mass
+    /// of the `normal_part`. See [`ContactData::normal_impulse_scale`].
+    pub normal_impulse_scale: Scalar,
+
+    /// The index of the [`ContactData`] in the [`ContactManifold`] that the point was generated from.
+    pub contact_index: usize,
 }
 
 /// A contact constraint used for resolving inter-penetration between two bodies.
@@ -68,13 +93,27 @@ pub struct ContactConstraint {
     /// The entity of the first collider in the contact.
     pub collider_entity2: Entity,
     /// The combined [`Friction`] of the bodies.
+    ///
+    /// This can be overridden for individual points, see [`ContactConstraintPoint::friction`].
     pub friction: Friction,
     /// The combined [`Restitution`] of the bodies.
+    ///
+    /// This can be overridden for individual points, see [`ContactConstraintPoint::restitution`].
     pub restitution: Restitution,
     /// The world-space contact normal shared by all points in the contact manifold.
     pub normal: Vector,
+    /// The world-space direction of the primary friction coefficient on the contact plane
+    /// for [`AnisotropicFriction`]. The first tangent direction is aligned with it.
+    ///
+    /// `None` if the friction is isotropic.
+    #[cfg(feature = "3d")]
+    pub friction_direction: Option<Vector>,
     /// The contact points in the manifold. Each point shares the same `normal`.
     pub points: Vec<ContactConstraintPoint>,
+    /// The rolling and spinning friction part of the contact constraint, shared by all points.
+    ///
+    /// `None` if the coefficients of rolling and spinning friction are zero.
+    pub rolling_part: Option<ContactRollingPart>,
     /// The index of the [`ContactManifold`] in the [`Contacts`] stored for the two bodies.
     pub manifold_index: usize,
 }
@@ -94,7 +133,10 @@ impl ContactConstraint {
         collision_margin: impl Into<CollisionMargin>,
         speculative_margin: impl Into<SpeculativeMargin>,
         friction: Friction,
+        anisotropic_friction: Option<(Vector, Friction)>,
         restitution: Restitution,
+        surface_velocity1: Option<SurfaceVelocity>,
+        surface_velocity2: Option<SurfaceVelocity>,
         softness: SoftnessCoefficients,
         warm_start: bool,
         delta_secs: Scalar,
@@ -125,20 +167,70 @@ impl ContactConstraint {
             friction,
             restitution,
             normal,
+            #[cfg(feature = "3d")]
+            friction_direction: None,
             points: Vec::with_capacity(manifold.contacts.len()),
+            rolling_part: None,
             manifold_index: manifold_id,
         };
 
-        let tangents =
-            constraint.tangent_directions(body1.linear_velocity.0, body2.linear_velocity.0);
+        // The combined coefficients of dynamic friction along the tangent directions.
+        let mut tangent_friction = [friction.dynamic_coefficient; DIM - 1];
+
+        if let Some((direction, secondary_friction)) = anisotropic_friction {
+            let primary = friction.dynamic_coefficient;
+            let secondary = secondary_friction.dynamic_coefficient;
+
+            #[cfg(feature = "2d")]
+            {
+                // There is only one tangent direction in 2D, so the coefficients
+                // are interpolated based on the angle between the tangent and the primary direction.
+                let tangent = Vector::new(normal.y, -normal.x);
+                tangent_friction[0] = AnisotropicFriction::interpolate_coefficient(
+                    primary,
+                    secondary,
+                    tangent.dot(direction),
+                );
+            }
+
+            #[cfg(feature = "3d")]
+            {
+                // Project the primary direction onto the contact plane.
+                // If it is parallel to the normal, all sliding is perpendicular to it.
+                if let Some(direction) =
+                    (direction - direction.dot(normal) * normal).try_normalize()
+                {
+                    constraint.friction_direction = Some(direction);
+                    tangent_friction = [primary, secondary];
+                } else {
+                    tangent_friction = [secondary; 2];
+                }
+            }
+        }
+
+        let tangents = constraint.tangent_directions();
+
+        for (contact_index, mut contact) in manifold.contacts.iter().copied().enumerate() {
+            // Skip contact points disabled by contact modification hooks.
+            if !contact.enabled {
+                continue;
+            }
+
+            // The target relative velocity of the surfaces. The surface velocities are computed
+            // in collider-space, and transformed to world-space along with the contact points.
+            let mut surface_velocity1 =
+                surface_velocity1.map_or(Vector::ZERO, |v| v.velocity_at_point(contact.point1));
+            let mut surface_velocity2 =
+                surface_velocity2.map_or(Vector::ZERO, |v| v.velocity_at_point(contact.point2));
 
-        for mut contact in manifold.contacts.iter().copied() {
             // Transform contact points from collider-space to body-space.
             if let Some(transform) = collider_transform1 {
                 contact.point1 = transform.rotation * contact.point1 + transform.translation;
+                surface_velocity1 = transform.rotation * surface_velocity1;
             }
             if let Some(transform) = collider_transform2 {
                 contact.point2 = transform.rotation * contact.point2 + transform.translation;
+                surface_velocity2 = transform.rotation * surface_velocity2;
             }
 
             contact.penetration += collision_margin;
@@ -153,6 +245,11 @@ impl ContactConstraint {
             let r1 = *body1.rotation * local_anchor1;
             let r2 = *body2.rotation * local_anchor2;
 
+            // A moving surface drags the other body along with it, so the target velocity
+            // of the second body relative to the first one is the difference of the surface velocities.
+            let target_velocity = contact.tangent_velocity + *body1.rotation * surface_velocity1
+                - *body2.rotation * surface_velocity2;
+
             // Relative velocity at the contact point.
             let relative_velocity = body2.velocity_at_point(r2) - body1.velocity_at_point(r1);
 
@@ -167,30 +264,45 @@ impl ContactConstraint {
                 continue;
             }
 
+            // TODO: Apply warm starting scale here instead of in `warm_start`?
+            let mut normal_part = ContactNormalPart::generate(
+                inverse_mass_sum,
+                i1,
+                i2,
+                r1,
+                r2,
+                normal,
+                warm_start.then_some(contact.normal_impulse),
+                softness,
+            );
+
+            // Scale the response along the normal.
+            normal_part.effective_mass *= contact.normal_impulse_scale;
+
+            // Per-point overrides for the combined coefficients.
+            let point_friction = contact.friction.unwrap_or(tangent_friction[0]);
+            #[cfg(feature = "3d")]
+            let point_secondary_friction = contact.friction.unwrap_or(tangent_friction[1]);
+
+            #[cfg(feature = "2d")]
+            let has_friction = point_friction > 0.0;
+            #[cfg(feature = "3d")]
+            let has_friction = point_friction > 0.0 || point_secondary_friction > 0.0;
+            let point_restitution = contact.restitution.unwrap_or(restitution.coefficient);
+
             let point = ContactConstraintPoint {
-                // TODO: Apply warm starting scale here instead of in `warm_start`?
-                normal_part: ContactNormalPart::generate(
+                normal_part,
+                // There should only be a friction part if the coefficient of friction is non-negative.
+                tangent_part: has_friction.then_some(ContactTangentPart::generate(
                     inverse_mass_sum,
                     i1,
                     i2,
                     r1,
                     r2,
-                    normal,
-                    warm_start.then_some(contact.normal_impulse),
-                    softness,
-                ),
-                // There should only be a friction part if the coefficient of friction is non-negative.
-                tangent_part: (friction.dynamic_coefficient > 0.0).then_some(
-                    ContactTangentPart::generate(
-                        inverse_mass_sum,
-                        i1,
-                        i2,
-                        r1,
-                        r2,
-                        tangents,
-                        warm_start.then_some(contact.tangent_impulse),
-                    ),
-                ),
+                    tangents,
+                    target_velocity - target_velocity.dot(normal) * normal,
+                    warm_start.then_some(contact.tangent_impulse),
+                )),
                 max_normal_impulse: 0.0,
                 local_anchor1,
                 local_anchor2,
@@ -198,11 +310,30 @@ impl ContactConstraint {
                 anchor2: r2,
                 normal_speed: normal.dot(relative_velocity),
                 initial_separation: -contact.penetration - (r2 - r1).dot(normal),
+                friction: point_friction,
+                #[cfg(feature = "3d")]
+                secondary_friction: point_secondary_friction,
+                restitution: point_restitution,
+                normal_impulse_scale: contact.normal_impulse_scale,
+                contact_index,
             };
 
             constraint.points.push(point);
         }
 
+        #[cfg(feature = "2d")]
+        let has_rolling_friction = friction.rolling_coefficient > 0.0;
+        #[cfg(feature = "3d")]
+        let has_rolling_friction =
+            friction.rolling_coefficient > 0.0 || friction.spinning_coefficient > 0.0;
+
+        // There should only be a rolling part if the coefficient of rolling or spinning friction is non-zero.
+        if has_rolling_friction && !constraint.points.is_empty() {
+            constraint.rolling_part = Some(ContactRollingPart::generate(
+                i1, i2, normal, tangents, friction,
+            ));
+        }
+
         constraint
     }
 
@@ -248,9 +379,24 @@ impl ContactConstraint {
                 body2.angular_velocity.0 += inv_inertia2 * cross(r2, p);
             }
         }
+
+        if let Some(ref rolling_part) = self.rolling_part {
+            let angular_impulse =
+                warm_start_coefficient * rolling_part.total_impulse(normal, tangent_directions);
+
+            if body1.rb.is_dynamic() && body1.dominance() <= body2.dominance() {
+                body1.angular_velocity.0 -= inv_inertia1 * angular_impulse;
+            }
+            if body2.rb.is_dynamic() && body2.dominance() <= body1.dominance() {
+                body2.angular_velocity.0 += inv_inertia2 * angular_impulse;
+            }
+        }
     }
 
     /// Solves the [`ContactConstraint`], applying an impulse to the given bodies.
+    ///
+    /// If `use_block_solver` is `true`, the normal impulses of manifolds with up to [`MAX_BLOCK_POINTS`] points
+    /// are solved simultaneously. See [`SolverConfig::use_block_solver`](crate::dynamics::solver::SolverConfig::use_block_solver).
     pub fn solve(
         &mut self,
         body1: &mut RigidBodyQueryItem,
@@ -258,6 +404,7 @@ impl ContactConstraint {
         delta_secs: Scalar,
         use_bias: bool,
         max_overlap_solve_speed: Scalar,
+        use_block_solver: bool,
     ) {
         let inv_mass1 = body1.effective_inv_mass();
         let inv_mass2 = body2.effective_inv_mass();
@@ -266,55 +413,58 @@ impl ContactConstraint {
 
         let delta_translation = body2.accumulated_translation.0 - body1.accumulated_translation.0;
 
-        // Normal impulses
-        for point in self.points.iter_mut() {
-            let r1 = *body1.rotation * point.local_anchor1;
-            let r2 = *body2.rotation * point.local_anchor2;
-
-            // TODO: Consider rotation delta for anchors
-            let delta_separation = delta_translation + (r2 - r1);
-            let separation = delta_separation.dot(self.normal) + point.initial_separation;
-
-            // Fixed anchors
-            let r1 = point.anchor1;
-            let r2 = point.anchor2;
-
-            // Relative velocity at contact point
-            let relative_velocity = body2.velocity_at_point(r2) - body1.velocity_at_point(r1);
-
-            // Compute the incremental impulse. The clamping and impulse accumulation is handled by the method.
-            let impulse_magnitude = point.normal_part.solve_impulse(
-                separation,
-                relative_velocity,
-                self.normal,
-                use_bias,
-                max_overlap_solve_speed,
-                delta_secs,
-            );
-
-            // Store the maximum impulse for restitution.
-            point.max_normal_impulse = impulse_magnitude.max(point.max_normal_impulse);
-
-            if impulse_magnitude == 0.0 {
-                continue;
-            }
-
-            let impulse = impulse_magnitude * self.normal;
-
-            // Apply the impulse.
-            if body1.rb.is_dynamic() && body1.dominance() <= body2.dominance() {
-                body1.linear_velocity.0 -= impulse * inv_mass1;
-                body1.angular_velocity.0 -= inv_inertia1 * cross(r1, impulse);
-            }
-            if body2.rb.is_dynamic() && body2.dominance() <= body1.dominance() {
-                body2.linear_velocity.0 += impulse * inv_mass2;
-                body2.angular_velocity.0 += inv_inertia2 * cross(r2, impulse);
+        // Normal impulses. With the block solver, all points are solved simultaneously if possible.
+        let solved_as_block = use_block_solver
+            && self.solve_normal_block(body1, body2, delta_secs, use_bias, max_overlap_solve_speed);
+
+        if !solved_as_block {
+            for point in self.points.iter_mut() {
+                let r1 = *body1.rotation * point.local_anchor1;
+                let r2 = *body2.rotation * point.local_anchor2;
+
+                // TODO: Consider rotation delta for anchors
+                let delta_separation = delta_translation + (r2 - r1);
+                let separation = delta_separation.dot(self.normal) + point.initial_separation;
+
+                // Fixed anchors
+                let r1 = point.anchor1;
+                let r2 = point.anchor2;
+
+                // Relative velocity at contact point
+                let relative_velocity = body2.velocity_at_point(r2) - body1.velocity_at_point(r1);
+
+                // Compute the incremental impulse. The clamping and impulse accumulation is handled by the method.
+                let impulse_magnitude = point.normal_part.solve_impulse(
+                    separation,
+                    relative_velocity,
+                    self.normal,
+                    use_bias,
+                    max_overlap_solve_speed,
+                    delta_secs,
+                );
+
+                // Store the maximum impulse for restitution.
+                point.max_normal_impulse = impulse_magnitude.max(point.max_normal_impulse);
+
+                if impulse_magnitude == 0.0 {
+                    continue;
+                }
+
+                let impulse = impulse_magnitude * self.normal;
+
+                // Apply the impulse.
+                if body1.rb.is_dynamic() && body1.dominance() <= body2.dominance() {
+                    body1.linear_velocity.0 -= impulse * inv_mass1;
+                    body1.angular_velocity.0 -= inv_inertia1 * cross(r1, impulse);
+                }
+                if body2.rb.is_dynamic() && body2.dominance() <= body1.dominance() {
+                    body2.linear_velocity.0 += impulse * inv_mass2;
+                    body2.angular_velocity.0 += inv_inertia2 * cross(r2, impulse);
+                }
             }
         }
 
-        let friction = self.friction;
-        let tangent_directions =
-            self.tangent_directions(body1.linear_velocity.0, body2.linear_velocity.0);
+        let tangent_directions = self.tangent_directions();
 
         // Friction
         for point in self.points.iter_mut() {
@@ -333,7 +483,10 @@ impl ContactConstraint {
             let impulse = friction_part.solve_impulse(
                 tangent_directions,
                 relative_velocity,
-                friction,
+                #[cfg(feature = "2d")]
+                [point.friction],
+                #[cfg(feature = "3d")]
+                [point.friction, point.secondary_friction],
                 point.normal_part.impulse,
             );
 
@@ -347,6 +500,31 @@ impl ContactConstraint {
                 body2.angular_velocity.0 += inv_inertia2 * cross(r2, impulse);
             }
         }
+
+        // Rolling and spinning friction
+        if let Some(ref mut rolling_part) = self.rolling_part {
+            let total_normal_impulse: Scalar =
+                self.points.iter().map(|p| p.normal_part.impulse).sum();
+
+            // Relative angular velocity
+            let relative_angular_velocity = body2.angular_velocity.0 - body1.angular_velocity.0;
+
+            // Compute the incremental angular impulse. The clamping and impulse accumulation is handled by the method.
+            let impulse = rolling_part.solve_impulse(
+                self.normal,
+                tangent_directions,
+                relative_angular_velocity,
+                total_normal_impulse,
+            );
+
+            // Apply the angular impulse.
+            if body1.rb.is_dynamic() && body1.dominance() <= body2.dominance() {
+                body1.angular_velocity.0 -= inv_inertia1 * impulse;
+            }
+            if body2.rb.is_dynamic() && body2.dominance() <= body1.dominance() {
+                body2.angular_velocity.0 += inv_inertia2 * impulse;
+            }
+        }
     }
 
     /// Applies [restitution](`Restitution`) for the given bodies if the relative speed
@@ -379,7 +557,7 @@ impl ContactConstraint {
 
             // Compute the incremental normal impulse to account for restitution.
             let mut impulse = -point.normal_part.effective_mass
-                * (normal_speed + self.restitution.coefficient * point.normal_speed);
+                * (normal_speed + point.restitution * point.normal_speed);
 
             // Clamp the accumulated impulse.
             let new_impulse = (point.normal_part.impulse + impulse).max(0.0);
@@ -402,22 +580,23 @@ impl ContactConstraint {
     }
 
     /// Computes `DIM - 1` tangent directions.
-    #[allow(unused_variables)]
-    pub fn tangent_directions(&self, velocity1: Vector, velocity2: Vector) -> [Vector; DIM - 1] {
+    ///
+    /// The directions only depend on the contact normal and the friction direction,
+    /// so they stay the same across substeps.
+    pub fn tangent_directions(&self) -> [Vector; DIM - 1] {
         #[cfg(feature = "2d")]
         {
             [Vector::new(self.normal.y, -self.normal.x)]
         }
         #[cfg(feature = "3d")]
         {
+            // The friction impulse is solved as a single 2D impulse on the tangent plane,
+            // so the basis only needs to be stable across substeps for warm starting.
+            // For anisotropic friction, the first tangent is aligned with the primary friction direction.
             let force_direction = -self.normal;
-            let relative_velocity = velocity1 - velocity2;
-            let tangent_velocity =
-                relative_velocity + force_direction * force_direction.dot(relative_velocity);
-
-            let tangent = tangent_velocity
-                .try_normalize()
-                .unwrap_or(force_direction.any_orthonormal_vector());
+            let tangent = self
+                .friction_direction
+                .unwrap_or_else(|| force_direction.any_orthonormal_vector());
             let bitangent = force_direction.cross(tangent);
             [tangent, bitangent]
         }
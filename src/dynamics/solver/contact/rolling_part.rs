@@ -0,0 +1,182 @@
+use super::tangent_part::TangentImpulse;
+use crate::prelude::*;
+use bevy::reflect::Reflect;
+
+#[cfg(feature = "2d")]
+pub type AngularImpulse = Scalar;
+#[cfg(feature = "3d")]
+pub type AngularImpulse = Vector;
+
+// TODO: One-body constraint version
+/// The rolling and spinning friction part of a [`ContactConstraint`](super::ContactConstraint).
+///
+/// Unlike the normal and tangent parts, this part is shared by all points in the contact manifold.
+/// It consists of angular rows that resist the relative rotation of the bodies
+/// around the contact tangents (rolling friction) and around the contact normal (spinning friction).
+#[derive(Clone, Debug, Default, PartialEq, Reflect)]
+pub struct ContactRollingPart {
+    /// The angular impulse of rolling friction around the contact tangents.
+    pub rolling_impulse: TangentImpulse,
+
+    /// The angular impulse of spinning friction around the contact normal.
+    #[cfg(feature = "3d")]
+    pub spinning_impulse: Scalar,
+
+    /// The coefficient of rolling friction used for the contact manifold.
+    pub rolling_coefficient: Scalar,
+
+    /// The coefficient of spinning friction used for the contact manifold.
+    #[cfg(feature = "3d")]
+    pub spinning_coefficient: Scalar,
+
+    /// The angular inertial properties of the bodies projected onto the contact tangents,
+    /// or in other words, the mass "seen" by the rolling friction rows.
+    pub rolling_effective_mass: [Scalar; DIM - 1],
+
+    /// The angular inertial properties of the bodies projected onto the contact normal,
+    /// or in other words, the mass "seen" by the spinning friction row.
+    #[cfg(feature = "3d")]
+    pub spinning_effective_mass: Scalar,
+}
+
+impl ContactRollingPart {
+    /// Generates a new [`ContactRollingPart`].
+    #[allow(unused_variables)]
+    pub fn generate(
+        inverse_inertia1: impl Into<InverseInertia>,
+        inverse_inertia2: impl Into<InverseInertia>,
+        normal: Vector,
+        tangents: [Vector; DIM - 1],
+        friction: Friction,
+    ) -> Self {
+        let i1 = inverse_inertia1.into().0;
+        let i2 = inverse_inertia2.into().0;
+
+        // The rows only have angular components, so the Jacobian for an axis `a` is the following:
+        //
+        //      linear1  angular1  linear2  angular2
+        // J = [    0,      -a,       0,        a    ]
+        //
+        // The effective inverse mass is then simply:
+        //
+        // K = J * M^-1 * J^T = dot(a, I1 * a) + dot(a, I2 * a)
+
+        #[cfg(feature = "2d")]
+        {
+            Self {
+                rolling_impulse: 0.0,
+                rolling_coefficient: friction.rolling_coefficient,
+                rolling_effective_mass: [(i1 + i2).recip_or_zero()],
+            }
+        }
+
+        #[cfg(feature = "3d")]
+        {
+            let effective_mass =
+                |axis: Vector| (axis.dot(i1 * axis) + axis.dot(i2 * axis)).recip_or_zero();
+
+            Self {
+                rolling_impulse: TangentImpulse::ZERO,
+                spinning_impulse: 0.0,
+                rolling_coefficient: friction.rolling_coefficient,
+                spinning_coefficient: friction.spinning_coefficient,
+                rolling_effective_mass: [effective_mass(tangents[0]), effective_mass(tangents[1])],
+                spinning_effective_mass: effective_mass(normal),
+            }
+        }
+    }
+
+    /// Returns the total angular impulse in world space applied by the rolling and spinning friction.
+    #[allow(unused_variables)]
+    pub fn total_impulse(
+        &self,
+        normal: Vector,
+        tangent_directions: [Vector; DIM - 1],
+    ) -> AngularImpulse {
+        #[cfg(feature = "2d")]
+        {
+            self.rolling_impulse
+        }
+        #[cfg(feature = "3d")]
+        {
+            self.rolling_impulse.x * tangent_directions[0]
+                + self.rolling_impulse.y * tangent_directions[1]
+                + self.spinning_impulse * normal
+        }
+    }
+
+    /// Solves the rolling and spinning friction, updating the total impulses in `self` and returning
+    /// the incremental angular impulse to apply to each body.
+    ///
+    /// The `normal_impulse` should be the total normal impulse of the contact manifold.
+    #[allow(unused_variables)]
+    pub fn solve_impulse(
+        &mut self,
+        normal: Vector,
+        tangent_directions: [Vector; DIM - 1],
+        relative_angular_velocity: AngularImpulse,
+        normal_impulse: Scalar,
+    ) -> AngularImpulse {
+        // Like for sliding friction, the friction torque is limited by the normal force
+        // multiplied by the coefficient of friction.
+        let rolling_limit = self.rolling_coefficient * normal_impulse;
+
+        #[cfg(feature = "2d")]
+        {
+            // In 2D, the only axis of rotation is perpendicular to the contact normal,
+            // so all relative rotation is rolling.
+            let impulse = -self.rolling_effective_mass[0] * relative_angular_velocity;
+
+            // Clamp the accumulated impulse.
+            let new_impulse = (self.rolling_impulse + impulse).clamp(-rolling_limit, rolling_limit);
+            let impulse = new_impulse - self.rolling_impulse;
+            self.rolling_impulse = new_impulse;
+
+            impulse
+        }
+
+        #[cfg(feature = "3d")]
+        {
+            let mut total_impulse = Vector::ZERO;
+
+            // Spinning friction
+            if self.spinning_coefficient > 0.0 {
+                let spinning_limit = self.spinning_coefficient * normal_impulse;
+                let spinning_speed = relative_angular_velocity.dot(normal);
+
+                let impulse = -self.spinning_effective_mass * spinning_speed;
+
+                // Clamp the accumulated impulse.
+                let new_impulse =
+                    (self.spinning_impulse + impulse).clamp(-spinning_limit, spinning_limit);
+                let impulse = new_impulse - self.spinning_impulse;
+                self.spinning_impulse = new_impulse;
+
+                total_impulse += impulse * normal;
+            }
+
+            // Rolling friction
+            if self.rolling_coefficient > 0.0 {
+                let rolling_speed1 = relative_angular_velocity.dot(tangent_directions[0]);
+                let rolling_speed2 = relative_angular_velocity.dot(tangent_directions[1]);
+
+                let delta_impulse = Vector2::new(
+                    self.rolling_effective_mass[0] * rolling_speed1,
+                    self.rolling_effective_mass[1] * rolling_speed2,
+                );
+
+                // Clamp the accumulated impulse. The two rows are clamped together,
+                // so that the rolling resistance is the same in every direction.
+                let new_impulse =
+                    (self.rolling_impulse - delta_impulse).clamp_length_max(rolling_limit);
+                let impulse = new_impulse - self.rolling_impulse;
+                self.rolling_impulse = new_impulse;
+
+                total_impulse +=
+                    impulse.x * tangent_directions[0] + impulse.y * tangent_directions[1];
+            }
+
+            total_impulse
+        }
+    }
+}
@@ -15,6 +15,12 @@ pub struct ContactTangentPart {
     /// This corresponds to the magnitude of the friction impulse.
     pub impulse: TangentImpulse,
 
+    /// The target relative velocity of the bodies along the contact tangents in world space.
+    ///
+    /// Friction drives the relative tangential velocity towards this velocity instead of zero,
+    /// which can be used for things like conveyor belts. See [`SurfaceVelocity`].
+    pub target_velocity: Vector,
+
     /// The inertial properties of the bodies projected onto the contact tangent,
     /// or in other words, the mass "seen" by the constraint along the tangent.
     #[cfg(feature = "2d")]
@@ -35,6 +41,7 @@ impl ContactTangentPart {
         r1: Vector,
         r2: Vector,
         tangents: [Vector; DIM - 1],
+        target_velocity: Vector,
         warm_start_impulse: Option<TangentImpulse>,
     ) -> Self {
         let i1 = inverse_inertia1.into().0;
@@ -42,6 +49,7 @@ impl ContactTangentPart {
 
         let mut part = Self {
             impulse: warm_start_impulse.unwrap_or_default(),
+            target_velocity,
             #[cfg(feature = "2d")]
             effective_mass: 0.0,
             #[cfg(feature = "3d")]
@@ -145,11 +153,14 @@ impl ContactTangentPart {
 
     /// Solves the friction constraint, updating the total impulse in `self` and returning
     /// the incremental impulse to apply to each body.
+    ///
+    /// The `friction_coefficients` are the coefficients of dynamic friction along each tangent direction.
+    /// In 3D, they can differ for [anisotropic friction](AnisotropicFriction).
     pub fn solve_impulse(
         &mut self,
         tangent_directions: [Vector; DIM - 1],
         relative_velocity: Vector,
-        friction: Friction,
+        friction_coefficients: [Scalar; DIM - 1],
         normal_impulse: Scalar,
     ) -> Vector {
         // Compute the maximum bound for the friction impulse.
@@ -175,7 +186,14 @@ impl ContactTangentPart {
         // -coefficient * length(normal_impulse) <= impulse_magnitude <= coefficient * length(normal_impulse)
 
         // TODO: Separate static and dynamic friction
-        let impulse_limit = friction.dynamic_coefficient * normal_impulse;
+        #[cfg(feature = "2d")]
+        let impulse_limit = friction_coefficients[0] * normal_impulse;
+        #[cfg(feature = "3d")]
+        let impulse_limit =
+            Vector2::new(friction_coefficients[0], friction_coefficients[1]) * normal_impulse;
+
+        // Friction drives the relative velocity towards the target velocity.
+        let relative_velocity = relative_velocity - self.target_velocity;
 
         #[cfg(feature = "2d")]
         {
@@ -218,8 +236,22 @@ impl ContactTangentPart {
             // Compute the incremental tangent impoulse.
             let delta_impulse = effective_mass * Vector2::new(tangent_speed1, tangent_speed2);
 
-            // Clamp the accumulated impulse.
-            let new_impulse = (self.impulse - delta_impulse).clamp_length_max(impulse_limit);
+            // Clamp the accumulated impulse to the friction cone. For anisotropic friction,
+            // the cross section of the cone is an ellipse with the impulse limits as its radii.
+            let new_impulse = if impulse_limit.x == impulse_limit.y {
+                (self.impulse - delta_impulse).clamp_length_max(impulse_limit.x)
+            } else {
+                // Scale the impulse such that the ellipse becomes a unit circle, clamp it, and scale back.
+                // Directions with a zero impulse limit are not allowed to have any impulse.
+                let new_impulse = self.impulse - delta_impulse;
+                let scaled_impulse = new_impulse * impulse_limit.recip_or_zero();
+                let length = scaled_impulse.length();
+                if length > 1.0 {
+                    scaled_impulse / length * impulse_limit
+                } else {
+                    scaled_impulse * impulse_limit
+                }
+            };
             let impulse = new_impulse - self.impulse;
 
             if !impulse.is_finite() {
@@ -0,0 +1,629 @@
+//! Graph coloring for solving independent constraints in parallel.
+//!
+//! See [`GraphColoring`].
+
+use super::{contact::ContactConstraint, ContactConstraints};
+use crate::prelude::*;
+#[cfg(feature = "parallel")]
+use bevy::{
+    ecs::{change_detection::Mut, component::Tick},
+    tasks::{ComputeTaskPool, ParallelSlice, ParallelSliceMut},
+};
+use bevy::{prelude::*, utils::HashMap};
+use core::{marker::PhantomData, ops::Range};
+
+/// The maximum number of colors used by [`GraphColoring`].
+///
+/// Constraints that can not be given any of the colors are added to the
+/// [overflow](GraphColoring::overflow) and solved serially.
+pub const MAX_GRAPH_COLORS: usize = 24;
+
+/// Groups constraints into *colors*, batches of constraints that don't share any dynamic bodies.
+///
+/// Constraints within a color are independent of each other, so with the `parallel` feature,
+/// they are solved in parallel through Bevy's [`ComputeTaskPool`](bevy::tasks::ComputeTaskPool).
+/// The colors themselves are solved one after the other, so the result of the solver
+/// only depends on the coloring, not on the number of threads.
+///
+/// Colors are assigned greedily in the order of the constraints, which keeps the coloring deterministic.
+/// Only dynamic bodies are exclusive to a single constraint of each color. Static and kinematic bodies
+/// are not changed by the solver, so for example all contacts against the ground can share a color,
+/// and the solver only reads them through a copy. Constraints that can not be given any of the
+/// [`MAX_GRAPH_COLORS`] colors are added to the [overflow](Self::overflow) and solved serially after the colors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphColoring {
+    /// The ranges of constraint indices for each color.
+    colors: Vec<Range<usize>>,
+    /// The range of constraint indices for constraints that could not be colored.
+    overflow: Range<usize>,
+    /// The bodies of each constraint at the time of coloring, sorted by color like the constraints.
+    bodies: Vec<ColoredBodies>,
+}
+
+/// The bodies of a constraint in a [`GraphColoring`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+struct ColoredBodies {
+    /// The entities of the bodies.
+    entities: [Entity; 2],
+    /// Whether each body is exclusive to the constraint within its color.
+    /// Bodies that are not exclusive must only be read.
+    exclusive: [bool; 2],
+}
+
+impl GraphColoring {
+    /// Returns the ranges of constraint indices for each color.
+    pub fn colors(&self) -> &[Range<usize>] {
+        &self.colors
+    }
+
+    /// Returns the range of constraint indices for constraints that could not be colored.
+    pub fn overflow(&self) -> Range<usize> {
+        self.overflow.clone()
+    }
+
+    /// Colors the given constraints and sorts them by color, so that the constraints
+    /// of each color are stored contiguously. The `bodies` function returns the bodies
+    /// that are affected by a constraint, and `is_exclusive` returns `false` for bodies
+    /// that are only read by the solver, like static and kinematic bodies.
+    ///
+    /// The order of constraints within a color is preserved.
+    pub fn color<T>(
+        &mut self,
+        constraints: &mut Vec<T>,
+        bodies: impl Fn(&T) -> [Entity; 2],
+        is_exclusive: impl Fn(Entity) -> bool,
+    ) {
+        self.colors.clear();
+        self.bodies.clear();
+
+        // A bit mask of the colors used by each exclusive body.
+        let mut body_colors = HashMap::<Entity, u32>::with_capacity(constraints.len());
+        let mut color_counts = [0; MAX_GRAPH_COLORS + 1];
+
+        let mut colored = constraints
+            .drain(..)
+            .map(|constraint| {
+                let entities = bodies(&constraint);
+                let exclusive = entities.map(&is_exclusive);
+                let used = entities
+                    .iter()
+                    .zip(exclusive)
+                    .filter(|(_, exclusive)| *exclusive)
+                    .fold(0, |used, (entity, _)| {
+                        used | body_colors.get(entity).copied().unwrap_or(0)
+                    });
+
+                // Use the first free color, or the overflow if all colors are taken.
+                let color = ((!used).trailing_zeros() as usize).min(MAX_GRAPH_COLORS);
+                if color < MAX_GRAPH_COLORS {
+                    for (entity, _) in entities.iter().zip(exclusive).filter(|(_, e)| *e) {
+                        *body_colors.entry(*entity).or_default() |= 1 << color;
+                    }
+                }
+                color_counts[color] += 1;
+                (
+                    color,
+                    ColoredBodies {
+                        entities,
+                        exclusive,
+                    },
+                    constraint,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // The sort is stable, so the order of constraints within a color is preserved.
+        colored.sort_by_key(|(color, _, _)| *color);
+        for (_, bodies, constraint) in colored {
+            self.bodies.push(bodies);
+            constraints.push(constraint);
+        }
+
+        let mut start = 0;
+        for &count in color_counts[..MAX_GRAPH_COLORS].iter() {
+            if count > 0 {
+                self.colors.push(start..start + count);
+                start += count;
+            }
+        }
+        self.overflow = start..start + color_counts[MAX_GRAPH_COLORS];
+    }
+}
+
+/// The [`GraphColoring`] of the [`ContactConstraints`].
+///
+/// The contact constraints are sorted by color before the substepping loop
+/// in [`SolverSet::PreSubstep`].
+#[derive(Resource, Clone, Debug, Default, Deref, DerefMut, PartialEq, Eq)]
+pub struct ContactGraphColoring(pub GraphColoring);
+
+/// The [`GraphColoring`] of the [joints](super::joints) of type `T` that are solved with [`JointSolver::Impulse`].
+///
+/// The joints are colored before the substepping loop in [`SolverSet::PreSubstep`]
+/// using the bodies returned by [`XpbdConstraint::entities`](super::xpbd::XpbdConstraint::entities).
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct JointGraphColoring<T: Joint> {
+    /// The joint entities, sorted by color.
+    pub joints: Vec<Entity>,
+    /// The coloring of the [`joints`](Self::joints).
+    pub coloring: GraphColoring,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: Joint> Default for JointGraphColoring<T> {
+    fn default() -> Self {
+        Self {
+            joints: Vec::new(),
+            coloring: GraphColoring::default(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Sorts the [`ContactConstraints`] by color and updates the [`ContactGraphColoring`].
+pub(crate) fn color_contact_constraints(
+    mut constraints: ResMut<ContactConstraints>,
+    mut coloring: ResMut<ContactGraphColoring>,
+    bodies: Query<&RigidBody>,
+) {
+    coloring.color(
+        &mut constraints.0,
+        |constraint| [constraint.entity1, constraint.entity2],
+        |entity| bodies.get(entity).is_ok_and(RigidBody::is_dynamic),
+    );
+}
+
+/// Colors the joints of type `T` and updates the [`JointGraphColoring`].
+pub(crate) fn color_joints<T: Joint>(
+    joints: Query<(Entity, &T), Without<RigidBody>>,
+    bodies: Query<&RigidBody>,
+    mut coloring: ResMut<JointGraphColoring<T>>,
+) {
+    let mut joints = joints
+        .iter()
+        .map(|(entity, joint)| (entity, joint.entities()))
+        .collect::<Vec<_>>();
+
+    // Sort the joints to make the coloring independent of the iteration order of the query.
+    joints.sort_unstable_by_key(|(joint, _)| *joint);
+
+    let coloring = &mut *coloring;
+    coloring.coloring.color(
+        &mut joints,
+        |(_, bodies)| *bodies,
+        |entity| bodies.get(entity).is_ok_and(RigidBody::is_dynamic),
+    );
+    coloring.joints.clear();
+    coloring
+        .joints
+        .extend(joints.into_iter().map(|(joint, _)| joint));
+}
+
+/// A component of a [`SharedBody`] along with the change detection ticks needed for a [`Mut`].
+#[cfg(feature = "parallel")]
+struct CopiedComponent<T> {
+    value: T,
+    added: Tick,
+    changed: Tick,
+}
+
+#[cfg(feature = "parallel")]
+impl<T: Copy> CopiedComponent<T> {
+    fn new(value: &T) -> Self {
+        Self {
+            value: *value,
+            added: Tick::new(0),
+            changed: Tick::new(0),
+        }
+    }
+
+    fn as_ref(&self) -> Ref<'_, T> {
+        Ref::new(
+            &self.value,
+            &self.added,
+            &self.changed,
+            Tick::new(0),
+            Tick::new(0),
+        )
+    }
+
+    fn as_mut(&mut self) -> Mut<'_, T> {
+        Mut::new(
+            &mut self.value,
+            &mut self.added,
+            &mut self.changed,
+            Tick::new(0),
+            Tick::new(0),
+        )
+    }
+}
+
+/// A copy of a body that is not exclusive to a single constraint of a color, like a static body
+/// shared by many contacts.
+///
+/// The solver only reads bodies that are not dynamic, but it expects mutable access to both bodies
+/// of a constraint. In parallel solves, other threads may be reading the same body at the same time,
+/// so the solver is given a mutable view of this copy instead of the actual components.
+#[cfg(feature = "parallel")]
+struct SharedBody<'w> {
+    entity: Entity,
+    rb: CopiedComponent<RigidBody>,
+    position: CopiedComponent<Position>,
+    rotation: CopiedComponent<Rotation>,
+    previous_rotation: CopiedComponent<PreviousRotation>,
+    accumulated_translation: CopiedComponent<AccumulatedTranslation>,
+    linear_velocity: CopiedComponent<LinearVelocity>,
+    pre_solve_linear_velocity: CopiedComponent<PreSolveLinearVelocity>,
+    angular_velocity: CopiedComponent<AngularVelocity>,
+    pre_solve_angular_velocity: CopiedComponent<PreSolveAngularVelocity>,
+    mass: CopiedComponent<Mass>,
+    inverse_mass: CopiedComponent<InverseMass>,
+    inertia: CopiedComponent<Inertia>,
+    inverse_inertia: CopiedComponent<InverseInertia>,
+    center_of_mass: CopiedComponent<CenterOfMass>,
+    friction: &'w Friction,
+    restitution: &'w Restitution,
+    locked_axes: Option<&'w LockedAxes>,
+    dominance: Option<&'w Dominance>,
+    time_sleeping: CopiedComponent<TimeSleeping>,
+    is_sleeping: bool,
+    is_sensor: bool,
+}
+
+#[cfg(feature = "parallel")]
+impl<'w> SharedBody<'w> {
+    fn new(body: RigidBodyQueryReadOnlyItem<'w>) -> Self {
+        Self {
+            entity: body.entity,
+            rb: CopiedComponent::new(&*body.rb),
+            position: CopiedComponent::new(body.position),
+            rotation: CopiedComponent::new(body.rotation),
+            previous_rotation: CopiedComponent::new(body.previous_rotation),
+            accumulated_translation: CopiedComponent::new(body.accumulated_translation),
+            linear_velocity: CopiedComponent::new(body.linear_velocity),
+            pre_solve_linear_velocity: CopiedComponent::new(body.pre_solve_linear_velocity),
+            angular_velocity: CopiedComponent::new(body.angular_velocity),
+            pre_solve_angular_velocity: CopiedComponent::new(body.pre_solve_angular_velocity),
+            mass: CopiedComponent::new(body.mass),
+            inverse_mass: CopiedComponent::new(body.inverse_mass),
+            inertia: CopiedComponent::new(body.inertia),
+            inverse_inertia: CopiedComponent::new(body.inverse_inertia),
+            center_of_mass: CopiedComponent::new(body.center_of_mass),
+            friction: body.friction,
+            restitution: body.restitution,
+            locked_axes: body.locked_axes,
+            dominance: body.dominance,
+            time_sleeping: CopiedComponent::new(body.time_sleeping),
+            is_sleeping: body.is_sleeping,
+            is_sensor: body.is_sensor,
+        }
+    }
+
+    /// Returns a mutable view of the copy. Changes are not written back to the body.
+    fn item(&mut self) -> RigidBodyQueryItem<'_> {
+        RigidBodyQueryItem {
+            entity: self.entity,
+            rb: self.rb.as_ref(),
+            position: self.position.as_mut(),
+            rotation: self.rotation.as_mut(),
+            previous_rotation: self.previous_rotation.as_mut(),
+            accumulated_translation: self.accumulated_translation.as_mut(),
+            linear_velocity: self.linear_velocity.as_mut(),
+            pre_solve_linear_velocity: self.pre_solve_linear_velocity.as_mut(),
+            angular_velocity: self.angular_velocity.as_mut(),
+            pre_solve_angular_velocity: self.pre_solve_angular_velocity.as_mut(),
+            mass: self.mass.as_mut(),
+            inverse_mass: self.inverse_mass.as_mut(),
+            inertia: self.inertia.as_mut(),
+            inverse_inertia: self.inverse_inertia.as_mut(),
+            center_of_mass: self.center_of_mass.as_mut(),
+            friction: self.friction,
+            restitution: self.restitution,
+            locked_axes: self.locked_axes,
+            dominance: self.dominance,
+            time_sleeping: self.time_sleeping.as_mut(),
+            is_sleeping: self.is_sleeping,
+            is_sensor: self.is_sensor,
+        }
+    }
+}
+
+/// Calls `solve` for the bodies of a constraint in a parallel batch of a [`GraphColoring`].
+///
+/// Exclusive bodies are accessed mutably, while other bodies are given as a [`SharedBody`] copy.
+/// Returns `false` if the bodies of the constraint have changed since it was colored,
+/// in which case the constraint must be solved serially instead.
+#[cfg(feature = "parallel")]
+fn solve_in_parallel_batch(
+    bodies: &Query<RigidBodyQuery>,
+    colored: ColoredBodies,
+    entities: [Entity; 2],
+    solve: impl for<'w> FnOnce(&mut RigidBodyQueryItem<'w>, &mut RigidBodyQueryItem<'w>),
+) -> bool {
+    if entities != colored.entities {
+        return false;
+    }
+
+    let [entity1, entity2] = entities;
+    if entity1 == entity2 {
+        return true;
+    }
+
+    let (mut shared1, mut shared2);
+
+    // Safety: `get_unchecked` is only unsafe if there are multiple mutable references to the same component.
+    //         Exclusive bodies are used by only one constraint of each color, and their entities were just
+    //         checked to match the coloring. Other bodies are only read, and the query is borrowed mutably
+    //         by the caller, so no other references exist.
+    let body1 = if colored.exclusive[0] {
+        unsafe { bodies.get_unchecked(entity1) }.ok()
+    } else {
+        shared1 = bodies.get(entity1).ok().map(SharedBody::new);
+        shared1.as_mut().map(SharedBody::item)
+    };
+    let body2 = if colored.exclusive[1] {
+        unsafe { bodies.get_unchecked(entity2) }.ok()
+    } else {
+        shared2 = bodies.get(entity2).ok().map(SharedBody::new);
+        shared2.as_mut().map(SharedBody::item)
+    };
+
+    if let (Some(mut body1), Some(mut body2)) = (body1, body2) {
+        solve(&mut body1, &mut body2);
+    }
+
+    true
+}
+
+/// Returns the chunk size used for solving a batch of `len` constraints in parallel.
+#[cfg(feature = "parallel")]
+fn parallel_chunk_size(len: usize) -> usize {
+    (len / ComputeTaskPool::get().thread_num().max(1)).max(1)
+}
+
+/// Calls `solve` for each contact constraint and the bodies it affects, one color at a time.
+///
+/// With the `parallel` feature, the constraints of each color are solved in parallel.
+/// The [overflow](GraphColoring::overflow) is always solved serially, along with constraints
+/// whose bodies have changed since they were colored.
+pub(crate) fn solve_colored_contacts(
+    constraints: &mut [ContactConstraint],
+    coloring: &GraphColoring,
+    bodies: &mut Query<RigidBodyQuery>,
+    solve: impl for<'w> Fn(&mut ContactConstraint, &mut RigidBodyQueryItem<'w>, &mut RigidBodyQueryItem<'w>)
+        + Sync,
+) {
+    let solve_serially = |constraint: &mut ContactConstraint,
+                          bodies: &mut Query<RigidBodyQuery>| {
+        if let Ok([mut body1, mut body2]) =
+            bodies.get_many_mut([constraint.entity1, constraint.entity2])
+        {
+            solve(constraint, &mut body1, &mut body2);
+        }
+    };
+
+    for color in coloring.colors() {
+        #[cfg_attr(not(feature = "parallel"), allow(unused_mut))]
+        let (Some(mut batch), Some(colored_bodies)) = (
+            constraints.get_mut(color.clone()),
+            coloring.bodies.get(color.clone()),
+        ) else {
+            continue;
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            let chunk_size = parallel_chunk_size(batch.len());
+            let shared_bodies = &*bodies;
+            let skipped = batch.par_chunk_map_mut(
+                ComputeTaskPool::get(),
+                chunk_size,
+                |chunk_index, chunk| {
+                    let start = chunk_index * chunk_size;
+                    let mut skipped = Vec::new();
+                    for (i, constraint) in chunk.iter_mut().enumerate() {
+                        let solved = solve_in_parallel_batch(
+                            shared_bodies,
+                            colored_bodies[start + i],
+                            [constraint.entity1, constraint.entity2],
+                            |body1, body2| solve(constraint, body1, body2),
+                        );
+                        if !solved {
+                            skipped.push(start + i);
+                        }
+                    }
+                    skipped
+                },
+            );
+
+            for index in skipped.into_iter().flatten() {
+                solve_serially(&mut batch[index], bodies);
+            }
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let _ = colored_bodies;
+            for constraint in batch {
+                solve_serially(constraint, bodies);
+            }
+        }
+    }
+
+    let Some(overflow) = constraints.get_mut(coloring.overflow()) else {
+        return;
+    };
+    for constraint in overflow {
+        solve_serially(constraint, bodies);
+    }
+}
+
+/// Calls `solve` for each joint of type `T` in the [`JointGraphColoring`] and the bodies it connects,
+/// one color at a time. The `rows` passed to `solve` can be used as a scratch buffer.
+///
+/// With the `parallel` feature, the joints of each color are solved in parallel.
+/// The [overflow](GraphColoring::overflow) is always solved serially, along with joints
+/// whose bodies have changed since they were colored.
+pub(crate) fn solve_colored_joints<T: Joint>(
+    coloring: &JointGraphColoring<T>,
+    joints: &mut Query<&mut T, Without<RigidBody>>,
+    bodies: &mut Query<RigidBodyQuery>,
+    rows: &mut Vec<JointRow>,
+    solve: impl for<'w> Fn(
+            &mut T,
+            &mut RigidBodyQueryItem<'w>,
+            &mut RigidBodyQueryItem<'w>,
+            &mut Vec<JointRow>,
+        ) + Sync,
+) {
+    let solve_serially = |entity: Entity,
+                          joints: &mut Query<&mut T, Without<RigidBody>>,
+                          bodies: &mut Query<RigidBodyQuery>,
+                          rows: &mut Vec<JointRow>| {
+        let Ok(mut joint) = joints.get_mut(entity) else {
+            return;
+        };
+        if let Ok([mut body1, mut body2]) = bodies.get_many_mut(joint.entities()) {
+            solve(&mut joint, &mut body1, &mut body2, rows);
+        }
+    };
+
+    for color in coloring.coloring.colors() {
+        let (Some(batch), Some(colored_bodies)) = (
+            coloring.joints.get(color.clone()),
+            coloring.coloring.bodies.get(color.clone()),
+        ) else {
+            continue;
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            let chunk_size = parallel_chunk_size(batch.len());
+            let shared_joints = &*joints;
+            let shared_bodies = &*bodies;
+            let skipped =
+                batch.par_chunk_map(ComputeTaskPool::get(), chunk_size, |chunk_index, chunk| {
+                    let start = chunk_index * chunk_size;
+                    let mut rows = Vec::new();
+                    let mut skipped = Vec::new();
+                    for (i, &entity) in chunk.iter().enumerate() {
+                        // Safety: Each joint entity is only in the coloring once,
+                        //         and the query is borrowed mutably.
+                        let Ok(mut joint) = (unsafe { shared_joints.get_unchecked(entity) }) else {
+                            continue;
+                        };
+                        let entities = joint.entities();
+                        let solved = solve_in_parallel_batch(
+                            shared_bodies,
+                            colored_bodies[start + i],
+                            entities,
+                            |body1, body2| solve(&mut joint, body1, body2, &mut rows),
+                        );
+                        if !solved {
+                            skipped.push(start + i);
+                        }
+                    }
+                    skipped
+                });
+
+            for index in skipped.into_iter().flatten() {
+                solve_serially(batch[index], joints, bodies, rows);
+            }
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            let _ = colored_bodies;
+            for &entity in batch {
+                solve_serially(entity, joints, bodies, rows);
+            }
+        }
+    }
+
+    let Some(overflow) = coloring.joints.get(coloring.coloring.overflow()) else {
+        return;
+    };
+    for &entity in overflow {
+        solve_serially(entity, joints, bodies, rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::utils::HashSet;
+
+    #[test]
+    fn colors_do_not_share_bodies() {
+        let bodies = (0..10).map(Entity::from_raw).collect::<Vec<_>>();
+
+        // A chain of bodies, plus a hub body connected to every other body.
+        let mut constraints = (0..9)
+            .map(|i| [bodies[i], bodies[i + 1]])
+            .chain((2..10).map(|i| [bodies[0], bodies[i]]))
+            .collect::<Vec<_>>();
+        let original = constraints.clone();
+
+        let mut coloring = GraphColoring::default();
+        coloring.color(&mut constraints, |constraint| *constraint, |_| true);
+
+        // All constraints are kept.
+        assert_eq!(constraints.len(), original.len());
+        assert!(coloring.overflow().is_empty());
+
+        for color in coloring.colors() {
+            let mut used = HashSet::new();
+            for [body1, body2] in &constraints[color.clone()] {
+                assert!(used.insert(*body1));
+                assert!(used.insert(*body2));
+            }
+        }
+    }
+
+    #[test]
+    fn overflow_is_used_when_colors_run_out() {
+        let hub = Entity::from_raw(0);
+        let count = MAX_GRAPH_COLORS + 5;
+
+        let mut constraints = (1..=count as u32)
+            .map(|i| [hub, Entity::from_raw(i)])
+            .collect::<Vec<_>>();
+
+        let mut coloring = GraphColoring::default();
+        coloring.color(&mut constraints, |constraint| *constraint, |_| true);
+
+        assert_eq!(coloring.colors().len(), MAX_GRAPH_COLORS);
+        assert_eq!(coloring.overflow(), MAX_GRAPH_COLORS..count);
+
+        // The order of the constraints is preserved.
+        assert_eq!(
+            constraints[count - 1],
+            [hub, Entity::from_raw(count as u32)]
+        );
+    }
+
+    #[test]
+    fn non_exclusive_bodies_do_not_use_colors() {
+        let ground = Entity::from_raw(0);
+        let count = MAX_GRAPH_COLORS + 5;
+
+        // Many bodies resting on the same static ground.
+        let mut constraints = (1..=count as u32)
+            .map(|i| [ground, Entity::from_raw(i)])
+            .collect::<Vec<_>>();
+
+        let mut coloring = GraphColoring::default();
+        coloring.color(
+            &mut constraints,
+            |constraint| *constraint,
+            |entity| entity != ground,
+        );
+
+        assert_eq!(coloring.colors().len(), 1);
+        assert_eq!(coloring.colors()[0], 0..count);
+        assert!(coloring.overflow().is_empty());
+        assert!(coloring
+            .bodies
+            .iter()
+            .all(|bodies| bodies.exclusive == [false, true]));
+    }
+}
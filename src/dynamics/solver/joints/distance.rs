@@ -1,5 +1,6 @@
 //! [`DistanceJoint`] component.
 
+use super::impulse::{push_limit_rows, world_anchors};
 use crate::{dynamics::solver::xpbd::*, prelude::*};
 use bevy::{
     ecs::{
@@ -25,6 +26,8 @@ pub struct DistanceJoint {
     pub local_anchor1: Vector,
     /// Attachment point on the second body.
     pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
     /// The distance the attached bodies will be kept relative to each other.
     pub rest_length: Scalar,
     /// The extents of the allowed relative translation between the attached bodies.
@@ -37,8 +40,14 @@ pub struct DistanceJoint {
     pub lagrange: Scalar,
     /// The joint's compliance, the inverse of stiffness, has the unit meters / Newton.
     pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
     /// The force exerted by the joint.
     pub force: Vector,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
 }
 
 impl XpbdConstraint<2> for DistanceJoint {
@@ -46,6 +55,10 @@ impl XpbdConstraint<2> for DistanceJoint {
         [self.entity1, self.entity2]
     }
 
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
     fn clear_lagrange_multipliers(&mut self) {
         self.lagrange = 0.0;
     }
@@ -62,13 +75,16 @@ impl Joint for DistanceJoint {
             entity2,
             local_anchor1: Vector::ZERO,
             local_anchor2: Vector::ZERO,
+            collisions_disabled: false,
             rest_length: 0.0,
             length_limits: None,
             damping_linear: 0.0,
             damping_angular: 0.0,
             lagrange: 0.0,
             compliance: 0.0,
+            softness: None,
             force: Vector::ZERO,
+            impulses: JointImpulses::default(),
         }
     }
 
@@ -76,6 +92,13 @@ impl Joint for DistanceJoint {
         Self { compliance, ..self }
     }
 
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
     fn with_local_anchor_1(self, anchor: Vector) -> Self {
         Self {
             local_anchor1: anchor,
@@ -104,6 +127,13 @@ impl Joint for DistanceJoint {
         }
     }
 
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
     fn local_anchor_1(&self) -> Vector {
         self.local_anchor1
     }
@@ -119,6 +149,18 @@ impl Joint for DistanceJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force
+    }
+
+    fn torque(&self) -> Torque {
+        Torque::ZERO
+    }
 }
 
 impl DistanceJoint {
@@ -189,6 +231,52 @@ impl DistanceJoint {
     }
 }
 
+impl ImpulseJoint for DistanceJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
+
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        _delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let softness = JointRowSoftness::from_joint(self.softness, self.compliance);
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+
+        let offset = p2 - p1;
+        let distance = offset.length();
+
+        if distance <= Scalar::EPSILON {
+            return;
+        }
+
+        let row = JointRow::linear(0, r1, r2, offset / distance, 0.0).with_softness(softness);
+        let limits = self
+            .length_limits
+            .unwrap_or(DistanceLimit::new(self.rest_length, self.rest_length));
+
+        if limits.min == limits.max {
+            rows.push(JointRow {
+                error: distance - limits.min,
+                ..row
+            });
+        } else {
+            push_limit_rows(rows, row, [0, 1], distance, limits.min, limits.max);
+        }
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, _torque: Torque) {
+        self.force = force;
+    }
+}
+
 impl PositionConstraint for DistanceJoint {}
 
 impl AngularConstraint for DistanceJoint {}
@@ -1,5 +1,6 @@
 //! [`FixedJoint`] component.
 
+use super::impulse::{push_angular_lock_rows, push_point_rows, world_anchors};
 use crate::{dynamics::solver::xpbd::*, prelude::*};
 use bevy::{
     ecs::{
@@ -26,6 +27,8 @@ pub struct FixedJoint {
     pub local_anchor1: Vector,
     /// Attachment point on the second body.
     pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
     /// Linear damping applied by the joint.
     pub damping_linear: Scalar,
     /// Angular damping applied by the joint.
@@ -36,10 +39,16 @@ pub struct FixedJoint {
     pub align_lagrange: Scalar,
     /// The joint's compliance, the inverse of stiffness, has the unit meters / Newton.
     pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
     /// The force exerted by the joint.
     pub force: Vector,
     /// The torque exerted by the joint when aligning the bodies.
     pub align_torque: Torque,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
 }
 
 impl XpbdConstraint<2> for FixedJoint {
@@ -47,6 +56,10 @@ impl XpbdConstraint<2> for FixedJoint {
         [self.entity1, self.entity2]
     }
 
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
     fn clear_lagrange_multipliers(&mut self) {
         self.position_lagrange = 0.0;
         self.align_lagrange = 0.0;
@@ -85,16 +98,19 @@ impl Joint for FixedJoint {
             entity2,
             local_anchor1: Vector::ZERO,
             local_anchor2: Vector::ZERO,
+            collisions_disabled: false,
             damping_linear: 1.0,
             damping_angular: 1.0,
             position_lagrange: 0.0,
             align_lagrange: 0.0,
             compliance: 0.0,
+            softness: None,
             force: Vector::ZERO,
             #[cfg(feature = "2d")]
             align_torque: 0.0,
             #[cfg(feature = "3d")]
             align_torque: Vector::ZERO,
+            impulses: JointImpulses::default(),
         }
     }
 
@@ -102,6 +118,13 @@ impl Joint for FixedJoint {
         Self { compliance, ..self }
     }
 
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
     fn with_local_anchor_1(self, anchor: Vector) -> Self {
         Self {
             local_anchor1: anchor,
@@ -130,6 +153,13 @@ impl Joint for FixedJoint {
         }
     }
 
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
     fn local_anchor_1(&self) -> Vector {
         self.local_anchor1
     }
@@ -145,6 +175,18 @@ impl Joint for FixedJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force
+    }
+
+    fn torque(&self) -> Torque {
+        self.align_torque
+    }
 }
 
 impl FixedJoint {
@@ -161,6 +203,36 @@ impl FixedJoint {
     }
 }
 
+impl ImpulseJoint for FixedJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
+
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        _delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let softness = JointRowSoftness::from_joint(self.softness, self.compliance);
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+
+        // Align the attachment points and lock the relative rotation of the bodies
+        push_point_rows(rows, r1, r2, p2 - p1, softness);
+        push_angular_lock_rows(rows, &body1.rotation, &body2.rotation, DIM, softness);
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, torque: Torque) {
+        self.force = force;
+        self.align_torque = torque;
+    }
+}
+
 impl PositionConstraint for FixedJoint {}
 
 impl AngularConstraint for FixedJoint {}
@@ -0,0 +1,626 @@
+//! [`GearJoint`] component.
+
+use crate::{
+    dynamics::solver::{ContactSoftnessCoefficients, SolverConfig},
+    prelude::*,
+    PI, TAU,
+};
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+/// A gear joint couples the motion of two [revolute](RevoluteJoint) or [prismatic](PrismaticJoint) joints
+/// so that `coordinate1 + ratio * coordinate2` stays constant.
+///
+/// The coordinate of a revolute joint is the relative angle of its bodies around the joint axis,
+/// and the coordinate of a prismatic joint is the relative translation of its bodies along the free axis.
+/// Both coordinates are measured from the configuration of the joints when the gear joint is first solved.
+///
+/// Coupling two revolute joints can be used for gearboxes and belt-driven mechanisms, while coupling a revolute joint
+/// and a prismatic joint results in a rack and pinion. A negative `ratio` makes the joints move in the same direction.
+///
+/// Unlike other joints, a gear joint references two joint entities instead of two bodies,
+/// so it can affect up to four bodies. The referenced joints keep working as usual, and if either of them
+/// is removed, the gear joint stops affecting the bodies.
+///
+/// ## Differences to other joints
+///
+/// The coupled joints often share a body, like the ground in the example below, so the four bodies
+/// are not necessarily distinct. Because of this, gear joints are solved by dedicated systems instead of
+/// [`solve_constraint`](crate::dynamics::solver::xpbd::solve_constraint) with an `ENTITY_COUNT` of 4,
+/// which requires the entities of a constraint to be unique.
+///
+/// Gear joints also don't implement the [`Joint`] trait, which assumes two bodies. As a result:
+///
+/// - The `force` and `torque` used for [`JointForces`] and [`BreakableJoint`] are the force and torque
+///   that the gear joint applies to the second body of the first coupled joint. A coupled revolute joint
+///   results in a torque around its axis, and a coupled prismatic joint in a force along its free axis.
+/// - In a [`JointBroken`] event, `entity1` and `entity2` are the coupled joint entities.
+/// - Gear joints never disable collisions between the bodies they affect.
+/// - In the [`JointGraph`], a gear joint is an edge between the second bodies of the coupled joints,
+///   so that the bodies of both joints are in the same connected component and simulation island.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     let ground = commands.spawn(RigidBody::Static).id();
+///     let small_gear = commands.spawn(RigidBody::Dynamic).id();
+///     let large_gear = commands.spawn(RigidBody::Dynamic).id();
+///
+///     let joint1 = commands.spawn(RevoluteJoint::new(ground, small_gear)).id();
+///     let joint2 = commands.spawn(RevoluteJoint::new(ground, large_gear)).id();
+///
+///     // The large gear turns at half the speed of the small gear, in the opposite direction.
+///     commands.spawn(GearJoint::new(joint1, joint2).with_ratio(2.0));
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, MapEntities, PartialEq)]
+pub struct GearJoint {
+    /// The first [`RevoluteJoint`] or [`PrismaticJoint`] entity coupled by the gear joint.
+    pub joint1: Entity,
+    /// The second [`RevoluteJoint`] or [`PrismaticJoint`] entity coupled by the gear joint.
+    pub joint2: Entity,
+    /// The gear ratio. The constraint keeps `coordinate1 + ratio * coordinate2` constant.
+    pub ratio: Scalar,
+    /// The current coordinates of the first and second joint, measured from their configuration
+    /// when the gear joint was first solved.
+    ///
+    /// Angles are not wrapped, so a revolute joint that has turned twice has a coordinate of `4 * PI`.
+    pub coordinates: [Scalar; 2],
+    /// The wrapped coordinates of the joints at the previous solve, used for accumulating `coordinates`.
+    previous_coordinates: Option<[Scalar; 2]>,
+    /// Lagrange multiplier for the coupling of the coordinates.
+    pub lagrange: Scalar,
+    /// The impulse accumulated by the [impulse-based joint solver](super#solvers), used for warm starting.
+    pub impulse: Scalar,
+    /// The force exerted by the joint on the second body of the first coupled joint.
+    ///
+    /// This is only non-zero if the first coupled joint is a [`PrismaticJoint`].
+    pub force: Vector,
+    /// The torque exerted by the joint on the second body of the first coupled joint.
+    ///
+    /// This is only non-zero if the first coupled joint is a [`RevoluteJoint`].
+    pub torque: Torque,
+    /// The joint's compliance, the inverse of stiffness.
+    pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
+}
+
+impl GearJoint {
+    /// Creates a new [`GearJoint`] between two [`RevoluteJoint`] or [`PrismaticJoint`] entities
+    /// with a ratio of `1.0`.
+    pub fn new(joint1: Entity, joint2: Entity) -> Self {
+        Self {
+            joint1,
+            joint2,
+            ratio: 1.0,
+            coordinates: [0.0; 2],
+            previous_coordinates: None,
+            lagrange: 0.0,
+            impulse: 0.0,
+            force: Vector::ZERO,
+            torque: Torque::ZERO,
+            compliance: 0.0,
+            softness: None,
+        }
+    }
+
+    /// Sets the gear ratio. The constraint keeps `coordinate1 + ratio * coordinate2` constant.
+    pub fn with_ratio(self, ratio: Scalar) -> Self {
+        Self { ratio, ..self }
+    }
+
+    /// Sets the joint's compliance (inverse of stiffness).
+    pub fn with_compliance(self, compliance: Scalar) -> Self {
+        Self { compliance, ..self }
+    }
+
+    /// Sets the joint's softness using a damping ratio and a frequency.
+    /// This overrides the compliance of the joint.
+    ///
+    /// When solved using XPBD, gear joints are not damped, so only the frequency is used.
+    pub fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
+    /// Updates the unwrapped `coordinates` using the current wrapped coordinates of the joints.
+    fn update_coordinates(&mut self, current: [Scalar; 2], axes: [GearAxis; 2]) {
+        if let Some(previous) = self.previous_coordinates {
+            for i in 0..2 {
+                let mut delta = current[i] - previous[i];
+
+                // Angles wrap around at PI, so the shortest rotation is used.
+                // Bodies never rotate by more than PI during a single substep.
+                if !matches!(axes[i], GearAxis::Linear(_)) {
+                    if delta > PI {
+                        delta -= TAU;
+                    } else if delta < -PI {
+                        delta += TAU;
+                    }
+                }
+
+                self.coordinates[i] += delta;
+            }
+        }
+        self.previous_coordinates = Some(current);
+    }
+
+    /// Sets the `force` and `torque` of the joint from the force along the coordinate of the first coupled joint.
+    fn set_force_and_torque(&mut self, axis: GearAxis, force: Scalar) {
+        (self.force, self.torque) = match axis {
+            #[cfg(feature = "2d")]
+            GearAxis::Angular => (Vector::ZERO, force),
+            #[cfg(feature = "3d")]
+            GearAxis::Angular(axis) => (Vector::ZERO, force * axis),
+            GearAxis::Linear(axis) => (force * axis, Torque::ZERO),
+        };
+    }
+}
+
+impl MapEntities for GearJoint {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.joint1 = entity_mapper.map_entity(self.joint1);
+        self.joint2 = entity_mapper.map_entity(self.joint2);
+    }
+}
+
+/// The world-space axis along which the coordinate of a joint coupled by a [`GearJoint`] is measured.
+#[derive(Clone, Copy, Debug)]
+enum GearAxis {
+    /// The relative angle of the bodies.
+    #[cfg(feature = "2d")]
+    Angular,
+    /// The relative angle of the bodies around an axis.
+    #[cfg(feature = "3d")]
+    Angular(Vector),
+    /// The relative translation of the bodies along an axis.
+    Linear(Vector),
+}
+
+/// The coordinate of a joint coupled by a [`GearJoint`] and the bodies it affects.
+struct GearCoordinate {
+    entities: [Entity; 2],
+    value: Scalar,
+    axis: GearAxis,
+}
+
+/// Computes the current coordinate of a [`RevoluteJoint`] or [`PrismaticJoint`] entity.
+fn joint_coordinate(
+    joint: Entity,
+    revolute_joints: &Query<&RevoluteJoint>,
+    prismatic_joints: &Query<&PrismaticJoint>,
+    bodies: &Query<RigidBodyQuery>,
+) -> Option<GearCoordinate> {
+    if let Ok(joint) = revolute_joints.get(joint) {
+        let [body1, body2] = bodies.get_many([joint.entity1, joint.entity2]).ok()?;
+
+        #[cfg(feature = "2d")]
+        let (value, axis) = (
+            body1.rotation.angle_between(*body2.rotation),
+            GearAxis::Angular,
+        );
+        #[cfg(feature = "3d")]
+        let (value, axis) = {
+            // Compute the relative angle around the aligned axis using perpendicular reference axes.
+            let axis = *body1.rotation * joint.aligned_axis;
+            let b = joint.aligned_axis.any_orthonormal_vector();
+            let b1 = *body1.rotation * b;
+            let b2 = *body2.rotation * b;
+            (
+                b1.cross(b2).dot(axis).atan2(b1.dot(b2)),
+                GearAxis::Angular(axis),
+            )
+        };
+
+        return Some(GearCoordinate {
+            entities: [joint.entity1, joint.entity2],
+            value,
+            axis,
+        });
+    }
+
+    if let Ok(joint) = prismatic_joints.get(joint) {
+        let [body1, body2] = bodies.get_many([joint.entity1, joint.entity2]).ok()?;
+
+        let world_r1 = *body1.rotation * joint.local_anchor1;
+        let world_r2 = *body2.rotation * joint.local_anchor2;
+        let offset = body2.current_position() + world_r2 - body1.current_position() - world_r1;
+        let axis = *body1.rotation * joint.free_axis;
+
+        return Some(GearCoordinate {
+            entities: [joint.entity1, joint.entity2],
+            value: offset.dot(axis),
+            axis: GearAxis::Linear(axis),
+        });
+    }
+
+    None
+}
+
+/// Computes the generalized inverse mass of a body when changing a coordinate along `axis`.
+fn gear_inverse_mass(body: &RigidBodyQueryReadOnlyItem, axis: GearAxis) -> Scalar {
+    if !body.rb.is_dynamic() {
+        return 0.0;
+    }
+
+    match axis {
+        #[cfg(feature = "2d")]
+        GearAxis::Angular => body.effective_world_inv_inertia(),
+        #[cfg(feature = "3d")]
+        GearAxis::Angular(axis) => axis.dot(body.effective_world_inv_inertia() * axis),
+        GearAxis::Linear(axis) => axis.dot(body.effective_inv_mass() * axis),
+    }
+}
+
+/// Moves a body along `axis` to change a coordinate by `impulse` scaled by the inverse mass of the body.
+fn apply_gear_impulse(body: &mut RigidBodyQueryItem, axis: GearAxis, impulse: Scalar) {
+    if !body.rb.is_dynamic() {
+        return;
+    }
+
+    match axis {
+        #[cfg(feature = "2d")]
+        GearAxis::Angular => {
+            let delta_angle = body.effective_world_inv_inertia() * impulse;
+            *body.rotation = body.rotation.add_angle(delta_angle);
+        }
+        #[cfg(feature = "3d")]
+        GearAxis::Angular(axis) => {
+            let delta_rot = body.effective_world_inv_inertia() * (impulse * axis);
+            let delta_quat = Quaternion::from_vec4(0.5 * delta_rot.extend(0.0)) * body.rotation.0;
+            // In 3D, adding quaternions can result in unnormalized rotations,
+            // so the rotation must be normalized.
+            body.rotation.0 = (body.rotation.0 + delta_quat).normalize();
+        }
+        GearAxis::Linear(axis) => {
+            let delta_translation = body.effective_inv_mass() * (impulse * axis);
+            body.accumulated_translation.0 += delta_translation;
+        }
+    }
+}
+
+/// Computes the rate of change of a coordinate caused by the velocity of a body along `axis`.
+fn gear_velocity(body: &RigidBodyQueryReadOnlyItem, axis: GearAxis) -> Scalar {
+    match axis {
+        #[cfg(feature = "2d")]
+        GearAxis::Angular => body.angular_velocity.0,
+        #[cfg(feature = "3d")]
+        GearAxis::Angular(axis) => axis.dot(body.angular_velocity.0),
+        GearAxis::Linear(axis) => axis.dot(body.linear_velocity.0),
+    }
+}
+
+/// Changes the velocity of a body along `axis` to change the rate of a coordinate by `impulse`
+/// scaled by the inverse mass of the body.
+fn apply_gear_velocity_impulse(body: &mut RigidBodyQueryItem, axis: GearAxis, impulse: Scalar) {
+    if !body.rb.is_dynamic() {
+        return;
+    }
+
+    match axis {
+        #[cfg(feature = "2d")]
+        GearAxis::Angular => {
+            let inv_inertia = body.effective_world_inv_inertia();
+            body.angular_velocity.0 += inv_inertia * impulse;
+        }
+        #[cfg(feature = "3d")]
+        GearAxis::Angular(axis) => {
+            let inv_inertia = body.effective_world_inv_inertia();
+            body.angular_velocity.0 += inv_inertia * (impulse * axis);
+        }
+        GearAxis::Linear(axis) => {
+            let inv_mass = body.effective_inv_mass();
+            body.linear_velocity.0 += inv_mass * (impulse * axis);
+        }
+    }
+}
+
+/// Returns the bodies affected by a gear joint, along with the gradient of the constraint function
+/// with respect to the coordinate of each body. The first body of each joint moves in the opposite direction.
+fn gear_bodies(
+    coordinate1: &GearCoordinate,
+    coordinate2: &GearCoordinate,
+    ratio: Scalar,
+) -> [(Entity, GearAxis, Scalar); 4] {
+    [
+        (coordinate1.entities[0], coordinate1.axis, -1.0),
+        (coordinate1.entities[1], coordinate1.axis, 1.0),
+        (coordinate2.entities[0], coordinate2.axis, -ratio),
+        (coordinate2.entities[1], coordinate2.axis, ratio),
+    ]
+}
+
+/// Solves [`GearJoint`]s by coupling the coordinates of the joints they reference.
+///
+/// Sleeping bodies are woken up when active bodies interact with them through a gear joint.
+///
+/// The joints are solved [`SolverConfig::position_iterations`] times.
+pub(crate) fn solve_gear_joints(
+    mut commands: Commands,
+    mut bodies: Query<RigidBodyQuery>,
+    mut gear_joints: Query<&mut GearJoint, Without<RigidBody>>,
+    revolute_joints: Query<&RevoluteJoint>,
+    prismatic_joints: Query<&PrismaticJoint>,
+    solver_config: Res<SolverConfig>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_seconds_adjusted();
+
+    // Clear Lagrange multipliers and forces
+    for mut gear in &mut gear_joints {
+        gear.lagrange = 0.0;
+        gear.force = Vector::ZERO;
+        gear.torque = Torque::ZERO;
+    }
+
+    for _ in 0..solver_config.position_iterations {
+        for mut gear in &mut gear_joints {
+            let (Some(coordinate1), Some(coordinate2)) = (
+                joint_coordinate(gear.joint1, &revolute_joints, &prismatic_joints, &bodies),
+                joint_coordinate(gear.joint2, &revolute_joints, &prismatic_joints, &bodies),
+            ) else {
+                continue;
+            };
+
+            gear.update_coordinates(
+                [coordinate1.value, coordinate2.value],
+                [coordinate1.axis, coordinate2.axis],
+            );
+
+            let affected_bodies = gear_bodies(&coordinate1, &coordinate2, gear.ratio);
+
+            let mut any_active = false;
+            let mut w_sum = 0.0;
+            for (entity, axis, gradient) in affected_bodies {
+                let Ok(body) = bodies.get(entity) else {
+                    continue;
+                };
+                any_active |= body.rb.is_dynamic() && !body.is_sleeping;
+                w_sum += gradient * gradient * gear_inverse_mass(&body, axis);
+            }
+
+            // No constraint solving if all of the bodies are either static, kinematic or sleeping
+            if !any_active || w_sum <= Scalar::EPSILON {
+                continue;
+            }
+
+            let c = gear.coordinates[0] + gear.ratio * gear.coordinates[1];
+
+            let compliance = gear.softness.map_or(gear.compliance, |softness| {
+                softness.compute_compliance(w_sum)
+            });
+
+            // tilde_a = a/h^2
+            let tilde_compliance = compliance / delta_secs.powi(2);
+            let delta_lagrange =
+                (-c - tilde_compliance * gear.lagrange) / (w_sum + tilde_compliance);
+            gear.lagrange += delta_lagrange;
+
+            let lagrange = gear.lagrange;
+            gear.set_force_and_torque(coordinate1.axis, lagrange / delta_secs.powi(2));
+
+            for (entity, axis, gradient) in affected_bodies {
+                let Ok(mut body) = bodies.get_mut(entity) else {
+                    continue;
+                };
+
+                // At least one of the participating bodies is active, so wake up any sleeping bodies
+                if body.is_sleeping {
+                    body.time_sleeping.0 = 0.0;
+                    commands.entity(body.entity).remove::<Sleeping>();
+                }
+
+                apply_gear_impulse(&mut body, axis, gradient * delta_lagrange);
+            }
+        }
+    }
+}
+
+/// Warm starts [`GearJoint`]s by applying the impulses accumulated during the previous substep.
+pub(crate) fn warm_start_gear_joints(
+    mut bodies: Query<RigidBodyQuery>,
+    gear_joints: Query<&GearJoint, Without<RigidBody>>,
+    revolute_joints: Query<&RevoluteJoint>,
+    prismatic_joints: Query<&PrismaticJoint>,
+    solver_config: Res<SolverConfig>,
+) {
+    for gear in &gear_joints {
+        if gear.impulse == 0.0 {
+            continue;
+        }
+
+        let (Some(coordinate1), Some(coordinate2)) = (
+            joint_coordinate(gear.joint1, &revolute_joints, &prismatic_joints, &bodies),
+            joint_coordinate(gear.joint2, &revolute_joints, &prismatic_joints, &bodies),
+        ) else {
+            continue;
+        };
+
+        let impulse = solver_config.warm_start_coefficient * gear.impulse;
+
+        for (entity, axis, gradient) in gear_bodies(&coordinate1, &coordinate2, gear.ratio) {
+            if let Ok(mut body) = bodies.get_mut(entity) {
+                apply_gear_velocity_impulse(&mut body, axis, gradient * impulse);
+            }
+        }
+    }
+}
+
+/// Solves [`GearJoint`]s using impulses, coupling the rates of change of the coordinates of the joints they reference.
+///
+/// If `USE_BIAS` is `true`, the coordinates are updated and the impulses are boosted to correct the coupling error.
+/// Sleeping bodies are woken up when active bodies interact with them through a gear joint.
+///
+/// This is run once per iteration in the [`SolveConstraintsSchedule`](crate::dynamics::solver::SolveConstraintsSchedule)
+/// and [`RelaxSchedule`](crate::dynamics::solver::RelaxSchedule).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn solve_gear_joints_impulse<const USE_BIAS: bool>(
+    mut commands: Commands,
+    mut bodies: Query<RigidBodyQuery>,
+    mut gear_joints: Query<&mut GearJoint, Without<RigidBody>>,
+    revolute_joints: Query<&RevoluteJoint>,
+    prismatic_joints: Query<&PrismaticJoint>,
+    contact_softness: Res<ContactSoftnessCoefficients>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_seconds_adjusted();
+
+    for mut gear in &mut gear_joints {
+        let (Some(coordinate1), Some(coordinate2)) = (
+            joint_coordinate(gear.joint1, &revolute_joints, &prismatic_joints, &bodies),
+            joint_coordinate(gear.joint2, &revolute_joints, &prismatic_joints, &bodies),
+        ) else {
+            gear.impulse = 0.0;
+            gear.force = Vector::ZERO;
+            gear.torque = Torque::ZERO;
+            continue;
+        };
+
+        if USE_BIAS {
+            gear.update_coordinates(
+                [coordinate1.value, coordinate2.value],
+                [coordinate1.axis, coordinate2.axis],
+            );
+        }
+
+        let affected_bodies = gear_bodies(&coordinate1, &coordinate2, gear.ratio);
+
+        let mut any_active = false;
+        let mut all_dynamic = true;
+        let mut w_sum = 0.0;
+        let mut velocity = 0.0;
+        for (entity, axis, gradient) in affected_bodies {
+            let Ok(body) = bodies.get(entity) else {
+                continue;
+            };
+            any_active |= body.rb.is_dynamic() && !body.is_sleeping;
+            all_dynamic &= body.rb.is_dynamic();
+            w_sum += gradient * gradient * gear_inverse_mass(&body, axis);
+            velocity += gradient * gear_velocity(&body, axis);
+        }
+
+        // No constraint solving if all of the bodies are either static, kinematic or sleeping
+        if !any_active || w_sum <= Scalar::EPSILON {
+            continue;
+        }
+
+        let rigid_coefficients = if all_dynamic {
+            contact_softness.dynamic
+        } else {
+            contact_softness.non_dynamic
+        };
+        let Some(coefficients) = JointRowSoftness::from_joint(gear.softness, gear.compliance)
+            .compute_coefficients(w_sum, rigid_coefficients, USE_BIAS, delta_secs)
+        else {
+            continue;
+        };
+
+        let c = gear.coordinates[0] + gear.ratio * gear.coordinates[1];
+        let impulse = -coefficients.mass_scale * (velocity + coefficients.bias * c) / w_sum
+            - coefficients.impulse_scale * gear.impulse;
+        gear.impulse += impulse;
+
+        if !USE_BIAS {
+            let impulse = gear.impulse;
+            gear.set_force_and_torque(coordinate1.axis, impulse / delta_secs);
+        }
+
+        for (entity, axis, gradient) in affected_bodies {
+            let Ok(mut body) = bodies.get_mut(entity) else {
+                continue;
+            };
+
+            // At least one of the participating bodies is active, so wake up any sleeping bodies
+            if USE_BIAS && body.is_sleeping {
+                body.time_sleeping.0 = 0.0;
+                commands.entity(body.entity).remove::<Sleeping>();
+            }
+
+            apply_gear_velocity_impulse(&mut body, axis, gradient * impulse);
+        }
+    }
+}
+
+/// Updates the [`JointGraph`] for [`GearJoint`]s, connecting the second bodies of the coupled joints.
+///
+/// The bodies of the coupled joints can change without the gear joint itself changing,
+/// so the edges of all gear joints are updated every time.
+pub(crate) fn update_gear_joint_graph(
+    gear_joints: Query<(Entity, &GearJoint)>,
+    mut removed_gear_joints: RemovedComponents<GearJoint>,
+    revolute_joints: Query<&RevoluteJoint>,
+    prismatic_joints: Query<&PrismaticJoint>,
+    mut joint_graph: ResMut<JointGraph>,
+) {
+    for entity in removed_gear_joints.read() {
+        joint_graph.remove(entity);
+    }
+
+    let second_body = |joint: Entity| {
+        revolute_joints
+            .get(joint)
+            .map(|joint| joint.entity2)
+            .or_else(|_| prismatic_joints.get(joint).map(|joint| joint.entity2))
+            .ok()
+    };
+
+    for (entity, gear) in &gear_joints {
+        let bodies = second_body(gear.joint1).zip(second_body(gear.joint2));
+        if joint_graph.bodies(entity) != bodies {
+            match bodies {
+                Some((body1, body2)) => joint_graph.insert(entity, body1, body2),
+                None => joint_graph.remove(entity),
+            }
+        }
+    }
+}
+
+/// Adds the force and torque exerted by [`GearJoint`]s during the current substep to their [`JointForces`].
+pub(crate) fn accumulate_gear_joint_forces(
+    mut gear_joints: Query<(&GearJoint, &mut JointForces), Without<RigidBody>>,
+) {
+    for (gear, mut forces) in &mut gear_joints {
+        forces.force += gear.force;
+        forces.torque += gear.torque;
+    }
+}
+
+/// Removes [`GearJoint`]s whose force or torque exceeds the thresholds of their [`BreakableJoint`] component,
+/// and sends a [`JointBroken`] event for each broken joint.
+pub(crate) fn break_gear_joints(
+    mut commands: Commands,
+    gear_joints: Query<(Entity, &GearJoint, &BreakableJoint), Without<RigidBody>>,
+    mut broken_joints: EventWriter<JointBroken>,
+) {
+    for (entity, gear, thresholds) in &gear_joints {
+        if thresholds.is_exceeded(gear.force, gear.torque) {
+            commands.entity(entity).remove::<GearJoint>();
+
+            broken_joints.send(JointBroken {
+                joint: entity,
+                entity1: gear.joint1,
+                entity2: gear.joint2,
+                force: gear.force,
+                torque: gear.torque,
+            });
+        }
+    }
+}
@@ -0,0 +1,547 @@
+//! [`GenericJoint`] component.
+
+use super::impulse::{push_limit_rows, world_anchors};
+use crate::{dynamics::solver::xpbd::*, prelude::*};
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+/// The allowed relative motion along or around an axis of a [`GenericJoint`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, PartialEq)]
+pub enum AxisMotion {
+    /// No relative motion is allowed along the axis.
+    #[default]
+    Locked,
+    /// Relative motion is allowed along the axis within the given limits.
+    ///
+    /// For linear axes, the limits are distances. For angular axes, they are angles in radians.
+    Limited {
+        /// The lower limit.
+        min: Scalar,
+        /// The upper limit.
+        max: Scalar,
+    },
+    /// Relative motion along the axis is unrestricted.
+    Free,
+}
+
+/// A generic joint that can restrict each translational and rotational degree of freedom separately.
+///
+/// Each of the linear and angular axes can be [locked](AxisMotion::Locked), [limited](AxisMotion::Limited)
+/// or [free](AxisMotion::Free). The axes are the local coordinate axes of the first body.
+/// By default, all axes are locked, and the joint behaves like a [`FixedJoint`].
+///
+/// Generic joints are also known as D6 joints. They can be useful for things like vehicle suspensions
+/// and ragdolls, or for joints that are not covered by the other joint types, such as a slider that can also rotate.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     let entity1 = commands.spawn(RigidBody::Dynamic).id();
+///     let entity2 = commands.spawn(RigidBody::Dynamic).id();
+///
+///     // A slider along the X axis that can also rotate
+///     commands.spawn(
+///         GenericJoint::new(entity1, entity2)
+///             .with_linear_motion(0, AxisMotion::Limited { min: -1.0, max: 1.0 })
+#[cfg_attr(
+    feature = "2d",
+    doc = "            .with_angular_motion(AxisMotion::Free),"
+)]
+#[cfg_attr(
+    feature = "3d",
+    doc = "            .with_angular_motion(0, AxisMotion::Free),"
+)]
+///     );
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, MapEntities, PartialEq)]
+pub struct GenericJoint {
+    /// First entity constrained by the joint.
+    pub entity1: Entity,
+    /// Second entity constrained by the joint.
+    pub entity2: Entity,
+    /// Attachment point on the first body.
+    pub local_anchor1: Vector,
+    /// Attachment point on the second body.
+    pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
+    /// The allowed relative translation along the local coordinate axes of the first body.
+    pub linear_motion: [AxisMotion; DIM],
+    /// The allowed relative rotation around the local coordinate axes of the first body.
+    ///
+    /// In 2D, there is only one rotational axis.
+    pub angular_motion: [AxisMotion; ANGULAR_DIM],
+    /// The compliance of each linear axis, the inverse of stiffness, has the unit meters / Newton.
+    pub linear_compliance: [Scalar; DIM],
+    /// The compliance of each angular axis, the inverse of stiffness, has the unit radians / Newton-meter.
+    pub angular_compliance: [Scalar; ANGULAR_DIM],
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
+    /// Linear damping applied by the joint.
+    pub damping_linear: Scalar,
+    /// Angular damping applied by the joint.
+    pub damping_angular: Scalar,
+    /// Lagrange multipliers for the positional corrections along each linear axis.
+    pub linear_lagrange: [Scalar; DIM],
+    /// Lagrange multipliers for the angular corrections around each angular axis.
+    pub angular_lagrange: [Scalar; ANGULAR_DIM],
+    /// The force exerted by the joint.
+    pub force: Vector,
+    /// The torque exerted by the joint.
+    pub torque: Torque,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
+}
+
+impl XpbdConstraint<2> for GenericJoint {
+    fn entities(&self) -> [Entity; 2] {
+        [self.entity1, self.entity2]
+    }
+
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
+    fn clear_lagrange_multipliers(&mut self) {
+        self.linear_lagrange = [0.0; DIM];
+        self.angular_lagrange = [0.0; ANGULAR_DIM];
+    }
+
+    fn solve(&mut self, bodies: [&mut RigidBodyQueryItem; 2], dt: Scalar) {
+        let [body1, body2] = bodies;
+
+        // Constrain the relative rotation of the bodies
+        self.torque = self.constrain_rotations(body1, body2, dt);
+
+        // Constrain the relative positions of the bodies
+        self.force = self.constrain_positions(body1, body2, dt);
+    }
+}
+
+impl Joint for GenericJoint {
+    fn new(entity1: Entity, entity2: Entity) -> Self {
+        Self {
+            entity1,
+            entity2,
+            local_anchor1: Vector::ZERO,
+            local_anchor2: Vector::ZERO,
+            collisions_disabled: false,
+            linear_motion: [AxisMotion::Locked; DIM],
+            angular_motion: [AxisMotion::Locked; ANGULAR_DIM],
+            linear_compliance: [0.0; DIM],
+            angular_compliance: [0.0; ANGULAR_DIM],
+            softness: None,
+            damping_linear: 1.0,
+            damping_angular: 1.0,
+            linear_lagrange: [0.0; DIM],
+            angular_lagrange: [0.0; ANGULAR_DIM],
+            force: Vector::ZERO,
+            #[cfg(feature = "2d")]
+            torque: 0.0,
+            #[cfg(feature = "3d")]
+            torque: Vector::ZERO,
+            impulses: JointImpulses::default(),
+        }
+    }
+
+    fn with_compliance(self, compliance: Scalar) -> Self {
+        Self {
+            linear_compliance: [compliance; DIM],
+            angular_compliance: [compliance; ANGULAR_DIM],
+            ..self
+        }
+    }
+
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
+    fn with_local_anchor_1(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor1: anchor,
+            ..self
+        }
+    }
+
+    fn with_local_anchor_2(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor2: anchor,
+            ..self
+        }
+    }
+
+    fn with_linear_velocity_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_linear: damping,
+            ..self
+        }
+    }
+
+    fn with_angular_velocity_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_angular: damping,
+            ..self
+        }
+    }
+
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
+    fn local_anchor_1(&self) -> Vector {
+        self.local_anchor1
+    }
+
+    fn local_anchor_2(&self) -> Vector {
+        self.local_anchor2
+    }
+
+    fn damping_linear(&self) -> Scalar {
+        self.damping_linear
+    }
+
+    fn damping_angular(&self) -> Scalar {
+        self.damping_angular
+    }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force
+    }
+
+    fn torque(&self) -> Torque {
+        self.torque
+    }
+}
+
+impl GenericJoint {
+    /// Sets the allowed relative translation along the linear axis with the given index.
+    ///
+    /// The indices `0`, `1` and `2` correspond to the local X, Y and Z axes of the first body.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn with_linear_motion(mut self, axis: usize, motion: AxisMotion) -> Self {
+        self.linear_motion[axis] = motion;
+        self
+    }
+
+    /// Sets the allowed relative rotation.
+    #[cfg(feature = "2d")]
+    pub fn with_angular_motion(mut self, motion: AxisMotion) -> Self {
+        self.angular_motion[0] = motion;
+        self
+    }
+
+    /// Sets the allowed relative rotation around the angular axis with the given index.
+    ///
+    /// The indices `0`, `1` and `2` correspond to the local X, Y and Z axes of the first body.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    #[cfg(feature = "3d")]
+    pub fn with_angular_motion(mut self, axis: usize, motion: AxisMotion) -> Self {
+        self.angular_motion[axis] = motion;
+        self
+    }
+
+    /// Sets the compliance of the linear axis with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn with_linear_compliance(mut self, axis: usize, compliance: Scalar) -> Self {
+        self.linear_compliance[axis] = compliance;
+        self
+    }
+
+    /// Sets the compliance of the rotational axis.
+    #[cfg(feature = "2d")]
+    pub fn with_angular_compliance(mut self, compliance: Scalar) -> Self {
+        self.angular_compliance[0] = compliance;
+        self
+    }
+
+    /// Sets the compliance of the angular axis with the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    #[cfg(feature = "3d")]
+    pub fn with_angular_compliance(mut self, axis: usize, compliance: Scalar) -> Self {
+        self.angular_compliance[axis] = compliance;
+        self
+    }
+
+    /// Constrains the relative positions of the bodies along each linear axis that is not free.
+    ///
+    /// Returns the force exerted by this constraint.
+    fn constrain_positions(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Vector {
+        let mut force = Vector::ZERO;
+
+        for i in 0..DIM {
+            let limits = match self.linear_motion[i] {
+                AxisMotion::Locked => DistanceLimit::ZERO,
+                AxisMotion::Limited { min, max } => DistanceLimit::new(min, max),
+                AxisMotion::Free => continue,
+            };
+
+            // The previous axes may have moved the bodies, so the anchors are recomputed for each axis.
+            let world_r1 = *body1.rotation * self.local_anchor1;
+            let world_r2 = *body2.rotation * self.local_anchor2;
+            let axis = *body1.rotation * Vector::AXES[i];
+
+            let delta_x = limits.compute_correction_along_axis(
+                body1.current_position() + world_r1,
+                body2.current_position() + world_r2,
+                axis,
+            );
+
+            let magnitude = delta_x.length();
+
+            if magnitude <= Scalar::EPSILON {
+                continue;
+            }
+
+            let dir = delta_x / magnitude;
+
+            // Compute generalized inverse masses
+            let w1 =
+                PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, dir);
+            let w2 =
+                PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, dir);
+
+            // Compute Lagrange multiplier update
+            let delta_lagrange = self.compute_lagrange_update(
+                self.linear_lagrange[i],
+                magnitude,
+                &[w1, w2],
+                self.linear_compliance[i],
+                dt,
+            );
+            self.linear_lagrange[i] += delta_lagrange;
+
+            // Apply positional correction along the axis
+            self.apply_positional_lagrange_update(
+                body1,
+                body2,
+                delta_lagrange,
+                dir,
+                world_r1,
+                world_r2,
+            );
+
+            force += self.compute_force(self.linear_lagrange[i], dir, dt);
+        }
+
+        force
+    }
+
+    /// Constrains the relative rotation of the bodies around the rotational axis if it is not free.
+    ///
+    /// Returns the torque exerted by this constraint.
+    #[cfg(feature = "2d")]
+    fn constrain_rotations(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Torque {
+        let limits = match self.angular_motion[0] {
+            AxisMotion::Locked => AngleLimit::ZERO,
+            AxisMotion::Limited { min, max } => AngleLimit::new(min, max),
+            AxisMotion::Free => return Torque::ZERO,
+        };
+
+        let Some(correction) = limits.compute_correction(*body1.rotation, *body2.rotation, PI)
+        else {
+            return Torque::ZERO;
+        };
+
+        let mut lagrange = self.angular_lagrange[0];
+        let torque = self.align_orientation(
+            body1,
+            body2,
+            correction,
+            &mut lagrange,
+            self.angular_compliance[0],
+            dt,
+        );
+        self.angular_lagrange[0] = lagrange;
+        torque
+    }
+
+    /// Constrains the relative rotation of the bodies around each angular axis that is not free.
+    ///
+    /// Returns the torque exerted by this constraint.
+    #[cfg(feature = "3d")]
+    fn constrain_rotations(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Torque {
+        let mut torque = Vector::ZERO;
+
+        for i in 0..ANGULAR_DIM {
+            let limits = match self.angular_motion[i] {
+                AxisMotion::Locked => AngleLimit::ZERO,
+                AxisMotion::Limited { min, max } => AngleLimit::new(min, max),
+                AxisMotion::Free => continue,
+            };
+
+            // The angle around the axis `n` is measured between a perpendicular reference axis
+            // on the first body and the same axis on the second body projected onto the plane of rotation.
+            let n = *body1.rotation * Vector::AXES[i];
+            let n1 = *body1.rotation * Vector::AXES[(i + 1) % 3];
+            let b2 = *body2.rotation * Vector::AXES[(i + 1) % 3];
+            let n2 = b2 - n.dot(b2) * n;
+            let n2_magnitude = n2.length();
+
+            if n2_magnitude <= Scalar::EPSILON {
+                continue;
+            }
+
+            let n2 = n2 / n2_magnitude;
+
+            if let Some(correction) = limits.compute_correction(n, n1, n2, PI) {
+                let mut lagrange = self.angular_lagrange[i];
+                torque += self.align_orientation(
+                    body1,
+                    body2,
+                    correction,
+                    &mut lagrange,
+                    self.angular_compliance[i],
+                    dt,
+                );
+                self.angular_lagrange[i] = lagrange;
+            }
+        }
+
+        torque
+    }
+}
+
+impl ImpulseJoint for GenericJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
+
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        _delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+        let offset = p2 - p1;
+
+        // The axes are attached to the first body, so its lever arm extends to the second attachment point
+        let r1 = r1 + offset;
+
+        // The lower limits and locked axes use the first slots, and the upper limits use the slots after them.
+        for i in 0..DIM {
+            let softness = JointRowSoftness::from_joint(self.softness, self.linear_compliance[i]);
+            let axis = *body1.rotation * Vector::AXES[i];
+            let position = offset.dot(axis);
+            let row = JointRow::linear(i, r1, r2, axis, position).with_softness(softness);
+
+            match self.linear_motion[i] {
+                AxisMotion::Locked => rows.push(row),
+                AxisMotion::Limited { min, max } => {
+                    push_limit_rows(rows, row, [i, DIM + i], position, min, max);
+                }
+                AxisMotion::Free => {}
+            }
+        }
+
+        for i in 0..ANGULAR_DIM {
+            let softness = JointRowSoftness::from_joint(self.softness, self.angular_compliance[i]);
+            let slot = 2 * DIM + i;
+
+            #[cfg(feature = "2d")]
+            let row = JointRow::angular(slot, body1.rotation.angle_between(*body2.rotation));
+            #[cfg(feature = "3d")]
+            let row = {
+                // The angle around the axis `n` is measured between a perpendicular reference axis
+                // on the first body and the same axis on the second body projected onto the plane of rotation.
+                let n = *body1.rotation * Vector::AXES[i];
+                let n1 = *body1.rotation * Vector::AXES[(i + 1) % 3];
+                let b2 = *body2.rotation * Vector::AXES[(i + 1) % 3];
+                let n2 = b2 - n.dot(b2) * n;
+
+                if n2.length_squared() <= Scalar::EPSILON {
+                    continue;
+                }
+
+                JointRow::angular(slot, n, n1.cross(n2).dot(n).atan2(n1.dot(n2)))
+            };
+            let row = row.with_softness(softness);
+
+            match self.angular_motion[i] {
+                AxisMotion::Locked => rows.push(row),
+                AxisMotion::Limited { min, max } => {
+                    let slots = [slot, slot + ANGULAR_DIM];
+                    push_limit_rows(rows, row, slots, row.error, min, max);
+                }
+                AxisMotion::Free => {}
+            }
+        }
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, torque: Torque) {
+        self.force = force;
+        self.torque = torque;
+    }
+}
+
+impl PositionConstraint for GenericJoint {}
+
+impl AngularConstraint for GenericJoint {}
+
+impl MapEntities for GenericJoint {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.entity1 = entity_mapper.map_entity(self.entity1);
+        self.entity2 = entity_mapper.map_entity(self.entity2);
+    }
+}
@@ -0,0 +1,625 @@
+//! Impulse-based solving for [joints](super).
+//!
+//! By default, joints are solved using the same substepped, impulse-based solver as contacts,
+//! TGS Soft. Each joint is described by a set of [`JointRow`]s, scalar velocity constraints
+//! that are rebuilt from the current poses of the bodies whenever the joint is solved.
+//!
+//! Like contacts, the rows are [warm started](crate::dynamics::solver::SubstepSolverSet::WarmStart)
+//! using the impulses accumulated during the previous substep, solved with a position bias
+//! based on the [`ContactSoftnessCoefficients`], and [relaxed](crate::dynamics::solver::SubstepSolverSet::Relax)
+//! without the bias to reduce overshooting. Soft joints, springs and motors use their own softness instead.
+//!
+//! The accumulated impulses are stored in the [`JointImpulses`] of each joint.
+//!
+//! Joints can also be solved using [XPBD](crate::dynamics::solver::xpbd) by setting
+//! [`SolverConfig::joint_solver`](crate::dynamics::solver::SolverConfig::joint_solver)
+//! to [`JointSolver::Xpbd`](crate::dynamics::solver::JointSolver::Xpbd).
+
+use crate::{
+    dynamics::solver::{softness_parameters::SoftnessCoefficients, ContactSoftnessCoefficients},
+    prelude::*,
+};
+use bevy::prelude::*;
+
+/// The maximum number of [`JointRow`]s that a single joint can use.
+pub const MAX_JOINT_ROWS: usize = 16;
+
+/// The angular part of a [`JointRow`] Jacobian. This is a scalar in 2D and a vector in 3D.
+#[cfg(feature = "2d")]
+pub type AngularJacobian = Scalar;
+/// The angular part of a [`JointRow`] Jacobian. This is a scalar in 2D and a vector in 3D.
+#[cfg(feature = "3d")]
+pub type AngularJacobian = Vector;
+
+/// The impulses accumulated by the [impulse-based joint solver](super#solvers) for each [`JointRow`] of a joint.
+///
+/// The impulses are indexed by [`JointRow::slot`], and they are used for
+/// [warm starting](crate::dynamics::solver::SubstepSolverSet::WarmStart) the next substep.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Default, PartialEq)]
+pub struct JointImpulses(pub [Scalar; MAX_JOINT_ROWS]);
+
+impl JointImpulses {
+    /// Returns the accumulated impulse of the row with the given `slot`.
+    pub fn get(&self, slot: usize) -> Scalar {
+        self.0[slot]
+    }
+
+    /// Resets the impulses of the slots that are not used by any of the given `rows`.
+    ///
+    /// This prevents stale impulses from being applied when a row becomes active again,
+    /// for example when a joint limit is violated.
+    pub fn retain_rows(&mut self, rows: &[JointRow]) {
+        let mut used = [false; MAX_JOINT_ROWS];
+        for row in rows {
+            used[row.slot] = true;
+        }
+        for (impulse, used) in self.0.iter_mut().zip(used) {
+            if !used {
+                *impulse = 0.0;
+            }
+        }
+    }
+}
+
+/// Determines how stiff a [`JointRow`] is.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum JointRowSoftness {
+    /// The row is rigid. When solved with a position bias, the error is corrected
+    /// using the [`ContactSoftnessCoefficients`].
+    #[default]
+    Rigid,
+    /// The row acts like a damped spring with the given damping ratio and frequency.
+    /// The behavior is independent of the masses of the bodies.
+    Soft(SoftnessParameters),
+    /// The row acts like a damped spring with the given stiffness and damping coefficients.
+    Spring {
+        /// The stiffness of the spring.
+        stiffness: Scalar,
+        /// The damping coefficient of the spring.
+        damping: Scalar,
+    },
+}
+
+impl JointRowSoftness {
+    /// Returns the softness used for the rows of a joint with the given [`SoftnessParameters`] and compliance.
+    ///
+    /// The softness parameters take priority over the compliance.
+    pub fn from_joint(softness: Option<SoftnessParameters>, compliance: Scalar) -> Self {
+        match softness {
+            Some(softness) => Self::Soft(softness),
+            None if compliance > 0.0 => Self::Spring {
+                stiffness: 1.0 / compliance,
+                damping: 0.0,
+            },
+            None => Self::Rigid,
+        }
+    }
+
+    /// Computes the [`SoftnessCoefficients`] for a row with the given effective `inverse_mass`.
+    ///
+    /// `rigid_coefficients` are used for rigid rows when `use_bias` is `true`.
+    /// Returns `None` if the row should not apply any impulse.
+    pub fn compute_coefficients(
+        self,
+        inverse_mass: Scalar,
+        rigid_coefficients: SoftnessCoefficients,
+        use_bias: bool,
+        delta_secs: Scalar,
+    ) -> Option<SoftnessCoefficients> {
+        match self {
+            Self::Rigid if use_bias => Some(rigid_coefficients),
+            Self::Rigid => Some(SoftnessCoefficients {
+                bias: 0.0,
+                mass_scale: 1.0,
+                impulse_scale: 0.0,
+            }),
+            Self::Soft(softness) => Some(softness.compute_coefficients(delta_secs)),
+            Self::Spring { stiffness, damping } => {
+                // Implicit spring-damper, see "Soft Constraints" by Erin Catto.
+                let denominator = damping + delta_secs * stiffness;
+                if denominator <= Scalar::EPSILON {
+                    return None;
+                }
+                let gamma = 1.0 / (delta_secs * denominator);
+                let mass_scale = inverse_mass / (inverse_mass + gamma);
+                Some(SoftnessCoefficients {
+                    bias: stiffness / denominator,
+                    mass_scale,
+                    impulse_scale: gamma / (inverse_mass + gamma),
+                })
+            }
+        }
+    }
+}
+
+/// A scalar velocity constraint solved by the [impulse-based joint solver](super#solvers).
+///
+/// The row constrains the relative velocity `J * v` of the bodies, where the Jacobian `J`
+/// consists of a linear and angular part for each body. Impulses are applied along the same Jacobian.
+///
+/// The solver drives the velocity towards the `target_velocity`, and the `error` towards zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JointRow {
+    /// The index of the row's accumulated impulse in the joint's [`JointImpulses`].
+    ///
+    /// The same constraint should use the same slot every substep so that it can be warm started.
+    pub slot: usize,
+    /// The linear part of the Jacobian for the first body.
+    pub linear1: Vector,
+    /// The angular part of the Jacobian for the first body.
+    pub angular1: AngularJacobian,
+    /// The linear part of the Jacobian for the second body.
+    pub linear2: Vector,
+    /// The angular part of the Jacobian for the second body.
+    pub angular2: AngularJacobian,
+    /// The position error of the constraint.
+    pub error: Scalar,
+    /// The target relative velocity along the row.
+    pub target_velocity: Scalar,
+    /// The minimum accumulated impulse.
+    pub min_impulse: Scalar,
+    /// The maximum accumulated impulse.
+    pub max_impulse: Scalar,
+    /// If `true`, the row is a limit, and a positive `error` is treated as a gap
+    /// that the bodies are allowed to close during the substep.
+    pub is_limit: bool,
+    /// The softness of the row.
+    pub softness: JointRowSoftness,
+}
+
+impl JointRow {
+    /// Creates a row that constrains the relative velocity of the points at `r1` and `r2` along the given `axis`.
+    ///
+    /// `r1` and `r2` are the world-space offsets of the points from the centers of mass of the bodies.
+    pub fn linear(slot: usize, r1: Vector, r2: Vector, axis: Vector, error: Scalar) -> Self {
+        Self {
+            slot,
+            linear1: -axis,
+            angular1: -cross(r1, axis),
+            linear2: axis,
+            angular2: cross(r2, axis),
+            error,
+            target_velocity: 0.0,
+            min_impulse: Scalar::NEG_INFINITY,
+            max_impulse: Scalar::INFINITY,
+            is_limit: false,
+            softness: JointRowSoftness::Rigid,
+        }
+    }
+
+    /// Creates a row that constrains the relative angular velocity of the bodies.
+    #[cfg(feature = "2d")]
+    pub fn angular(slot: usize, error: Scalar) -> Self {
+        Self {
+            slot,
+            linear1: Vector::ZERO,
+            angular1: -1.0,
+            linear2: Vector::ZERO,
+            angular2: 1.0,
+            error,
+            target_velocity: 0.0,
+            min_impulse: Scalar::NEG_INFINITY,
+            max_impulse: Scalar::INFINITY,
+            is_limit: false,
+            softness: JointRowSoftness::Rigid,
+        }
+    }
+
+    /// Creates a row that constrains the relative angular velocity of the bodies around the given `axis`.
+    #[cfg(feature = "3d")]
+    pub fn angular(slot: usize, axis: Vector, error: Scalar) -> Self {
+        Self {
+            slot,
+            linear1: Vector::ZERO,
+            angular1: -axis,
+            linear2: Vector::ZERO,
+            angular2: axis,
+            error,
+            target_velocity: 0.0,
+            min_impulse: Scalar::NEG_INFINITY,
+            max_impulse: Scalar::INFINITY,
+            is_limit: false,
+            softness: JointRowSoftness::Rigid,
+        }
+    }
+
+    /// Turns the row into a limit that only pushes the error to be non-negative.
+    pub fn as_limit(self) -> Self {
+        Self {
+            min_impulse: 0.0,
+            max_impulse: Scalar::INFINITY,
+            is_limit: true,
+            ..self
+        }
+    }
+
+    /// Turns the row into a row driven by the given [`JointMotor`].
+    ///
+    /// The `error` of the row should be the current position along the motor axis.
+    pub fn with_motor(self, motor: &JointMotor, delta_secs: Scalar) -> Self {
+        let max_impulse = motor.max_force * delta_secs;
+        Self {
+            error: self.error - motor.target_position,
+            target_velocity: motor.target_velocity,
+            min_impulse: -max_impulse,
+            max_impulse,
+            softness: JointRowSoftness::Spring {
+                stiffness: motor.stiffness,
+                damping: motor.damping,
+            },
+            ..self
+        }
+    }
+
+    /// Returns the row with the Jacobian negated.
+    pub fn negated(self) -> Self {
+        Self {
+            linear1: -self.linear1,
+            angular1: -self.angular1,
+            linear2: -self.linear2,
+            angular2: -self.angular2,
+            ..self
+        }
+    }
+
+    /// Sets the softness of the row.
+    pub fn with_softness(self, softness: JointRowSoftness) -> Self {
+        Self { softness, ..self }
+    }
+
+    /// Sets the minimum and maximum accumulated impulse of the row.
+    pub fn with_bounds(self, min_impulse: Scalar, max_impulse: Scalar) -> Self {
+        Self {
+            min_impulse,
+            max_impulse,
+            ..self
+        }
+    }
+
+    /// Sets the target relative velocity along the row.
+    pub fn with_target_velocity(self, target_velocity: Scalar) -> Self {
+        Self {
+            target_velocity,
+            ..self
+        }
+    }
+
+    /// Computes the effective inverse mass of the row, taking into account
+    /// which bodies the impulses are applied to.
+    pub fn inverse_mass(&self, body1: &RigidBodyQueryItem, body2: &RigidBodyQueryItem) -> Scalar {
+        let mut inverse_mass = 0.0;
+        if applies_to(body1, body2) {
+            inverse_mass += (self.linear1 * body1.effective_inv_mass()).dot(self.linear1)
+                + angular_inverse_mass(body1.effective_world_inv_inertia(), self.angular1);
+        }
+        if applies_to(body2, body1) {
+            inverse_mass += (self.linear2 * body2.effective_inv_mass()).dot(self.linear2)
+                + angular_inverse_mass(body2.effective_world_inv_inertia(), self.angular2);
+        }
+        inverse_mass
+    }
+
+    /// Computes the relative velocity of the bodies along the row.
+    pub fn relative_velocity(
+        &self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+    ) -> Scalar {
+        #[cfg(feature = "2d")]
+        let angular =
+            self.angular1 * body1.angular_velocity.0 + self.angular2 * body2.angular_velocity.0;
+        #[cfg(feature = "3d")]
+        let angular = self.angular1.dot(body1.angular_velocity.0)
+            + self.angular2.dot(body2.angular_velocity.0);
+
+        self.linear1.dot(body1.linear_velocity.0)
+            + self.linear2.dot(body2.linear_velocity.0)
+            + angular
+    }
+
+    /// Applies the given `impulse` along the row.
+    pub fn apply_impulse(
+        &self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        impulse: Scalar,
+    ) {
+        let applies1 = applies_to(body1, body2);
+        let applies2 = applies_to(body2, body1);
+
+        if applies1 {
+            let inv_mass1 = body1.effective_inv_mass();
+            let inv_inertia1 = body1.effective_world_inv_inertia();
+            body1.linear_velocity.0 += inv_mass1 * self.linear1 * impulse;
+            body1.angular_velocity.0 += inv_inertia1 * (self.angular1 * impulse);
+        }
+        if applies2 {
+            let inv_mass2 = body2.effective_inv_mass();
+            let inv_inertia2 = body2.effective_world_inv_inertia();
+            body2.linear_velocity.0 += inv_mass2 * self.linear2 * impulse;
+            body2.angular_velocity.0 += inv_inertia2 * (self.angular2 * impulse);
+        }
+    }
+
+    /// Computes the change in the `accumulated_impulse` of the row for the given
+    /// effective `inverse_mass` and `relative_velocity`, and updates the accumulated impulse.
+    ///
+    /// If `use_bias` is `true`, the impulse is boosted to correct the position error.
+    pub fn compute_impulse(
+        &self,
+        accumulated_impulse: &mut Scalar,
+        inverse_mass: Scalar,
+        relative_velocity: Scalar,
+        rigid_coefficients: SoftnessCoefficients,
+        use_bias: bool,
+        delta_secs: Scalar,
+    ) -> Scalar {
+        if inverse_mass <= Scalar::EPSILON {
+            return 0.0;
+        }
+
+        let coefficients = if self.is_limit && self.error > 0.0 {
+            // Speculative limit: allow the bodies to close the gap during the substep.
+            SoftnessCoefficients {
+                bias: 1.0 / delta_secs,
+                mass_scale: 1.0,
+                impulse_scale: 0.0,
+            }
+        } else {
+            let Some(coefficients) = self.softness.compute_coefficients(
+                inverse_mass,
+                rigid_coefficients,
+                use_bias,
+                delta_secs,
+            ) else {
+                return 0.0;
+            };
+            coefficients
+        };
+
+        let velocity = relative_velocity - self.target_velocity;
+        let impulse = -coefficients.mass_scale * (velocity + coefficients.bias * self.error)
+            / inverse_mass
+            - coefficients.impulse_scale * *accumulated_impulse;
+
+        // Clamp the accumulated impulse.
+        let new_impulse =
+            (*accumulated_impulse + impulse).clamp(self.min_impulse, self.max_impulse);
+        let impulse = new_impulse - *accumulated_impulse;
+        *accumulated_impulse = new_impulse;
+
+        impulse
+    }
+
+    /// Solves the row, applying an impulse to the bodies and updating the `accumulated_impulse`.
+    pub fn solve(
+        &self,
+        accumulated_impulse: &mut Scalar,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        rigid_coefficients: SoftnessCoefficients,
+        use_bias: bool,
+        delta_secs: Scalar,
+    ) {
+        let inverse_mass = self.inverse_mass(body1, body2);
+        let relative_velocity = self.relative_velocity(body1, body2);
+        let impulse = self.compute_impulse(
+            accumulated_impulse,
+            inverse_mass,
+            relative_velocity,
+            rigid_coefficients,
+            use_bias,
+            delta_secs,
+        );
+        if impulse != 0.0 {
+            self.apply_impulse(body1, body2, impulse);
+        }
+    }
+}
+
+/// Returns `true` if joint impulses should be applied to `body`, taking into account
+/// the [dominance](Dominance) of the `other` body.
+fn applies_to(body: &RigidBodyQueryItem, other: &RigidBodyQueryItem) -> bool {
+    body.rb.is_dynamic() && body.dominance() <= other.dominance()
+}
+
+#[cfg(feature = "2d")]
+fn angular_inverse_mass(inverse_inertia: Scalar, axis: AngularJacobian) -> Scalar {
+    axis * inverse_inertia * axis
+}
+
+#[cfg(feature = "3d")]
+fn angular_inverse_mass(inverse_inertia: Matrix3, axis: AngularJacobian) -> Scalar {
+    axis.dot(inverse_inertia * axis)
+}
+
+/// A trait for [joints](super) that can be solved using the [impulse-based joint solver](super#solvers).
+pub trait ImpulseJoint: Joint {
+    /// Returns the impulses accumulated for each [`JointRow`] of the joint.
+    fn impulses(&self) -> &JointImpulses;
+
+    /// Returns a mutable reference to the impulses accumulated for each [`JointRow`] of the joint.
+    fn impulses_mut(&mut self) -> &mut JointImpulses;
+
+    /// Computes the [`JointRow`]s of the joint for the current poses of the bodies and pushes them to `rows`.
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    );
+
+    /// Computes the force and torque exerted by the joint from the accumulated impulses of the given `rows`.
+    ///
+    /// By default, this is the force and torque applied to the second body.
+    fn compute_force_and_torque(&self, rows: &[JointRow], delta_secs: Scalar) -> (Vector, Torque) {
+        let mut force = Vector::ZERO;
+        #[cfg(feature = "2d")]
+        let mut torque: Torque = 0.0;
+        #[cfg(feature = "3d")]
+        let mut torque = Vector::ZERO;
+
+        for row in rows {
+            let impulse = self.impulses().get(row.slot);
+            if row.linear1 == Vector::ZERO && row.linear2 == Vector::ZERO {
+                torque += row.angular2 * impulse / delta_secs;
+            } else {
+                force += row.linear2 * impulse / delta_secs;
+            }
+        }
+
+        (force, torque)
+    }
+
+    /// Stores the force and torque exerted by the joint during the last substep.
+    fn set_force_and_torque(&mut self, force: Vector, torque: Torque);
+}
+
+/// Computes the world-space offsets of the joint anchors from the centers of mass of the bodies,
+/// and the world-space positions of the anchors.
+pub(crate) fn world_anchors(
+    body1: &RigidBodyQueryItem,
+    body2: &RigidBodyQueryItem,
+    local_anchor1: Vector,
+    local_anchor2: Vector,
+) -> [Vector; 4] {
+    let r1 = *body1.rotation * (local_anchor1 - body1.center_of_mass.0);
+    let r2 = *body2.rotation * (local_anchor2 - body2.center_of_mass.0);
+    let p1 = body1.current_position() + *body1.rotation * local_anchor1;
+    let p2 = body2.current_position() + *body2.rotation * local_anchor2;
+    [r1, r2, p1, p2]
+}
+
+/// Pushes rows that keep the anchors of the bodies at the same position, one row for each world axis.
+///
+/// The rows use the slots from `0` to `DIM - 1`.
+pub(crate) fn push_point_rows(
+    rows: &mut Vec<JointRow>,
+    r1: Vector,
+    r2: Vector,
+    separation: Vector,
+    softness: JointRowSoftness,
+) {
+    #[cfg(feature = "2d")]
+    let axes = [Vector::X, Vector::Y];
+    #[cfg(feature = "3d")]
+    let axes = [Vector::X, Vector::Y, Vector::Z];
+
+    for (slot, axis) in axes.into_iter().enumerate() {
+        rows.push(
+            JointRow::linear(slot, r1, r2, axis, separation.dot(axis)).with_softness(softness),
+        );
+    }
+}
+
+/// Pushes limit rows that keep the `position` along the Jacobian of the given `row` between `min` and `max`.
+///
+/// The lower and upper limits use the given `slots`.
+pub(crate) fn push_limit_rows(
+    rows: &mut Vec<JointRow>,
+    row: JointRow,
+    slots: [usize; 2],
+    position: Scalar,
+    min: Scalar,
+    max: Scalar,
+) {
+    rows.push(
+        JointRow {
+            slot: slots[0],
+            error: position - min,
+            ..row
+        }
+        .as_limit(),
+    );
+    rows.push(
+        JointRow {
+            slot: slots[1],
+            error: max - position,
+            ..row.negated()
+        }
+        .as_limit(),
+    );
+}
+
+/// Pushes rows that lock the relative rotation of the bodies.
+///
+/// The rows use the slots from `first_slot` to `first_slot + ANGULAR_DIM - 1`.
+pub(crate) fn push_angular_lock_rows(
+    rows: &mut Vec<JointRow>,
+    rotation1: &Rotation,
+    rotation2: &Rotation,
+    first_slot: usize,
+    softness: JointRowSoftness,
+) {
+    #[cfg(feature = "2d")]
+    {
+        let angle = rotation1.angle_between(*rotation2);
+        rows.push(JointRow::angular(first_slot, angle).with_softness(softness));
+    }
+    #[cfg(feature = "3d")]
+    {
+        // The rotation from the first body to the second body, using the shortest arc.
+        let mut difference = rotation2.0 * rotation1.0.inverse();
+        if difference.w < 0.0 {
+            difference = -difference;
+        }
+        let error = 2.0 * difference.xyz();
+
+        for (i, axis) in [Vector::X, Vector::Y, Vector::Z].into_iter().enumerate() {
+            rows.push(
+                JointRow::angular(first_slot + i, axis, error.dot(axis)).with_softness(softness),
+            );
+        }
+    }
+}
+
+/// Pushes two rows that align `axis2` on the second body with `axis1` on the first body,
+/// leaving the bodies free to rotate around the axis.
+///
+/// The rows use the slots `first_slot` and `first_slot + 1`.
+#[cfg(feature = "3d")]
+pub(crate) fn push_axis_alignment_rows(
+    rows: &mut Vec<JointRow>,
+    axis1: Vector,
+    axis2: Vector,
+    first_slot: usize,
+    softness: JointRowSoftness,
+) {
+    let error = axis1.cross(axis2);
+    let (b, c) = axis1.any_orthonormal_pair();
+    rows.push(JointRow::angular(first_slot, b, error.dot(b)).with_softness(softness));
+    rows.push(JointRow::angular(first_slot + 1, c, error.dot(c)).with_softness(softness));
+}
+
+/// Computes the relative angle of the bodies around the `local_axis` of the first body,
+/// along with the axis in world space.
+#[cfg(feature = "3d")]
+pub(crate) fn relative_angle(
+    rotation1: &Rotation,
+    rotation2: &Rotation,
+    local_axis: Vector,
+) -> (Vector, Scalar) {
+    // The angle is measured using perpendicular reference axes.
+    let axis = *rotation1 * local_axis;
+    let b = local_axis.any_orthonormal_vector();
+    let b1 = *rotation1 * b;
+    let b2 = *rotation2 * b;
+    (axis, b1.cross(b2).dot(axis).atan2(b1.dot(b2)))
+}
+
+/// Returns the [`SoftnessCoefficients`] used for rigid joint rows between the given bodies.
+pub(crate) fn rigid_coefficients(
+    coefficients: &ContactSoftnessCoefficients,
+    body1: &RigidBodyQueryItem,
+    body2: &RigidBodyQueryItem,
+) -> SoftnessCoefficients {
+    if body1.rb.is_dynamic() && body2.rb.is_dynamic() {
+        coefficients.dynamic
+    } else {
+        coefficients.non_dynamic
+    }
+}
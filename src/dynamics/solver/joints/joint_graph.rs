@@ -0,0 +1,239 @@
+//! [`JointGraph`] resource for finding bodies that are connected by joints.
+
+use crate::prelude::*;
+use bevy::{
+    ecs::system::EntityCommand,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use std::collections::VecDeque;
+
+/// A graph of the rigid bodies connected by [joints](super), where bodies are nodes and joints are edges.
+///
+/// Joints are standalone components that only reference the entities they connect, so this resource
+/// is used to find the joints attached to a body or the connected assembly of bodies and joints,
+/// like a ragdoll or a chain, without iterating over every joint.
+///
+/// The graph is updated automatically before the [broad phase](PhysicsStepSet::BroadPhase)
+/// when joints are added, changed or removed. It contains all joints that implement the [`Joint`] trait,
+/// along with [`GearJoint`]s, which connect the second bodies of the joints they couple.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Ragdoll;
+///
+/// fn despawn_ragdolls(
+///     mut commands: Commands,
+///     ragdolls: Query<Entity, With<Ragdoll>>,
+///     joint_graph: Res<JointGraph>,
+/// ) {
+///     for entity in &ragdolls {
+///         info!("Despawning {} joints", joint_graph.connected_component(entity).joints.len());
+///
+///         // Despawn all bodies and joints connected to the entity
+///         commands.entity(entity).add(DespawnJointAssembly);
+///     }
+/// }
+/// ```
+#[derive(Resource, Clone, Debug, Default)]
+pub struct JointGraph {
+    /// The pair of bodies for each joint entity.
+    joints: HashMap<Entity, (Entity, Entity)>,
+    /// The joint entities attached to each body.
+    body_joints: HashMap<Entity, Vec<Entity>>,
+    /// The bodies in the graph that are not [dynamic](RigidBody::Dynamic).
+    /// Connected components are not traversed through these bodies.
+    non_dynamic_bodies: HashSet<Entity>,
+}
+
+/// A set of bodies and the joints connecting them, returned by [`JointGraph::connected_component`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JointAssembly {
+    /// The bodies in the assembly, in breadth-first order starting from the body that was queried.
+    pub bodies: Vec<Entity>,
+    /// The joints connecting the bodies in the assembly.
+    pub joints: Vec<Entity>,
+}
+
+impl JointGraph {
+    /// Returns the bodies connected by the given joint entity, or `None` if the joint is not in the graph.
+    pub fn bodies(&self, joint: Entity) -> Option<(Entity, Entity)> {
+        self.joints.get(&joint).copied()
+    }
+
+    /// Returns an iterator over all joint entities in the graph.
+    pub fn joints(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.joints.keys().copied()
+    }
+
+    /// Returns an iterator over the joint entities attached to the given body.
+    pub fn joints_of(&self, body: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.body_joints.get(&body).into_iter().flatten().copied()
+    }
+
+    /// Returns an iterator over the bodies that are directly connected to the given body by a joint.
+    ///
+    /// If several joints connect the same bodies, the connected body is returned once for each joint.
+    pub fn connected_bodies(&self, body: Entity) -> impl Iterator<Item = Entity> + '_ {
+        self.joints_of(body).filter_map(move |joint| {
+            let (body1, body2) = self.joints.get(&joint)?;
+            Some(if *body1 == body { *body2 } else { *body1 })
+        })
+    }
+
+    /// Returns `true` if the given bodies are directly connected by a joint.
+    pub fn are_connected(&self, body1: Entity, body2: Entity) -> bool {
+        self.connected_bodies(body1).any(|body| body == body2)
+    }
+
+    /// Returns the bodies and joints that are connected to the given body, directly or through other bodies.
+    ///
+    /// The bodies are traversed in breadth-first order, so the first body is always the given `body`.
+    ///
+    /// Static and kinematic bodies are included in the assembly, but the traversal doesn't continue through them
+    /// unless they are the given `body`. This way, two ropes hanging from the same static anchor are separate assemblies.
+    pub fn connected_component(&self, body: Entity) -> JointAssembly {
+        let mut assembly = JointAssembly::default();
+        let mut visited_bodies = HashSet::new();
+        let mut visited_joints = HashSet::new();
+        let mut queue = VecDeque::from([body]);
+        visited_bodies.insert(body);
+
+        while let Some(current) = queue.pop_front() {
+            assembly.bodies.push(current);
+
+            if current != body && self.non_dynamic_bodies.contains(&current) {
+                continue;
+            }
+
+            for joint in self.joints_of(current) {
+                if !visited_joints.insert(joint) {
+                    continue;
+                }
+                assembly.joints.push(joint);
+
+                let Some((body1, body2)) = self.bodies(joint) else {
+                    continue;
+                };
+                let other = if body1 == current { body2 } else { body1 };
+                if visited_bodies.insert(other) {
+                    queue.push_back(other);
+                }
+            }
+        }
+
+        assembly
+    }
+
+    /// Returns all sets of bodies and joints that are connected to each other.
+    ///
+    /// Bodies that are not attached to any joint are not included. Like in [`JointGraph::connected_component`],
+    /// the traversal doesn't continue through static and kinematic bodies, so they can be included
+    /// in several components.
+    pub fn connected_components(&self) -> Vec<JointAssembly> {
+        let mut components = Vec::new();
+        let mut visited_bodies = HashSet::new();
+
+        for &body in self.body_joints.keys() {
+            if visited_bodies.contains(&body) || self.non_dynamic_bodies.contains(&body) {
+                continue;
+            }
+            let component = self.connected_component(body);
+            visited_bodies.extend(component.bodies.iter().copied());
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// Adds a joint connecting `body1` and `body2` to the graph, replacing the previous edge of the joint.
+    pub(crate) fn insert(&mut self, joint: Entity, body1: Entity, body2: Entity) {
+        self.remove(joint);
+        self.joints.insert(joint, (body1, body2));
+        self.body_joints.entry(body1).or_default().push(joint);
+        if body1 != body2 {
+            self.body_joints.entry(body2).or_default().push(joint);
+        }
+    }
+
+    /// Removes the given joint from the graph.
+    pub(crate) fn remove(&mut self, joint: Entity) {
+        let Some((body1, body2)) = self.joints.remove(&joint) else {
+            return;
+        };
+        for body in [body1, body2] {
+            if let Some(joints) = self.body_joints.get_mut(&body) {
+                joints.retain(|&entity| entity != joint);
+                if joints.is_empty() {
+                    self.body_joints.remove(&body);
+                    self.non_dynamic_bodies.remove(&body);
+                }
+            }
+        }
+    }
+
+    /// Updates which bodies in the graph are [dynamic](RigidBody::Dynamic) using the given function.
+    /// Connected components are not traversed through bodies that are not dynamic.
+    pub(crate) fn update_body_types(&mut self, is_dynamic: impl Fn(Entity) -> bool) {
+        self.non_dynamic_bodies.clear();
+        self.non_dynamic_bodies.extend(
+            self.body_joints
+                .keys()
+                .copied()
+                .filter(|&body| !is_dynamic(body)),
+        );
+    }
+}
+
+/// An [`EntityCommand`] that despawns a body along with all bodies and joints connected to it,
+/// as given by [`JointGraph::connected_component`].
+///
+/// The entities are despawned recursively, so child entities like colliders are despawned too.
+/// Only [dynamic](RigidBody::Dynamic) bodies are despawned, so static and kinematic bodies
+/// like anchors stay in the world along with any other assemblies attached to them.
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn despawn_assembly(mut commands: Commands, body: Entity) {
+///     commands.entity(body).add(DespawnJointAssembly);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DespawnJointAssembly;
+
+impl EntityCommand for DespawnJointAssembly {
+    fn apply(self, id: Entity, world: &mut World) {
+        let assembly = world.get_resource::<JointGraph>().map_or_else(
+            || JointAssembly {
+                bodies: vec![id],
+                joints: vec![],
+            },
+            |graph| graph.connected_component(id),
+        );
+
+        let dynamic_bodies = assembly
+            .bodies
+            .into_iter()
+            .filter(|&body| {
+                world
+                    .get::<RigidBody>(body)
+                    .is_some_and(RigidBody::is_dynamic)
+            })
+            .collect::<Vec<_>>();
+
+        for entity in assembly.joints.into_iter().chain(dynamic_bodies) {
+            if let Some(entity_mut) = world.get_entity_mut(entity) {
+                entity_mut.despawn_recursive();
+            }
+        }
+    }
+}
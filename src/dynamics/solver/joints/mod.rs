@@ -1,6 +1,5 @@
 //! **Joints** are a way to connect entities in a way that restricts their movement relative to each other.
-//! They act as [constraints](dynamics::solver::xpbd#constraints) that restrict different *Degrees Of Freedom*
-//! depending on the joint type.
+//! They act as constraints that restrict different *Degrees Of Freedom* depending on the joint type.
 //!
 //! ## Degrees Of Freedom (DOF)
 //!
@@ -22,6 +21,11 @@
     feature = "3d",
     doc = "| [`SphericalJoint`] | 1 Rotation                | 3 Rotations                 |"
 )]
+//! | [`GenericJoint`]   | Configurable              | Configurable                |
+//! | [`PulleyJoint`]    | All but 1 (coupled)       | All but 1 (coupled)         |
+//! | [`WheelJoint`]     | 1 Translation, 1 Rotation | 1 Translation, 1 Rotation   |
+//! | [`GearJoint`]      | Couples two joints        | Couples two joints          |
+//! | [`TargetJoint`]    | All, pulled to a target   | All, pulled to a target     |
 //!
 //! ## Using joints
 //!
@@ -49,6 +53,29 @@
 //! *Compliance* refers to the inverse of stiffness, so using a compliance of 0 corresponds to
 //! infinite stiffness.
 //!
+//! Compliance depends on the masses of the bodies, which can make it difficult to tune.
+//! Alternatively, joints can be made soft using [`SoftnessParameters`] with the `with_softness` method.
+//! The softness is given as a damping ratio and a frequency in Hertz, which behave consistently
+//! regardless of the masses of the bodies and the [`SubstepCount`].
+//!
+//! ```
+#![cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#![cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+//! use bevy::prelude::*;
+//!
+//! fn setup(mut commands: Commands) {
+//!     let entity1 = commands.spawn(RigidBody::Static).id();
+//!     let entity2 = commands.spawn(RigidBody::Dynamic).id();
+//!
+//!     // A springy suspension that oscillates 5 times per second
+//!     commands.spawn(
+//!         DistanceJoint::new(entity1, entity2)
+//!             .with_rest_length(1.0)
+//!             .with_softness(SoftnessParameters::new(0.5, 5.0)),
+//!     );
+//! }
+//! ```
+//!
 //! ### Attachment positions
 //!
 //! By default, joints are connected to the centers of entities, but attachment positions can be used to change this.
@@ -62,6 +89,77 @@
 //! `with_angular_velocity_damping` methods. Increasing the damping values will cause the velocities
 //! of the connected entities to decrease faster.
 //!
+//! ### Collisions between connected bodies
+//!
+//! By default, revolute and spherical joints disable collisions between the bodies they connect,
+//! which is useful for things like ragdolls and chains where neighboring bodies overlap at the joints.
+//! Other joints let the connected bodies collide. This can be configured using `with_collisions_disabled`.
+//!
+//! ### Joint graph
+//!
+//! The [`JointGraph`] resource keeps track of which bodies are connected by joints. It can be used to find
+//! the joints attached to a body or the whole assembly of bodies and joints connected to it, like a ragdoll.
+//! An assembly can be despawned using the [`DespawnJointAssembly`] command.
+//!
+//! ### Motors
+//!
+//! [`RevoluteJoint`] and [`PrismaticJoint`] can be driven by a [`JointMotor`] using the `with_motor` method.
+//! Motors can drive the joint towards a target velocity, or towards a target position using a spring
+//! with a given stiffness and damping. The force or torque applied by the motor can be limited using
+//! [`JointMotor::with_max_force`].
+//!
+//! ```
+#![cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#![cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+//! use bevy::prelude::*;
+//!
+//! fn setup(mut commands: Commands) {
+//!     let entity1 = commands.spawn(RigidBody::Static).id();
+//!     let entity2 = commands.spawn(RigidBody::Dynamic).id();
+//!
+//!     // Spin the second body at 2 radians per second, using a torque of at most 50 Newton-meters
+//!     commands.spawn(
+//!         RevoluteJoint::new(entity1, entity2)
+//!             .with_motor(JointMotor::new_velocity(2.0, 100.0).with_max_force(50.0)),
+//!     );
+//! }
+//! ```
+//!
+//! ### Reading joint forces
+//!
+//! The force and torque exerted by a joint during the last physics step can be read by adding
+//! the [`JointForces`] component to the joint entity. This can be useful for things like stress meters
+//! and load sensors, or for tuning the compliance of joints.
+//!
+//! ### Breaking
+//!
+//! Joints can be made breakable by adding the [`BreakableJoint`] component to the joint entity.
+//! When the force or torque exerted by the joint exceeds the configured threshold,
+//! the joint component is removed and a [`JointBroken`] event is sent.
+//!
+//! ```
+#![cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#![cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+//! use bevy::prelude::*;
+//!
+//! fn setup(mut commands: Commands) {
+//!     let entity1 = commands.spawn(RigidBody::Static).id();
+//!     let entity2 = commands.spawn(RigidBody::Dynamic).id();
+//!
+//!     // Break the joint if it exerts a force larger than 500 Newtons
+//!     commands.spawn((
+//!         FixedJoint::new(entity1, entity2),
+//!         BreakableJoint::default().with_break_force(500.0),
+//!     ));
+//! }
+//!
+//! fn print_broken_joints(mut broken_joints: EventReader<JointBroken>) {
+//!     for event in broken_joints.read() {
+//!         println!("Joint {:?} broke with a force of {}", event.joint, event.force.length());
+//!     }
+//! }
+//! ```
+//!
 //! ### Other configuration
 //!
 //! Different joints may have different configuration options. Many joints allow you to change the axis of allowed
@@ -69,9 +167,22 @@
 //!
 //! Take a look at the documentation and methods of each joint to see all of the configuration options.
 //!
+//! ## Solvers
+//!
+//! By default, joints are solved with the same impulse-based soft step solver as contacts.
+//! Each joint is split into [`JointRow`]s, which are warm started, solved with a position bias,
+//! and relaxed alongside contacts. This lets joints and contacts share [softness](SoftnessParameters)
+//! and makes stacks of jointed bodies resting on the ground behave consistently.
+//!
+//! Alternatively, joints can be solved with [Extended Position-Based Dynamics (XPBD)](dynamics::solver::xpbd)
+//! by setting [`SolverConfig::joint_solver`](dynamics::solver::SolverConfig::joint_solver)
+//! to [`JointSolver::Xpbd`](dynamics::solver::JointSolver::Xpbd).
+//!
 //! ## Custom joints
 //!
 //! Joints are [constraints](dynamics::solver::xpbd#constraints) that implement [`Joint`] and [`XpbdConstraint`].
+//! To support the impulse-based solver, they should also implement [`ImpulseJoint`], which computes
+//! the [`JointRow`]s of the joint.
 //!
 //! The process of creating a joint is essentially the same as [creating a constraint](dynamics::solver::xpbd#custom-constraints),
 //! except you should also implement the [`Joint`] trait's methods. The trait has some useful helper methods
@@ -80,25 +191,42 @@
 //! Many joints also have joint limits. You can use [`DistanceLimit`] and [`AngleLimit`] to help store these limits
 //! and to compute the current distance from the specified limits.
 //!
+//! Joints with a free axis can also be driven by a [`JointMotor`]. The `compute_motor_lagrange_update`
+//! helper method of the [`Joint`] trait computes the motor's correction along a given axis.
+//!
 //! [See the code implementations](https://github.com/Jondolf/avian/tree/main/src/constraints/joints)
 //! of the implemented joints to get a better idea of how to create joints.
 
 mod distance;
 mod fixed;
+mod gear;
+mod generic;
+mod impulse;
+mod joint_graph;
 mod prismatic;
+mod pulley;
 mod revolute;
 #[cfg(feature = "3d")]
 mod spherical;
+mod target;
+mod wheel;
 
 pub use distance::*;
 pub use fixed::*;
+pub use gear::*;
+pub use generic::*;
+pub use impulse::*;
+pub use joint_graph::*;
 pub use prismatic::*;
+pub use pulley::*;
 pub use revolute::*;
 #[cfg(feature = "3d")]
 pub use spherical::*;
+pub use target::*;
+pub use wheel::*;
 
 use crate::{dynamics::solver::xpbd::*, prelude::*};
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
 /// A trait for [joints](self).
 pub trait Joint: Component + PositionConstraint + AngularConstraint {
@@ -108,6 +236,17 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
     /// Sets the joint's compliance (inverse of stiffness, meters / Newton).
     fn with_compliance(self, compliance: Scalar) -> Self;
 
+    /// Sets the joint's softness using a damping ratio and a frequency.
+    /// This overrides the compliance of the joint.
+    ///
+    /// The default implementation ignores the softness, for joints that don't support it.
+    fn with_softness(self, _softness: SoftnessParameters) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
     /// Sets the attachment point on the first body.
     fn with_local_anchor_1(self, anchor: Vector) -> Self;
 
@@ -120,6 +259,16 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
     /// Sets the angular velocity damping caused by the joint.
     fn with_angular_velocity_damping(self, damping: Scalar) -> Self;
 
+    /// Sets whether collisions between the bodies connected by the joint are disabled.
+    ///
+    /// The default implementation ignores the value, for joints that always allow collisions.
+    fn with_collisions_disabled(self, _disabled: bool) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
     /// Returns the local attachment point on the first body.
     fn local_anchor_1(&self) -> Vector;
 
@@ -132,6 +281,27 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
     /// Returns the angular velocity damping of the joint.
     fn damping_angular(&self) -> Scalar;
 
+    /// Returns `true` if collisions between the bodies connected by the joint are disabled.
+    ///
+    /// Returns `false` by default.
+    fn collisions_disabled(&self) -> bool {
+        false
+    }
+
+    /// Returns the total force exerted by the joint during the last substep.
+    ///
+    /// Returns zero by default.
+    fn force(&self) -> Vector {
+        Vector::ZERO
+    }
+
+    /// Returns the total torque exerted by the joint during the last substep.
+    ///
+    /// Returns zero by default.
+    fn torque(&self) -> Torque {
+        Torque::ZERO
+    }
+
     /// Applies a positional correction that aligns the positions of the local attachment points `r1` and `r2`.
     ///
     /// Returns the force exerted by the alignment.
@@ -180,6 +350,231 @@ pub trait Joint: Component + PositionConstraint + AngularConstraint {
         // Return constraint force
         self.compute_force(*lagrange, dir, dt)
     }
+
+    /// Computes the Lagrange multiplier update for a [`JointMotor`] driving the relative
+    /// `position` and `velocity` of the bodies along a joint axis.
+    ///
+    /// `inverse_mass` is the sum of the generalized inverse masses of the bodies along the axis.
+    /// The returned value should be applied such that a positive value increases `position`.
+    ///
+    /// The motor is treated as an implicit spring-damper, so the update is stable
+    /// even for large stiffness and damping values.
+    fn compute_motor_lagrange_update(
+        &self,
+        motor: &JointMotor,
+        position: Scalar,
+        velocity: Scalar,
+        inverse_mass: Scalar,
+        dt: Scalar,
+    ) -> Scalar {
+        if inverse_mass <= Scalar::EPSILON {
+            return 0.0;
+        }
+
+        let stiffness = motor.stiffness;
+        let damping = motor.damping;
+
+        // Implicit spring-damper: the impulse `p` is evaluated using the velocity after the impulse.
+        // p = h * (k * (x_target - x) + c * (v_target - v')), where v' = v + w * p
+        let impulse = dt
+            * (stiffness * (motor.target_position - position)
+                + damping * (motor.target_velocity - velocity))
+            / (1.0 + dt * (stiffness * dt + damping) * inverse_mass);
+
+        // Limit the force applied by the motor
+        let max_impulse = motor.max_force * dt;
+        let impulse = impulse.clamp(-max_impulse, max_impulse);
+
+        // XPBD works at the position level, so the Lagrange multiplier is the impulse times the time step
+        impulse * dt
+    }
+}
+
+/// A component that stores the force and torque exerted by a [joint](self) during the last physics step.
+///
+/// The values are summed over the substeps of the physics step. To get the average force
+/// exerted during the step, divide them by the [`SubstepCount`].
+///
+/// This component is not added to joints automatically. Add it to the joint entity to enable
+/// reading the joint's forces.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     let entity1 = commands.spawn(RigidBody::Static).id();
+///     let entity2 = commands.spawn(RigidBody::Dynamic).id();
+///
+///     commands.spawn((FixedJoint::new(entity1, entity2), JointForces::default()));
+/// }
+///
+/// fn print_joint_forces(query: Query<(Entity, &JointForces)>) {
+///     for (entity, forces) in &query {
+///         println!("Joint {:?} exerts a force of {} Newtons", entity, forces.force.length());
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, Default, PartialEq)]
+pub struct JointForces {
+    /// The force exerted by the joint during the last physics step.
+    pub force: Vector,
+    /// The torque exerted by the joint during the last physics step.
+    pub torque: Torque,
+}
+
+/// A component that makes a [joint](self) breakable.
+///
+/// When the force or torque exerted by a joint on the same entity exceeds
+/// `break_force` or `break_torque`, the joint component is removed
+/// and a [`JointBroken`] event is sent.
+///
+/// By default, both thresholds are infinite, so the joint never breaks.
+///
+/// For a [`GearJoint`], the force or torque applied to the second body of its first coupled joint
+/// is compared against the thresholds. A [`TargetJoint`] never exerts a torque, so only `break_force` applies to it.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, Default, PartialEq)]
+pub struct BreakableJoint {
+    /// The maximum force that the joint can exert before breaking.
+    pub break_force: Scalar,
+    /// The maximum torque that the joint can exert before breaking.
+    pub break_torque: Scalar,
+}
+
+impl Default for BreakableJoint {
+    fn default() -> Self {
+        Self {
+            break_force: Scalar::INFINITY,
+            break_torque: Scalar::INFINITY,
+        }
+    }
+}
+
+impl BreakableJoint {
+    /// Creates a new [`BreakableJoint`] with the given force and torque thresholds.
+    pub const fn new(break_force: Scalar, break_torque: Scalar) -> Self {
+        Self {
+            break_force,
+            break_torque,
+        }
+    }
+
+    /// Sets the maximum force that the joint can exert before breaking.
+    pub const fn with_break_force(self, break_force: Scalar) -> Self {
+        Self {
+            break_force,
+            ..self
+        }
+    }
+
+    /// Sets the maximum torque that the joint can exert before breaking.
+    pub const fn with_break_torque(self, break_torque: Scalar) -> Self {
+        Self {
+            break_torque,
+            ..self
+        }
+    }
+
+    /// Returns `true` if the given force or torque exceeds the thresholds.
+    pub(crate) fn is_exceeded(&self, force: Vector, torque: Torque) -> bool {
+        #[cfg(feature = "2d")]
+        let torque_magnitude = torque.abs();
+        #[cfg(feature = "3d")]
+        let torque_magnitude = torque.length();
+
+        force.length() > self.break_force || torque_magnitude > self.break_torque
+    }
+}
+
+/// An event that is sent when a [`BreakableJoint`] breaks because the force or torque
+/// exerted by the joint exceeded the configured threshold.
+///
+/// The joint component has already been removed from the `joint` entity when the event is read.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct JointBroken {
+    /// The entity of the joint that broke.
+    pub joint: Entity,
+    /// The first entity that was constrained by the joint.
+    ///
+    /// For a [`GearJoint`], this is the first coupled joint entity.
+    /// For a [`TargetJoint`], this is the constrained body, like `entity2`.
+    pub entity1: Entity,
+    /// The second entity that was constrained by the joint.
+    ///
+    /// For a [`GearJoint`], this is the second coupled joint entity.
+    pub entity2: Entity,
+    /// The force exerted by the joint when it broke.
+    pub force: Vector,
+    /// The torque exerted by the joint when it broke.
+    pub torque: Torque,
+}
+
+/// Pairs of bodies that are connected by joints with [collisions disabled](Joint::collisions_disabled).
+///
+/// The pairs are updated automatically before the [broad phase](PhysicsStepSet::BroadPhase),
+/// which skips collision pairs whose bodies are connected by such a joint.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct JointCollisionPairs {
+    /// The pair of bodies for each joint entity with collisions disabled.
+    joints: HashMap<Entity, (Entity, Entity)>,
+    /// The number of joints disabling collisions between each pair of bodies.
+    pairs: HashMap<(Entity, Entity), usize>,
+}
+
+impl JointCollisionPairs {
+    /// Returns `true` if collisions between the given bodies are disabled by a joint.
+    pub fn contains(&self, entity1: Entity, entity2: Entity) -> bool {
+        !self.pairs.is_empty() && self.pairs.contains_key(&Self::ordered(entity1, entity2))
+    }
+
+    /// Returns `true` if the given `joint` entity disables collisions between `entity1` and `entity2`.
+    pub(crate) fn contains_joint(&self, joint: Entity, entity1: Entity, entity2: Entity) -> bool {
+        self.joints.get(&joint) == Some(&Self::ordered(entity1, entity2))
+    }
+
+    /// Returns `true` if the given `joint` entity disables collisions between any bodies.
+    pub(crate) fn has_joint(&self, joint: Entity) -> bool {
+        self.joints.contains_key(&joint)
+    }
+
+    /// Disables collisions between `entity1` and `entity2` for the given `joint` entity.
+    pub(crate) fn insert(&mut self, joint: Entity, entity1: Entity, entity2: Entity) {
+        self.remove(joint);
+        let pair = Self::ordered(entity1, entity2);
+        self.joints.insert(joint, pair);
+        *self.pairs.entry(pair).or_default() += 1;
+    }
+
+    /// Removes the pair of bodies of the given `joint` entity.
+    pub(crate) fn remove(&mut self, joint: Entity) {
+        let Some(pair) = self.joints.remove(&joint) else {
+            return;
+        };
+        if let Some(count) = self.pairs.get_mut(&pair) {
+            *count -= 1;
+            if *count == 0 {
+                self.pairs.remove(&pair);
+            }
+        }
+    }
+
+    fn ordered(entity1: Entity, entity2: Entity) -> (Entity, Entity) {
+        if entity1 < entity2 {
+            (entity1, entity2)
+        } else {
+            (entity2, entity1)
+        }
+    }
 }
 
 /// A limit that indicates that the distance between two points should be between `min` and `max`.
@@ -338,3 +733,84 @@ impl AngleLimit {
         None
     }
 }
+
+/// A motor that drives the relative motion of the bodies attached to a joint along a free axis.
+///
+/// The motor is modeled as a spring-damper that applies a force proportional
+/// to the error in position and velocity:
+///
+/// ```text
+/// force = stiffness * (target_position - position) + damping * (target_velocity - velocity)
+/// ```
+///
+/// The force is limited by `max_force`. For angular motors, the force is a torque.
+///
+/// A velocity motor can be created with [`JointMotor::new_velocity`],
+/// and a position motor with [`JointMotor::new_position`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, PartialEq)]
+pub struct JointMotor {
+    /// The target relative velocity along the joint axis.
+    pub target_velocity: Scalar,
+    /// The target relative position along the joint axis.
+    ///
+    /// For angular motors, this is an angle in radians.
+    pub target_position: Scalar,
+    /// The stiffness of the spring pulling the joint towards `target_position`.
+    pub stiffness: Scalar,
+    /// The damping that drives the joint towards `target_velocity`.
+    pub damping: Scalar,
+    /// The maximum force (or torque for angular motors) that the motor can apply.
+    pub max_force: Scalar,
+}
+
+impl JointMotor {
+    /// Creates a new [`JointMotor`] that drives the joint towards the given `target_velocity`.
+    ///
+    /// The `damping` controls how strongly the motor tries to reach the target velocity.
+    /// Large values result in a stiff velocity constraint that is only limited by the [maximum force](Self::max_force).
+    pub const fn new_velocity(target_velocity: Scalar, damping: Scalar) -> Self {
+        Self {
+            target_velocity,
+            target_position: 0.0,
+            stiffness: 0.0,
+            damping,
+            max_force: Scalar::MAX,
+        }
+    }
+
+    /// Creates a new [`JointMotor`] that drives the joint towards the given `target_position`
+    /// using a spring with the given `stiffness` and `damping`.
+    pub const fn new_position(target_position: Scalar, stiffness: Scalar, damping: Scalar) -> Self {
+        Self {
+            target_velocity: 0.0,
+            target_position,
+            stiffness,
+            damping,
+            max_force: Scalar::MAX,
+        }
+    }
+
+    /// Sets the target relative velocity along the joint axis.
+    pub const fn with_target_velocity(self, target_velocity: Scalar) -> Self {
+        Self {
+            target_velocity,
+            ..self
+        }
+    }
+
+    /// Sets the target relative position along the joint axis.
+    pub const fn with_target_position(self, target_position: Scalar) -> Self {
+        Self {
+            target_position,
+            ..self
+        }
+    }
+
+    /// Sets the maximum force (or torque for angular motors) that the motor can apply.
+    pub const fn with_max_force(self, max_force: Scalar) -> Self {
+        Self { max_force, ..self }
+    }
+}
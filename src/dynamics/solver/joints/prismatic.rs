@@ -1,5 +1,6 @@
 //! [`PrismaticJoint`] component.
 
+use super::impulse::{push_angular_lock_rows, push_limit_rows, world_anchors};
 use crate::{dynamics::solver::xpbd::*, prelude::*};
 use bevy::{
     ecs::{
@@ -25,10 +26,14 @@ pub struct PrismaticJoint {
     pub local_anchor1: Vector,
     /// Attachment point on the second body.
     pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
     /// A free axis that the attached bodies can translate along relative to each other.
     pub free_axis: Vector,
     /// The extents of the allowed relative translation along the free axis.
     pub free_axis_limits: Option<DistanceLimit>,
+    /// A motor that drives the relative translation of the bodies along the free axis.
+    pub motor: Option<JointMotor>,
     /// Linear damping applied by the joint.
     pub damping_linear: Scalar,
     /// Angular damping applied by the joint.
@@ -37,12 +42,22 @@ pub struct PrismaticJoint {
     pub position_lagrange: Scalar,
     /// Lagrange multiplier for the angular correction caused by the alignment of the bodies.
     pub align_lagrange: Scalar,
+    /// Lagrange multiplier for the positional correction caused by the motor.
+    pub motor_lagrange: Scalar,
     /// The joint's compliance, the inverse of stiffness, has the unit meters / Newton.
     pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
     /// The force exerted by the joint.
     pub force: Vector,
     /// The torque exerted by the joint when aligning the bodies.
     pub align_torque: Torque,
+    /// The force exerted by the joint's motor along the free axis.
+    pub motor_force: Vector,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
 }
 
 impl XpbdConstraint<2> for PrismaticJoint {
@@ -50,9 +65,14 @@ impl XpbdConstraint<2> for PrismaticJoint {
         [self.entity1, self.entity2]
     }
 
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
     fn clear_lagrange_multipliers(&mut self) {
         self.position_lagrange = 0.0;
         self.align_lagrange = 0.0;
+        self.motor_lagrange = 0.0;
     }
 
     fn solve(&mut self, bodies: [&mut RigidBodyQueryItem; 2], dt: Scalar) {
@@ -66,6 +86,9 @@ impl XpbdConstraint<2> for PrismaticJoint {
             self.align_orientation(body1, body2, difference, &mut lagrange, compliance, dt);
         self.align_lagrange = lagrange;
 
+        // Drive the translation along the free axis using the motor
+        self.motor_force = self.apply_motor(body1, body2, dt);
+
         // Constrain the relative positions of the bodies, only allowing translation along one free axis
         self.force = self.constrain_positions(body1, body2, dt);
     }
@@ -78,18 +101,24 @@ impl Joint for PrismaticJoint {
             entity2,
             local_anchor1: Vector::ZERO,
             local_anchor2: Vector::ZERO,
+            collisions_disabled: false,
             free_axis: Vector::X,
             free_axis_limits: None,
+            motor: None,
             damping_linear: 1.0,
             damping_angular: 1.0,
             position_lagrange: 0.0,
             align_lagrange: 0.0,
+            motor_lagrange: 0.0,
             compliance: 0.0,
+            softness: None,
             force: Vector::ZERO,
             #[cfg(feature = "2d")]
             align_torque: 0.0,
             #[cfg(feature = "3d")]
             align_torque: Vector::ZERO,
+            motor_force: Vector::ZERO,
+            impulses: JointImpulses::default(),
         }
     }
 
@@ -97,6 +126,13 @@ impl Joint for PrismaticJoint {
         Self { compliance, ..self }
     }
 
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
     fn with_local_anchor_1(self, anchor: Vector) -> Self {
         Self {
             local_anchor1: anchor,
@@ -125,6 +161,13 @@ impl Joint for PrismaticJoint {
         }
     }
 
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
     fn local_anchor_1(&self) -> Vector {
         self.local_anchor1
     }
@@ -140,6 +183,18 @@ impl Joint for PrismaticJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force + self.motor_force
+    }
+
+    fn torque(&self) -> Torque {
+        self.align_torque
+    }
 }
 
 impl PrismaticJoint {
@@ -230,6 +285,53 @@ impl PrismaticJoint {
         self.compute_force(self.position_lagrange, dir, dt)
     }
 
+    /// Applies the joint's motor to drive the relative translation of the bodies along the free axis.
+    ///
+    /// Returns the force exerted by the motor.
+    fn apply_motor(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Vector {
+        let Some(motor) = self.motor else {
+            return Vector::ZERO;
+        };
+
+        let world_r1 = *body1.rotation * self.local_anchor1;
+        let world_r2 = *body2.rotation * self.local_anchor2;
+        let axis = *body1.rotation * self.free_axis;
+
+        // The relative position and velocity of the attachment points along the free axis
+        let offset = body2.current_position() + world_r2 - body1.current_position() - world_r1;
+        let position = offset.dot(axis);
+        let velocity =
+            (body2.velocity_at_point(world_r2) - body1.velocity_at_point(world_r1)).dot(axis);
+
+        // Compute generalized inverse masses
+        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, axis);
+        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, axis);
+
+        let delta_lagrange =
+            self.compute_motor_lagrange_update(&motor, position, velocity, w1 + w2, dt);
+        self.motor_lagrange += delta_lagrange;
+
+        // Positional corrections push the bodies together along the given direction,
+        // so the axis is negated to increase the relative position instead.
+        let dir = -axis;
+        self.apply_positional_lagrange_update(
+            body1,
+            body2,
+            delta_lagrange,
+            dir,
+            world_r1,
+            world_r2,
+        );
+
+        // Return motor force
+        self.compute_force(self.motor_lagrange, dir, dt)
+    }
+
     /// Sets the joint's free axis. Relative translations are allowed along this free axis.
     pub fn with_free_axis(self, axis: Vector) -> Self {
         Self {
@@ -246,6 +348,14 @@ impl PrismaticJoint {
         }
     }
 
+    /// Sets the motor that drives the relative translation along the free axis.
+    pub fn with_motor(self, motor: JointMotor) -> Self {
+        Self {
+            motor: Some(motor),
+            ..self
+        }
+    }
+
     #[cfg(feature = "2d")]
     fn get_rotation_difference(&self, rot1: &Rotation, rot2: &Rotation) -> Scalar {
         rot1.angle_between(*rot2)
@@ -259,6 +369,81 @@ impl PrismaticJoint {
     }
 }
 
+impl ImpulseJoint for PrismaticJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
+
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let softness = JointRowSoftness::from_joint(self.softness, self.compliance);
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+        let offset = p2 - p1;
+        let axis = *body1.rotation * self.free_axis;
+
+        // Lock the relative rotation of the bodies
+        push_angular_lock_rows(rows, &body1.rotation, &body2.rotation, 0, softness);
+
+        // The axes are attached to the first body, so its lever arm extends to the second attachment point
+        let r1 = r1 + offset;
+
+        // Only allow translation along the free axis
+        #[cfg(feature = "2d")]
+        let perpendicular_axes = [Vector::new(axis.y, -axis.x)];
+        #[cfg(feature = "3d")]
+        let perpendicular_axes: [Vector; 2] = axis.any_orthonormal_pair().into();
+
+        for (i, perpendicular) in perpendicular_axes.into_iter().enumerate() {
+            let error = offset.dot(perpendicular);
+            rows.push(
+                JointRow::linear(ANGULAR_DIM + i, r1, r2, perpendicular, error)
+                    .with_softness(softness),
+            );
+        }
+
+        // The limits and the motor use the slots after the angular and perpendicular rows
+        let slot = ANGULAR_DIM + DIM - 1;
+        let row = JointRow::linear(slot, r1, r2, axis, 0.0);
+        let position = offset.dot(axis);
+
+        if let Some(limits) = self.free_axis_limits {
+            let row = row.with_softness(softness);
+            push_limit_rows(
+                rows,
+                row,
+                [slot, slot + 1],
+                position,
+                limits.min,
+                limits.max,
+            );
+        }
+
+        if let Some(motor) = self.motor {
+            let row = JointRow {
+                slot: slot + 2,
+                error: position,
+                ..row
+            };
+            rows.push(row.with_motor(&motor, delta_secs));
+        }
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, torque: Torque) {
+        self.force = force;
+        self.align_torque = torque;
+        self.motor_force = Vector::ZERO;
+    }
+}
+
 impl PositionConstraint for PrismaticJoint {}
 
 impl AngularConstraint for PrismaticJoint {}
@@ -0,0 +1,486 @@
+//! [`PulleyJoint`] component.
+
+use super::impulse::world_anchors;
+use crate::{dynamics::solver::xpbd::*, prelude::*};
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+/// A pulley joint connects two bodies to two fixed ground anchors with a rope that runs over a pulley.
+///
+/// The joint keeps the sum `length1 + ratio * length2` constant, where `length1` and `length2` are
+/// the distances from each body's attachment point to its own world-space ground anchor.
+/// When one body moves away from its ground anchor, the other body is pulled towards its ground anchor.
+///
+/// The `ratio` can be used to simulate a block and tackle, where one side of the rope moves
+/// faster than the other. The length of each side can also be limited using [`PulleyJoint::with_max_lengths`].
+///
+/// Pulley joints can be useful for things like cranes, elevators and counterweights.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, MapEntities, PartialEq)]
+pub struct PulleyJoint {
+    /// First entity constrained by the joint.
+    pub entity1: Entity,
+    /// Second entity constrained by the joint.
+    pub entity2: Entity,
+    /// Attachment point on the first body.
+    pub local_anchor1: Vector,
+    /// Attachment point on the second body.
+    pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
+    /// The world-space ground anchor that the rope of the first body is attached to.
+    pub ground_anchor1: Vector,
+    /// The world-space ground anchor that the rope of the second body is attached to.
+    pub ground_anchor2: Vector,
+    /// The pulley ratio. The constraint keeps `length1 + ratio * length2` constant.
+    pub ratio: Scalar,
+    /// The constant total length `length1 + ratio * length2`.
+    ///
+    /// If `None`, the length is computed from the positions of the bodies the first time the joint is solved.
+    pub length: Option<Scalar>,
+    /// The maximum distance between the first body's attachment point and its ground anchor.
+    pub max_length1: Option<Scalar>,
+    /// The maximum distance between the second body's attachment point and its ground anchor.
+    pub max_length2: Option<Scalar>,
+    /// Linear damping applied by the joint.
+    pub damping_linear: Scalar,
+    /// Angular damping applied by the joint.
+    pub damping_angular: Scalar,
+    /// Lagrange multiplier for the positional correction.
+    pub lagrange: Scalar,
+    /// Lagrange multiplier for the positional correction caused by the maximum length of the first side.
+    pub max_length_lagrange1: Scalar,
+    /// Lagrange multiplier for the positional correction caused by the maximum length of the second side.
+    pub max_length_lagrange2: Scalar,
+    /// The joint's compliance, the inverse of stiffness, has the unit meters / Newton.
+    pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
+    /// The force exerted by the joint on the first body.
+    pub force: Vector,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
+}
+
+impl XpbdConstraint<2> for PulleyJoint {
+    fn entities(&self) -> [Entity; 2] {
+        [self.entity1, self.entity2]
+    }
+
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
+    fn clear_lagrange_multipliers(&mut self) {
+        self.lagrange = 0.0;
+        self.max_length_lagrange1 = 0.0;
+        self.max_length_lagrange2 = 0.0;
+    }
+
+    fn solve(&mut self, bodies: [&mut RigidBodyQueryItem; 2], dt: Scalar) {
+        let [body1, body2] = bodies;
+
+        // Keep the total length of the rope constant
+        self.force = self.constrain_length(body1, body2, dt);
+
+        // Limit the length of each side of the rope
+        let mut lagrange = self.max_length_lagrange1;
+        self.force += self.limit_length(
+            body1,
+            self.local_anchor1,
+            self.ground_anchor1,
+            self.max_length1,
+            &mut lagrange,
+            dt,
+        );
+        self.max_length_lagrange1 = lagrange;
+
+        let mut lagrange = self.max_length_lagrange2;
+        self.limit_length(
+            body2,
+            self.local_anchor2,
+            self.ground_anchor2,
+            self.max_length2,
+            &mut lagrange,
+            dt,
+        );
+        self.max_length_lagrange2 = lagrange;
+    }
+}
+
+impl Joint for PulleyJoint {
+    fn new(entity1: Entity, entity2: Entity) -> Self {
+        Self {
+            entity1,
+            entity2,
+            local_anchor1: Vector::ZERO,
+            local_anchor2: Vector::ZERO,
+            collisions_disabled: false,
+            ground_anchor1: Vector::ZERO,
+            ground_anchor2: Vector::ZERO,
+            ratio: 1.0,
+            length: None,
+            max_length1: None,
+            max_length2: None,
+            damping_linear: 0.0,
+            damping_angular: 0.0,
+            lagrange: 0.0,
+            max_length_lagrange1: 0.0,
+            max_length_lagrange2: 0.0,
+            compliance: 0.0,
+            softness: None,
+            force: Vector::ZERO,
+            impulses: JointImpulses::default(),
+        }
+    }
+
+    fn with_compliance(self, compliance: Scalar) -> Self {
+        Self { compliance, ..self }
+    }
+
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
+    fn with_local_anchor_1(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor1: anchor,
+            ..self
+        }
+    }
+
+    fn with_local_anchor_2(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor2: anchor,
+            ..self
+        }
+    }
+
+    fn with_linear_velocity_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_linear: damping,
+            ..self
+        }
+    }
+
+    fn with_angular_velocity_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_angular: damping,
+            ..self
+        }
+    }
+
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
+    fn local_anchor_1(&self) -> Vector {
+        self.local_anchor1
+    }
+
+    fn local_anchor_2(&self) -> Vector {
+        self.local_anchor2
+    }
+
+    fn damping_linear(&self) -> Scalar {
+        self.damping_linear
+    }
+
+    fn damping_angular(&self) -> Scalar {
+        self.damping_angular
+    }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force
+    }
+
+    fn torque(&self) -> Torque {
+        Torque::ZERO
+    }
+}
+
+impl PulleyJoint {
+    /// Sets the world-space ground anchors that the ropes of the first and second body are attached to.
+    pub fn with_ground_anchors(self, ground_anchor1: Vector, ground_anchor2: Vector) -> Self {
+        Self {
+            ground_anchor1,
+            ground_anchor2,
+            ..self
+        }
+    }
+
+    /// Sets the pulley ratio. The constraint keeps `length1 + ratio * length2` constant.
+    pub fn with_ratio(self, ratio: Scalar) -> Self {
+        Self { ratio, ..self }
+    }
+
+    /// Sets the constant total length `length1 + ratio * length2`.
+    ///
+    /// By default, the length is computed from the positions of the bodies
+    /// the first time the joint is solved.
+    pub fn with_length(self, length: Scalar) -> Self {
+        Self {
+            length: Some(length),
+            ..self
+        }
+    }
+
+    /// Sets the maximum distances between the attachment points and the ground anchors
+    /// of the first and second body.
+    pub fn with_max_lengths(self, max_length1: Scalar, max_length2: Scalar) -> Self {
+        Self {
+            max_length1: Some(max_length1),
+            max_length2: Some(max_length2),
+            ..self
+        }
+    }
+
+    /// Keeps the total length `length1 + ratio * length2` of the rope constant.
+    ///
+    /// Returns the force exerted on the first body.
+    fn constrain_length(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Vector {
+        let world_r1 = *body1.rotation * self.local_anchor1;
+        let world_r2 = *body2.rotation * self.local_anchor2;
+
+        // Compute the current lengths and directions of both sides of the rope
+        let offset1 = body1.current_position() + world_r1 - self.ground_anchor1;
+        let offset2 = body2.current_position() + world_r2 - self.ground_anchor2;
+        let length1 = offset1.length();
+        let length2 = offset2.length();
+
+        let length = *self.length.get_or_insert(length1 + self.ratio * length2);
+
+        if length1 <= Scalar::EPSILON || length2 <= Scalar::EPSILON {
+            return Vector::ZERO;
+        }
+
+        // The gradients of the constraint function point away from the ground anchors
+        let dir1 = offset1 / length1;
+        let dir2 = offset2 / length2;
+
+        let c = length1 + self.ratio * length2 - length;
+
+        if c.abs() <= Scalar::EPSILON {
+            return Vector::ZERO;
+        }
+
+        // Compute generalized inverse masses
+        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, dir1);
+        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, dir2);
+
+        // Compute Lagrange multiplier update
+        let delta_lagrange = self.compute_lagrange_update_with_gradients(
+            self.lagrange,
+            c,
+            &[dir1, self.ratio * dir2],
+            &[w1, w2],
+            self.compliance,
+            dt,
+        );
+        self.lagrange += delta_lagrange;
+
+        // Move both bodies along their gradients
+        self.apply_single_body_impulse(body1, delta_lagrange * dir1, world_r1);
+        self.apply_single_body_impulse(body2, delta_lagrange * self.ratio * dir2, world_r2);
+
+        // Return constraint force
+        self.compute_force(self.lagrange, dir1, dt)
+    }
+
+    /// Limits the distance between the attachment point of a body and its ground anchor
+    /// to be at most `max_length`.
+    ///
+    /// Returns the force exerted on the body.
+    fn limit_length(
+        &self,
+        body: &mut RigidBodyQueryItem,
+        local_anchor: Vector,
+        ground_anchor: Vector,
+        max_length: Option<Scalar>,
+        lagrange: &mut Scalar,
+        dt: Scalar,
+    ) -> Vector {
+        let Some(max_length) = max_length else {
+            return Vector::ZERO;
+        };
+
+        let world_r = *body.rotation * local_anchor;
+
+        let (dir, magnitude) = DistanceLimit::new(0.0, max_length)
+            .compute_correction(ground_anchor, body.current_position() + world_r);
+
+        if magnitude <= Scalar::EPSILON {
+            return Vector::ZERO;
+        }
+
+        // The ground anchor is static, so only the body contributes to the inverse mass.
+        let w = PositionConstraint::compute_generalized_inverse_mass(self, body, world_r, dir);
+
+        // Compute Lagrange multiplier update
+        let delta_lagrange =
+            self.compute_lagrange_update(*lagrange, magnitude, &[w], self.compliance, dt);
+        *lagrange += delta_lagrange;
+
+        // `dir` points from the body towards the ground anchor,
+        // so the gradient of the constraint function is `-dir`.
+        self.apply_single_body_impulse(body, -delta_lagrange * dir, world_r);
+
+        self.compute_force(*lagrange, -dir, dt)
+    }
+
+    /// Applies a positional impulse to a single body at the attachment point `r`.
+    fn apply_single_body_impulse(&self, body: &mut RigidBodyQueryItem, impulse: Vector, r: Vector) {
+        if !body.rb.is_dynamic() {
+            return;
+        }
+
+        let inv_mass = body.effective_inv_mass();
+        let inv_inertia = body.effective_world_inv_inertia();
+
+        body.accumulated_translation.0 += impulse * inv_mass;
+
+        #[cfg(feature = "2d")]
+        {
+            let delta_angle = <Self as PositionConstraint>::get_delta_rot(
+                *body.rotation,
+                inv_inertia,
+                r,
+                impulse,
+            );
+            *body.rotation = body.rotation.add_angle(delta_angle);
+        }
+        #[cfg(feature = "3d")]
+        {
+            // In 3D, adding quaternions can result in unnormalized rotations,
+            // so the rotation must be normalized.
+            let delta_quat = <Self as PositionConstraint>::get_delta_rot(
+                *body.rotation,
+                inv_inertia,
+                r,
+                impulse,
+            );
+            body.rotation.0 = (body.rotation.0 + delta_quat).normalize();
+        }
+    }
+}
+
+impl ImpulseJoint for PulleyJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
+
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        _delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let softness = JointRowSoftness::from_joint(self.softness, self.compliance);
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+
+        // Compute the current lengths and directions of both sides of the rope
+        let offset1 = p1 - self.ground_anchor1;
+        let offset2 = p2 - self.ground_anchor2;
+        let length1 = offset1.length();
+        let length2 = offset2.length();
+
+        let length = *self.length.get_or_insert(length1 + self.ratio * length2);
+
+        if length1 <= Scalar::EPSILON || length2 <= Scalar::EPSILON {
+            return;
+        }
+
+        // The Jacobian points away from the ground anchors
+        let dir1 = offset1 / length1;
+        let dir2 = offset2 / length2;
+
+        // Keep the total length of the rope constant
+        rows.push(
+            JointRow {
+                linear1: dir1,
+                angular1: cross(r1, dir1),
+                linear2: self.ratio * dir2,
+                angular2: self.ratio * cross(r2, dir2),
+                ..JointRow::linear(0, r1, r2, dir2, length1 + self.ratio * length2 - length)
+            }
+            .with_softness(softness),
+        );
+
+        // Limit the length of each side of the rope
+        if let Some(max_length) = self.max_length1 {
+            let row = JointRow::linear(1, r1, Vector::ZERO, dir1, max_length - length1);
+            rows.push(
+                JointRow {
+                    linear2: Vector::ZERO,
+                    ..row
+                }
+                .as_limit()
+                .with_softness(softness),
+            );
+        }
+        if let Some(max_length) = self.max_length2 {
+            let row = JointRow::linear(2, Vector::ZERO, r2, -dir2, max_length - length2);
+            rows.push(
+                JointRow {
+                    linear1: Vector::ZERO,
+                    ..row
+                }
+                .as_limit()
+                .with_softness(softness),
+            );
+        }
+    }
+
+    fn compute_force_and_torque(&self, rows: &[JointRow], delta_secs: Scalar) -> (Vector, Torque) {
+        // The force is reported for the first body.
+        let force = rows
+            .iter()
+            .map(|row| row.linear1 * self.impulses.get(row.slot) / delta_secs)
+            .sum();
+        (force, Torque::ZERO)
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, _torque: Torque) {
+        self.force = force;
+    }
+}
+
+impl PositionConstraint for PulleyJoint {}
+
+impl AngularConstraint for PulleyJoint {}
+
+impl MapEntities for PulleyJoint {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.entity1 = entity_mapper.map_entity(self.entity1);
+        self.entity2 = entity_mapper.map_entity(self.entity2);
+    }
+}
@@ -1,5 +1,8 @@
 //! [`RevoluteJoint`] component.
 
+#[cfg(feature = "3d")]
+use super::impulse::{push_axis_alignment_rows, relative_angle};
+use super::impulse::{push_limit_rows, push_point_rows, world_anchors};
 use crate::{dynamics::solver::xpbd::*, prelude::*};
 use bevy::{
     ecs::{
@@ -25,6 +28,8 @@ pub struct RevoluteJoint {
     pub local_anchor1: Vector,
     /// Attachment point on the second body.
     pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
     /// A unit vector that controls which axis should be aligned for both entities.
     ///
     /// In 2D this should always be the Z axis.
@@ -35,6 +40,8 @@ pub struct RevoluteJoint {
     pub aligned_axis: Vector,
     /// The extents of the allowed relative rotation of the bodies around the `aligned_axis`.
     pub angle_limit: Option<AngleLimit>,
+    /// A motor that drives the relative rotation of the bodies around the `aligned_axis`.
+    pub motor: Option<JointMotor>,
     /// Linear damping applied by the joint.
     pub damping_linear: Scalar,
     /// Angular damping applied by the joint.
@@ -45,14 +52,24 @@ pub struct RevoluteJoint {
     pub align_lagrange: Scalar,
     /// Lagrange multiplier for the angular correction caused by the angle limits.
     pub angle_limit_lagrange: Scalar,
+    /// Lagrange multiplier for the angular correction caused by the motor.
+    pub motor_lagrange: Scalar,
     /// The joint's compliance, the inverse of stiffness, has the unit meters / Newton.
     pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
     /// The force exerted by the joint.
     pub force: Vector,
     /// The torque exerted by the joint when aligning the bodies.
     pub align_torque: Torque,
     /// The torque exerted by the joint when limiting the relative rotation of the bodies around the `aligned_axis`.
     pub angle_limit_torque: Torque,
+    /// The torque exerted by the joint's motor around the `aligned_axis`.
+    pub motor_torque: Torque,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
 }
 
 impl XpbdConstraint<2> for RevoluteJoint {
@@ -60,10 +77,15 @@ impl XpbdConstraint<2> for RevoluteJoint {
         [self.entity1, self.entity2]
     }
 
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
     fn clear_lagrange_multipliers(&mut self) {
         self.position_lagrange = 0.0;
         self.align_lagrange = 0.0;
         self.angle_limit_lagrange = 0.0;
+        self.motor_lagrange = 0.0;
     }
 
     fn solve(&mut self, bodies: [&mut RigidBodyQueryItem; 2], dt: Scalar) {
@@ -80,6 +102,9 @@ impl XpbdConstraint<2> for RevoluteJoint {
             self.align_lagrange = lagrange;
         }
 
+        // Drive the rotation around the free axis using the motor
+        self.motor_torque = self.apply_motor(body1, body2, dt);
+
         // Apply angle limits when rotating around the free axis
         self.angle_limit_torque = self.apply_angle_limits(body1, body2, dt);
 
@@ -105,14 +130,18 @@ impl Joint for RevoluteJoint {
             entity2,
             local_anchor1: Vector::ZERO,
             local_anchor2: Vector::ZERO,
+            collisions_disabled: true,
             aligned_axis: Vector3::Z,
             angle_limit: None,
+            motor: None,
             damping_linear: 1.0,
             damping_angular: 1.0,
             position_lagrange: 0.0,
             align_lagrange: 0.0,
             angle_limit_lagrange: 0.0,
+            motor_lagrange: 0.0,
             compliance: 0.0,
+            softness: None,
             force: Vector::ZERO,
             #[cfg(feature = "2d")]
             align_torque: 0.0,
@@ -122,6 +151,11 @@ impl Joint for RevoluteJoint {
             angle_limit_torque: 0.0,
             #[cfg(feature = "3d")]
             angle_limit_torque: Vector::ZERO,
+            #[cfg(feature = "2d")]
+            motor_torque: 0.0,
+            #[cfg(feature = "3d")]
+            motor_torque: Vector::ZERO,
+            impulses: JointImpulses::default(),
         }
     }
 
@@ -129,6 +163,13 @@ impl Joint for RevoluteJoint {
         Self { compliance, ..self }
     }
 
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
     fn with_local_anchor_1(self, anchor: Vector) -> Self {
         Self {
             local_anchor1: anchor,
@@ -157,6 +198,13 @@ impl Joint for RevoluteJoint {
         }
     }
 
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
     fn local_anchor_1(&self) -> Vector {
         self.local_anchor1
     }
@@ -172,6 +220,18 @@ impl Joint for RevoluteJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force
+    }
+
+    fn torque(&self) -> Torque {
+        self.align_torque + self.angle_limit_torque + self.motor_torque
+    }
 }
 
 impl RevoluteJoint {
@@ -192,6 +252,14 @@ impl RevoluteJoint {
         }
     }
 
+    /// Sets the motor that drives the relative rotation around the `aligned_axis`.
+    pub fn with_motor(self, motor: JointMotor) -> Self {
+        Self {
+            motor: Some(motor),
+            ..self
+        }
+    }
+
     #[cfg(feature = "3d")]
     fn get_rotation_difference(&self, rot1: &Rotation, rot2: &Rotation) -> Vector3 {
         let a1 = rot1 * self.aligned_axis;
@@ -199,6 +267,52 @@ impl RevoluteJoint {
         a1.cross(a2)
     }
 
+    /// Applies the joint's motor to drive the relative rotation of the bodies around the `aligned_axis`.
+    fn apply_motor(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Torque {
+        let Some(motor) = self.motor else {
+            return Torque::ZERO;
+        };
+
+        #[cfg(feature = "2d")]
+        {
+            let angle = body1.rotation.angle_between(*body2.rotation);
+            let angular_velocity = body2.angular_velocity.0 - body1.angular_velocity.0;
+            let w = body1.effective_world_inv_inertia() + body2.effective_world_inv_inertia();
+
+            let delta_lagrange =
+                self.compute_motor_lagrange_update(&motor, angle, angular_velocity, w, dt);
+            self.motor_lagrange += delta_lagrange;
+            self.apply_angular_lagrange_update(body1, body2, delta_lagrange);
+
+            self.compute_torque(self.motor_lagrange, dt)
+        }
+        #[cfg(feature = "3d")]
+        {
+            // Compute the relative angle around the aligned axis using perpendicular reference axes.
+            let axis = *body1.rotation * self.aligned_axis;
+            let b = self.aligned_axis.any_orthonormal_vector();
+            let b1 = *body1.rotation * b;
+            let b2 = *body2.rotation * b;
+            let angle = b1.cross(b2).dot(axis).atan2(b1.dot(b2));
+            let angular_velocity = (body2.angular_velocity.0 - body1.angular_velocity.0).dot(axis);
+
+            let w1 = AngularConstraint::compute_generalized_inverse_mass(self, body1, axis);
+            let w2 = AngularConstraint::compute_generalized_inverse_mass(self, body2, axis);
+
+            let delta_lagrange =
+                self.compute_motor_lagrange_update(&motor, angle, angular_velocity, w1 + w2, dt);
+            self.motor_lagrange += delta_lagrange;
+            self.apply_angular_lagrange_update(body1, body2, delta_lagrange, axis);
+
+            self.compute_torque(self.motor_lagrange, axis, dt)
+        }
+    }
+
     /// Applies angle limits to limit the relative rotation of the bodies around the `aligned_axis`.
     #[allow(clippy::too_many_arguments)]
     fn apply_angle_limits(
@@ -232,6 +346,68 @@ impl RevoluteJoint {
     }
 }
 
+impl ImpulseJoint for RevoluteJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
+
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let softness = JointRowSoftness::from_joint(self.softness, self.compliance);
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+
+        // Align the attachment points
+        push_point_rows(rows, r1, r2, p2 - p1, softness);
+
+        #[cfg(feature = "2d")]
+        let (row, angle) = (
+            JointRow::angular(0, 0.0),
+            body1.rotation.angle_between(*body2.rotation),
+        );
+        #[cfg(feature = "3d")]
+        let (row, angle) = {
+            // Align the axes of the bodies, only allowing rotation around the aligned axis
+            let (axis, angle) = relative_angle(&body1.rotation, &body2.rotation, self.aligned_axis);
+            let axis2 = *body2.rotation * self.aligned_axis;
+            push_axis_alignment_rows(rows, axis, axis2, DIM, softness);
+            (JointRow::angular(0, axis, 0.0), angle)
+        };
+
+        // The angle limits and the motor use the slots after the point and alignment rows
+        let slot = DIM + ANGULAR_DIM - 1;
+
+        if let Some(limit) = self.angle_limit {
+            let row = row.with_softness(softness);
+            push_limit_rows(rows, row, [slot, slot + 1], angle, limit.min, limit.max);
+        }
+
+        if let Some(motor) = self.motor {
+            let row = JointRow {
+                slot: slot + 2,
+                error: angle,
+                ..row
+            };
+            rows.push(row.with_motor(&motor, delta_secs));
+        }
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, torque: Torque) {
+        self.force = force;
+        self.align_torque = torque;
+        self.angle_limit_torque = Torque::ZERO;
+        self.motor_torque = Torque::ZERO;
+    }
+}
+
 impl PositionConstraint for RevoluteJoint {}
 
 impl AngularConstraint for RevoluteJoint {}
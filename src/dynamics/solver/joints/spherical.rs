@@ -1,5 +1,6 @@
 //! [`SphericalJoint`] component.
 
+use super::impulse::{push_point_rows, world_anchors};
 use crate::{dynamics::solver::xpbd::*, prelude::*};
 use bevy::{
     ecs::{
@@ -12,6 +13,18 @@ use bevy::{
 /// A spherical joint prevents relative translation of the attached bodies while allowing rotation around all axes.
 ///
 /// Spherical joints can be useful for things like pendula, chains, ragdolls etc.
+///
+/// ## Limits
+///
+/// The rotation of the bodies can be limited using swing and twist limits. Swinging is measured as the angle
+/// between the `swing_axis` of the two bodies, while twisting is measured as the rotation around it.
+///
+/// [`SphericalJoint::with_swing_limits`] limits the swing to a circular cone. Joints like shoulders and hips
+/// often need different ranges of motion along different directions, so the swing can also be limited
+/// to an elliptical cone using [`SphericalJoint::with_elliptical_swing_limits`].
+///
+/// By default, the limits are hard stops. [`SphericalJoint::with_limit_softness`] can be used to make them act
+/// like damped springs instead, letting the bodies rotate past the limits and then pushing them back.
 #[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
 #[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
@@ -25,6 +38,8 @@ pub struct SphericalJoint {
     pub local_anchor1: Vector,
     /// Attachment point on the second body.
     pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
     /// An axis that the attached bodies can swing around. This is normally the x-axis.
     pub swing_axis: Vector3,
     /// An axis that the attached bodies can twist around. This is normally the y-axis.
@@ -33,6 +48,14 @@ pub struct SphericalJoint {
     pub swing_limit: Option<AngleLimit>,
     /// The extents of the allowed relative rotation of the bodies around the `twist_axis`.
     pub twist_limit: Option<AngleLimit>,
+    /// An elliptical cone that limits the swing of the bodies.
+    ///
+    /// If `Some`, this is used instead of the `swing_limit`.
+    pub swing_cone_limit: Option<ConeLimit>,
+    /// The softness of the swing and twist limits, given as a damping ratio and a frequency.
+    ///
+    /// If `None`, the limits are hard stops.
+    pub limit_softness: Option<SoftnessParameters>,
     /// Linear damping applied by the joint.
     pub damping_linear: Scalar,
     /// Angular damping applied by the joint.
@@ -45,12 +68,18 @@ pub struct SphericalJoint {
     pub twist_lagrange: Scalar,
     /// The joint's compliance, the inverse of stiffness, has the unit meters / Newton.
     pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    pub softness: Option<SoftnessParameters>,
     /// The force exerted by the joint.
     pub force: Vector,
     /// The torque exerted by the joint when limiting the relative rotation of the bodies around the `swing_axis`.
     pub swing_torque: Torque,
     /// The torque exerted by the joint when limiting the relative rotation of the bodies around the `twist_axis`.
     pub twist_torque: Torque,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
 }
 
 impl XpbdConstraint<2> for SphericalJoint {
@@ -58,6 +87,10 @@ impl XpbdConstraint<2> for SphericalJoint {
         [self.entity1, self.entity2]
     }
 
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
     fn clear_lagrange_multipliers(&mut self) {
         self.position_lagrange = 0.0;
         self.swing_lagrange = 0.0;
@@ -96,16 +129,20 @@ impl Joint for SphericalJoint {
             entity2,
             local_anchor1: Vector::ZERO,
             local_anchor2: Vector::ZERO,
+            collisions_disabled: true,
             swing_axis: Vector3::X,
             twist_axis: Vector3::Y,
             swing_limit: None,
             twist_limit: None,
+            swing_cone_limit: None,
+            limit_softness: None,
             damping_linear: 1.0,
             damping_angular: 1.0,
             position_lagrange: 0.0,
             swing_lagrange: 0.0,
             twist_lagrange: 0.0,
             compliance: 0.0,
+            softness: None,
             force: Vector::ZERO,
             #[cfg(feature = "2d")]
             swing_torque: 0.0,
@@ -115,6 +152,7 @@ impl Joint for SphericalJoint {
             twist_torque: 0.0,
             #[cfg(feature = "3d")]
             twist_torque: Vector::ZERO,
+            impulses: JointImpulses::default(),
         }
     }
 
@@ -122,6 +160,13 @@ impl Joint for SphericalJoint {
         Self { compliance, ..self }
     }
 
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
     fn with_local_anchor_1(self, anchor: Vector) -> Self {
         Self {
             local_anchor1: anchor,
@@ -150,6 +195,13 @@ impl Joint for SphericalJoint {
         }
     }
 
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
     fn local_anchor_1(&self) -> Vector {
         self.local_anchor1
     }
@@ -165,6 +217,18 @@ impl Joint for SphericalJoint {
     fn damping_angular(&self) -> Scalar {
         self.damping_angular
     }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force
+    }
+
+    fn torque(&self) -> Torque {
+        self.swing_torque + self.twist_torque
+    }
 }
 
 impl SphericalJoint {
@@ -185,6 +249,28 @@ impl SphericalJoint {
         }
     }
 
+    /// Limits the swing of the bodies to an elliptical cone.
+    ///
+    /// `max_angle1` is the maximum swing angle around the `twist_axis`, and `max_angle2` is
+    /// the maximum swing angle around the axis perpendicular to both the `swing_axis` and the `twist_axis`.
+    /// If both angles are equal, the cone is circular.
+    ///
+    /// This is used instead of the limits set with [`SphericalJoint::with_swing_limits`].
+    pub fn with_elliptical_swing_limits(self, max_angle1: Scalar, max_angle2: Scalar) -> Self {
+        Self {
+            swing_cone_limit: Some(ConeLimit::new(max_angle1, max_angle2)),
+            ..self
+        }
+    }
+
+    /// Makes the swing and twist limits soft, so that they act like damped springs instead of hard stops.
+    pub fn with_limit_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            limit_softness: Some(softness),
+            ..self
+        }
+    }
+
     /// Applies angle limits to limit the relative rotation of the bodies around the `swing_axis`.
     fn apply_swing_limits(
         &mut self,
@@ -192,88 +278,209 @@ impl SphericalJoint {
         body2: &mut RigidBodyQueryItem,
         dt: Scalar,
     ) -> Torque {
-        if let Some(joint_limit) = self.swing_limit {
-            let a1 = *body1.rotation * self.swing_axis;
-            let a2 = *body2.rotation * self.swing_axis;
+        let Some(correction) = self.swing_correction(&body1.rotation, &body2.rotation) else {
+            return Torque::ZERO;
+        };
+
+        let mut lagrange = self.swing_lagrange;
+        let torque = self.apply_limit_correction(body1, body2, correction, &mut lagrange, dt);
+        self.swing_lagrange = lagrange;
+        torque
+    }
 
-            let n = a1.cross(a2);
-            let n_magnitude = n.length();
+    /// Applies angle limits to limit the relative rotation of the bodies around the `twist_axis`.
+    fn apply_twist_limits(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Torque {
+        let Some(correction) = self.twist_correction(&body1.rotation, &body2.rotation, dt) else {
+            return Torque::ZERO;
+        };
+
+        let mut lagrange = self.twist_lagrange;
+        let torque = self.apply_limit_correction(body1, body2, correction, &mut lagrange, dt);
+        self.twist_lagrange = lagrange;
+        torque
+    }
 
-            if n_magnitude <= Scalar::EPSILON {
-                return Torque::ZERO;
-            }
+    /// Returns the angular correction required to keep the swing of the bodies within the swing limits.
+    fn swing_correction(&self, rotation1: &Rotation, rotation2: &Rotation) -> Option<Vector> {
+        let a1 = *rotation1 * self.swing_axis;
+        let a2 = *rotation2 * self.swing_axis;
 
-            let n = n / n_magnitude;
-
-            if let Some(correction) = joint_limit.compute_correction(n, a1, a2, PI) {
-                let mut lagrange = self.swing_lagrange;
-                let torque = self.align_orientation(
-                    body1,
-                    body2,
-                    correction,
-                    &mut lagrange,
-                    self.compliance,
-                    dt,
-                );
-                self.swing_lagrange = lagrange;
-                return torque;
-            }
+        if let Some(cone_limit) = self.swing_cone_limit {
+            let b1 = *rotation1 * self.twist_axis;
+            return cone_limit.compute_correction(a1, a2, b1);
         }
-        Torque::ZERO
+
+        let joint_limit = self.swing_limit?;
+
+        let n = a1.cross(a2);
+        let n_magnitude = n.length();
+
+        if n_magnitude <= Scalar::EPSILON {
+            return None;
+        }
+
+        let n = n / n_magnitude;
+
+        joint_limit.compute_correction(n, a1, a2, PI)
     }
 
-    /// Applies angle limits to limit the relative rotation of the bodies around the `twist_axis`.
-    fn apply_twist_limits(
-        &mut self,
+    /// Returns the angular correction required to keep the twist of the bodies within the twist limits.
+    fn twist_correction(
+        &self,
+        rotation1: &Rotation,
+        rotation2: &Rotation,
+        dt: Scalar,
+    ) -> Option<Vector> {
+        let joint_limit = self.twist_limit?;
+
+        let a1 = *rotation1 * self.swing_axis;
+        let a2 = *rotation2 * self.swing_axis;
+
+        let b1 = *rotation1 * self.twist_axis;
+        let b2 = *rotation2 * self.twist_axis;
+
+        let n = a1 + a2;
+        let n_magnitude = n.length();
+
+        if n_magnitude <= Scalar::EPSILON {
+            return None;
+        }
+
+        let n = n / n_magnitude;
+
+        let n1 = b1 - n.dot(b1) * n;
+        let n2 = b2 - n.dot(b2) * n;
+        let n1_magnitude = n1.length();
+        let n2_magnitude = n2.length();
+
+        if n1_magnitude <= Scalar::EPSILON || n2_magnitude <= Scalar::EPSILON {
+            return None;
+        }
+
+        let n1 = n1 / n1_magnitude;
+        let n2 = n2 / n2_magnitude;
+
+        let max_correction = if a1.dot(a2) > -0.5 { 2.0 * PI } else { dt };
+
+        joint_limit.compute_correction(n, n1, n2, max_correction)
+    }
+
+    /// Applies an angular correction for the swing or twist limits.
+    ///
+    /// If `limit_softness` is set, the limit acts like a damped spring. Otherwise, it is a hard stop.
+    fn apply_limit_correction(
+        &self,
         body1: &mut RigidBodyQueryItem,
         body2: &mut RigidBodyQueryItem,
+        correction: Vector,
+        lagrange: &mut Scalar,
         dt: Scalar,
     ) -> Torque {
-        if let Some(joint_limit) = self.twist_limit {
-            let a1 = *body1.rotation * self.swing_axis;
-            let a2 = *body2.rotation * self.swing_axis;
+        let Some(softness) = self.limit_softness else {
+            return self.align_orientation(body1, body2, correction, lagrange, self.compliance, dt);
+        };
 
-            let b1 = *body1.rotation * self.twist_axis;
-            let b2 = *body2.rotation * self.twist_axis;
+        let angle = correction.length();
 
-            let n = a1 + a2;
-            let n_magnitude = n.length();
+        if angle <= Scalar::EPSILON {
+            return Torque::ZERO;
+        }
 
-            if n_magnitude <= Scalar::EPSILON {
-                return Torque::ZERO;
-            }
+        let axis = correction / angle;
+
+        // Compute generalized inverse masses
+        let w1 = AngularConstraint::compute_generalized_inverse_mass(self, body1, axis);
+        let w2 = AngularConstraint::compute_generalized_inverse_mass(self, body2, axis);
+        let w_sum = w1 + w2;
 
-            let n = n / n_magnitude;
+        if w_sum <= Scalar::EPSILON {
+            return Torque::ZERO;
+        }
 
-            let n1 = b1 - n.dot(b1) * n;
-            let n2 = b2 - n.dot(b2) * n;
-            let n1_magnitude = n1.length();
-            let n2_magnitude = n2.length();
+        // Damped XPBD update (Eq. 26 in the XPBD paper). The compliance is scaled by the inverse masses,
+        // and the damping coefficient is chosen to match the damping ratio, which makes the spring
+        // behave the same regardless of the masses of the bodies.
+        let tilde_compliance = softness.compute_compliance(w_sum) / dt.powi(2);
+        let gamma = 2.0 * softness.damping_ratio() / (softness.angular_frequency() * dt);
 
-            if n1_magnitude <= Scalar::EPSILON || n2_magnitude <= Scalar::EPSILON {
-                return Torque::ZERO;
-            }
+        // The rate at which the limit violation is increasing
+        let relative_velocity = (body2.angular_velocity.0 - body1.angular_velocity.0).dot(axis);
+
+        let delta_lagrange =
+            (-angle - tilde_compliance * *lagrange - gamma * relative_velocity * dt)
+                / ((1.0 + gamma) * w_sum + tilde_compliance);
+
+        // The limit can only push the bodies back towards the allowed range.
+        let delta_lagrange = delta_lagrange.min(-*lagrange);
+        *lagrange += delta_lagrange;
+
+        self.apply_angular_lagrange_update(body1, body2, delta_lagrange, axis);
+
+        self.compute_torque(delta_lagrange, axis, dt)
+    }
+}
+
+impl ImpulseJoint for SphericalJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
 
-            let n1 = n1 / n1_magnitude;
-            let n2 = n2 / n2_magnitude;
-
-            let max_correction = if a1.dot(a2) > -0.5 { 2.0 * PI } else { dt };
-
-            if let Some(correction) = joint_limit.compute_correction(n, n1, n2, max_correction) {
-                let mut lagrange = self.twist_lagrange;
-                let torque = self.align_orientation(
-                    body1,
-                    body2,
-                    correction,
-                    &mut lagrange,
-                    self.compliance,
-                    dt,
-                );
-                self.twist_lagrange = lagrange;
-                return torque;
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let softness = JointRowSoftness::from_joint(self.softness, self.compliance);
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+
+        // Align the attachment points
+        push_point_rows(rows, r1, r2, p2 - p1, softness);
+
+        // The limits are only active when they are violated. The correction is the rotation
+        // of the second body past the limit, so the rows rotate it back.
+        let limit_softness = self.limit_softness.map_or(softness, JointRowSoftness::Soft);
+        let corrections = [
+            (3, self.swing_correction(&body1.rotation, &body2.rotation)),
+            (
+                4,
+                self.twist_correction(&body1.rotation, &body2.rotation, delta_secs),
+            ),
+        ];
+
+        for (slot, correction) in corrections {
+            let Some(correction) = correction else {
+                continue;
+            };
+
+            let angle = correction.length();
+
+            if angle <= Scalar::EPSILON {
+                continue;
             }
+
+            rows.push(
+                JointRow::angular(slot, -correction / angle, -angle)
+                    .as_limit()
+                    .with_softness(limit_softness),
+            );
         }
-        Torque::ZERO
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, torque: Torque) {
+        self.force = force;
+        self.swing_torque = torque;
+        self.twist_torque = Vector::ZERO;
     }
 }
 
@@ -287,3 +494,80 @@ impl MapEntities for SphericalJoint {
         self.entity2 = entity_mapper.map_entity(self.entity2);
     }
 }
+
+/// An elliptical cone that limits the swing of a [`SphericalJoint`].
+///
+/// The cone is defined by two maximum swing angles around two perpendicular axes.
+/// If the angles are equal, the cone is circular.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, PartialEq)]
+pub struct ConeLimit {
+    /// The maximum swing angle around the first axis.
+    pub max_angle1: Scalar,
+    /// The maximum swing angle around the second axis.
+    pub max_angle2: Scalar,
+}
+
+impl ConeLimit {
+    /// Creates a new `ConeLimit`.
+    pub const fn new(max_angle1: Scalar, max_angle2: Scalar) -> Self {
+        Self {
+            max_angle1,
+            max_angle2,
+        }
+    }
+
+    /// Returns the maximum swing angle for a swing around the given `axis`, where `axis1`
+    /// and `axis2` are the perpendicular axes of the cone.
+    pub fn max_angle(&self, axis: Vector, axis1: Vector, axis2: Vector) -> Scalar {
+        // The swing axis expressed in the basis of the cone, as polar coordinates
+        // on the unit circle.
+        let cos = axis.dot(axis1);
+        let sin = axis.dot(axis2);
+
+        // Polar equation of an ellipse with the semi-axes `max_angle1` and `max_angle2`
+        let x = cos * self.max_angle2;
+        let y = sin * self.max_angle1;
+        let denominator = (x * x + y * y).sqrt();
+
+        if denominator <= Scalar::EPSILON {
+            return self.max_angle1.min(self.max_angle2);
+        }
+
+        self.max_angle1 * self.max_angle2 / denominator
+    }
+
+    /// Returns the angular correction required to keep `swing_axis2` within the cone
+    /// around `swing_axis1`. The first axis of the cone is `twist_axis1`, which should be
+    /// perpendicular to `swing_axis1`.
+    pub fn compute_correction(
+        &self,
+        swing_axis1: Vector,
+        swing_axis2: Vector,
+        twist_axis1: Vector,
+    ) -> Option<Vector> {
+        let n = swing_axis1.cross(swing_axis2);
+        let n_magnitude = n.length();
+
+        if n_magnitude <= Scalar::EPSILON {
+            return None;
+        }
+
+        let n = n / n_magnitude;
+
+        // Compute the swing angle and the maximum angle in the direction of the swing.
+        let angle = n_magnitude.atan2(swing_axis1.dot(swing_axis2));
+        let axis2 = swing_axis1.cross(twist_axis1);
+        let max_angle = self.max_angle(n, twist_axis1, axis2);
+
+        if angle <= max_angle {
+            return None;
+        }
+
+        // Rotate the first axis by the maximum angle and compute the correction.
+        let rot = Quaternion::from_axis_angle(n, max_angle);
+        Some((rot * swing_axis1).cross(swing_axis2))
+    }
+}
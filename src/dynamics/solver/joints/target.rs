@@ -0,0 +1,361 @@
+//! [`TargetJoint`] component.
+
+use crate::{
+    dynamics::solver::{xpbd::*, SolverConfig},
+    prelude::*,
+};
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+/// A target joint pulls an attachment point on a single body towards a world-space `target` like a damped spring.
+///
+/// Unlike other joints, a target joint only affects one body, so no second entity is required.
+/// The `target` can be moved freely, and the body follows it with the configured frequency and damping.
+/// The force applied by the joint can be limited with a `max_force` to keep the body from being pulled
+/// through obstacles or from dragging heavy objects unrealistically.
+///
+/// Target joints are useful for things like dragging bodies with the mouse in editors and physics sandboxes,
+/// or for "gravity gun" mechanics where a body is held in front of the player.
+///
+/// Target joints support [`BreakableJoint`] and [`JointForces`] like other joints.
+/// They never exert a torque, so only the `break_force` of a [`BreakableJoint`] applies.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// #[derive(Component)]
+/// struct Grab;
+///
+/// fn setup(mut commands: Commands) {
+///     let body = commands.spawn(RigidBody::Dynamic).id();
+///
+///     // Pull the body towards the origin
+///     commands.spawn((TargetJoint::new(body).with_max_force(1000.0), Grab));
+/// }
+///
+/// fn move_target(mut joints: Query<&mut TargetJoint, With<Grab>>) {
+///     for mut joint in &mut joints {
+///         // Move the target upwards
+///         joint.target.y += 0.1;
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, MapEntities, PartialEq)]
+pub struct TargetJoint {
+    /// The entity constrained by the joint.
+    pub entity: Entity,
+    /// Attachment point on the body.
+    pub local_anchor: Vector,
+    /// The world-space point that the attachment point is pulled towards.
+    pub target: Vector,
+    /// The maximum force that the joint can apply.
+    ///
+    /// Default: `Scalar::MAX`
+    pub max_force: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// Default: A damping ratio of `0.7` and a frequency of `5.0` Hz
+    pub softness: SoftnessParameters,
+    /// Lagrange multiplier for the positional correction.
+    pub lagrange: Scalar,
+    /// The impulse accumulated by the [impulse-based joint solver](super#solvers), used for warm starting.
+    pub impulse: Vector,
+    /// The force exerted by the joint.
+    pub force: Vector,
+}
+
+impl XpbdConstraint<1> for TargetJoint {
+    fn entities(&self) -> [Entity; 1] {
+        [self.entity]
+    }
+
+    fn clear_lagrange_multipliers(&mut self) {
+        self.lagrange = 0.0;
+    }
+
+    fn solve(&mut self, bodies: [&mut RigidBodyQueryItem; 1], dt: Scalar) {
+        let [body] = bodies;
+
+        let world_r = *body.rotation * (self.local_anchor - body.center_of_mass.0);
+        let offset = body.current_position() + *body.rotation * self.local_anchor - self.target;
+
+        #[cfg(feature = "2d")]
+        let velocity = body.linear_velocity.0 + body.angular_velocity.0 * world_r.perp();
+        #[cfg(feature = "3d")]
+        let velocity = body.linear_velocity.0 + body.angular_velocity.0.cross(world_r);
+
+        // Damped XPBD update (Eq. 26 in the XPBD paper). The damping coefficient is chosen
+        // to match the damping ratio, and the velocity of the attachment point is used to estimate
+        // how much the offset changes during the substep. Combining the offset and the damping term
+        // into a single vector also damps motion perpendicular to the offset.
+        let gamma = 2.0 * self.softness.damping_ratio() / (self.softness.angular_frequency() * dt);
+        let c = offset + gamma * velocity * dt;
+        let magnitude = c.length();
+
+        if magnitude <= Scalar::EPSILON {
+            self.force = Vector::ZERO;
+            return;
+        }
+
+        let dir = c / magnitude;
+
+        let w = self.compute_generalized_inverse_mass(body, world_r, dir);
+
+        if w <= Scalar::EPSILON {
+            self.force = Vector::ZERO;
+            return;
+        }
+
+        // The compliance is scaled by the inverse mass so that the frequency is independent of the mass.
+        let tilde_compliance = self.softness.compute_compliance(w) / dt.powi(2);
+        let delta_lagrange = -magnitude / ((1.0 + gamma) * w + tilde_compliance);
+
+        // Limit the force applied by the joint.
+        let delta_lagrange = delta_lagrange.max(-self.max_force * dt.powi(2) - self.lagrange);
+        self.lagrange += delta_lagrange;
+
+        self.apply_impulse(body, delta_lagrange * dir, world_r);
+
+        self.force = self.lagrange * dir / dt.powi(2);
+    }
+}
+
+impl TargetJoint {
+    /// Creates a new [`TargetJoint`] that pulls the given entity towards the origin.
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            local_anchor: Vector::ZERO,
+            target: Vector::ZERO,
+            max_force: Scalar::MAX,
+            softness: SoftnessParameters::new(0.7, 5.0),
+            lagrange: 0.0,
+            impulse: Vector::ZERO,
+            force: Vector::ZERO,
+        }
+    }
+
+    /// Sets the attachment point on the body.
+    pub fn with_local_anchor(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor: anchor,
+            ..self
+        }
+    }
+
+    /// Sets the world-space point that the attachment point is pulled towards.
+    pub fn with_target(self, target: Vector) -> Self {
+        Self { target, ..self }
+    }
+
+    /// Sets the maximum force that the joint can apply.
+    pub fn with_max_force(self, max_force: Scalar) -> Self {
+        Self { max_force, ..self }
+    }
+
+    /// Sets the softness of the joint using a damping ratio and a frequency.
+    pub fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self { softness, ..self }
+    }
+
+    /// Computes the generalized inverse mass of the body when applying a positional correction
+    /// at point `r` along the vector `n`.
+    fn compute_generalized_inverse_mass(
+        &self,
+        body: &RigidBodyQueryItem,
+        r: Vector,
+        n: Vector,
+    ) -> Scalar {
+        if !body.rb.is_dynamic() {
+            return 0.0;
+        }
+
+        #[cfg(feature = "2d")]
+        {
+            body.inverse_mass.0 + body.inverse_inertia.0 * r.perp_dot(n).powi(2)
+        }
+        #[cfg(feature = "3d")]
+        {
+            let r_cross_n = r.cross(n);
+            body.inverse_mass.0 + r_cross_n.dot(body.effective_world_inv_inertia() * r_cross_n)
+        }
+    }
+
+    /// Applies a positional impulse to the body at the attachment point `r`.
+    fn apply_impulse(&self, body: &mut RigidBodyQueryItem, impulse: Vector, r: Vector) {
+        if !body.rb.is_dynamic() {
+            return;
+        }
+
+        let inv_mass = body.effective_inv_mass();
+        let inv_inertia = body.effective_world_inv_inertia();
+
+        body.accumulated_translation.0 += impulse * inv_mass;
+
+        #[cfg(feature = "2d")]
+        {
+            let delta_angle = inv_inertia * r.perp_dot(impulse);
+            *body.rotation = body.rotation.add_angle(delta_angle);
+        }
+        #[cfg(feature = "3d")]
+        {
+            // In 3D, adding quaternions can result in unnormalized rotations,
+            // so the rotation must be normalized.
+            let delta_quat =
+                Quaternion::from_vec4(0.5 * (inv_inertia * r.cross(impulse)).extend(0.0))
+                    * body.rotation.0;
+            body.rotation.0 = (body.rotation.0 + delta_quat).normalize();
+        }
+    }
+}
+
+impl MapEntities for TargetJoint {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.entity = entity_mapper.map_entity(self.entity);
+    }
+}
+
+/// Applies a velocity `impulse` to a body at the world-space offset `r` from its center of mass.
+fn apply_velocity_impulse(body: &mut RigidBodyQueryItem, impulse: Vector, r: Vector) {
+    if !body.rb.is_dynamic() {
+        return;
+    }
+
+    let inv_mass = body.effective_inv_mass();
+    let inv_inertia = body.effective_world_inv_inertia();
+    body.linear_velocity.0 += inv_mass * impulse;
+    body.angular_velocity.0 += inv_inertia * cross(r, impulse);
+}
+
+/// Warm starts [`TargetJoint`]s by applying the impulses accumulated during the previous substep.
+pub(crate) fn warm_start_target_joints(
+    mut bodies: Query<RigidBodyQuery>,
+    joints: Query<&TargetJoint, Without<RigidBody>>,
+    solver_config: Res<SolverConfig>,
+) {
+    for joint in &joints {
+        let Ok(mut body) = bodies.get_mut(joint.entity) else {
+            continue;
+        };
+
+        let r = *body.rotation * (joint.local_anchor - body.center_of_mass.0);
+        let impulse = solver_config.warm_start_coefficient * joint.impulse;
+        apply_velocity_impulse(&mut body, impulse, r);
+    }
+}
+
+/// Solves [`TargetJoint`]s using impulses, pulling the attachment points towards the targets like damped springs.
+///
+/// The joint is solved separately along each coordinate axis, and the accumulated impulse
+/// is limited by the `max_force` of the joint. Sleeping bodies are woken up if `USE_BIAS` is `true`.
+///
+/// This is run once per iteration in the [`SolveConstraintsSchedule`](crate::dynamics::solver::SolveConstraintsSchedule)
+/// and [`RelaxSchedule`](crate::dynamics::solver::RelaxSchedule).
+pub(crate) fn solve_target_joints<const USE_BIAS: bool>(
+    mut commands: Commands,
+    mut bodies: Query<RigidBodyQuery>,
+    mut joints: Query<&mut TargetJoint, Without<RigidBody>>,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_seconds_adjusted();
+
+    for mut joint in &mut joints {
+        let Ok(mut body) = bodies.get_mut(joint.entity) else {
+            continue;
+        };
+
+        if !body.rb.is_dynamic() {
+            continue;
+        }
+
+        if USE_BIAS {
+            body.time_sleeping.0 = 0.0;
+            if body.is_sleeping {
+                commands.entity(body.entity).remove::<Sleeping>();
+            }
+        }
+
+        let r = *body.rotation * (joint.local_anchor - body.center_of_mass.0);
+        let offset = body.current_position() + *body.rotation * joint.local_anchor - joint.target;
+        let coefficients = joint.softness.compute_coefficients(delta_secs);
+
+        for axis in Vector::AXES {
+            let inv_mass = axis.dot(body.effective_inv_mass() * axis);
+            #[cfg(feature = "2d")]
+            let inv_inertia = body.effective_world_inv_inertia() * cross(r, axis).powi(2);
+            #[cfg(feature = "3d")]
+            let inv_inertia = {
+                let r_cross_axis = cross(r, axis);
+                r_cross_axis.dot(body.effective_world_inv_inertia() * r_cross_axis)
+            };
+            let w = inv_mass + inv_inertia;
+
+            if w <= Scalar::EPSILON {
+                continue;
+            }
+
+            let velocity = body.velocity_at_point(r).dot(axis);
+            let impulse =
+                -coefficients.mass_scale * (velocity + coefficients.bias * offset.dot(axis)) / w
+                    - coefficients.impulse_scale * joint.impulse.dot(axis);
+
+            joint.impulse += impulse * axis;
+            apply_velocity_impulse(&mut body, impulse * axis, r);
+        }
+
+        // Limit the force applied by the joint.
+        let max_impulse = joint.max_force * delta_secs;
+        if joint.impulse.length_squared() > max_impulse * max_impulse {
+            let clamped_impulse = joint.impulse.clamp_length_max(max_impulse);
+            apply_velocity_impulse(&mut body, clamped_impulse - joint.impulse, r);
+            joint.impulse = clamped_impulse;
+        }
+
+        if !USE_BIAS {
+            joint.force = joint.impulse / delta_secs;
+        }
+    }
+}
+
+/// Adds the force exerted by [`TargetJoint`]s during the current substep to their [`JointForces`].
+pub(crate) fn accumulate_target_joint_forces(
+    mut joints: Query<(&TargetJoint, &mut JointForces), Without<RigidBody>>,
+) {
+    for (joint, mut forces) in &mut joints {
+        forces.force += joint.force;
+    }
+}
+
+/// Removes [`TargetJoint`]s whose force exceeds the `break_force` of their [`BreakableJoint`] component,
+/// and sends a [`JointBroken`] event for each broken joint.
+pub(crate) fn break_target_joints(
+    mut commands: Commands,
+    joints: Query<(Entity, &TargetJoint, &BreakableJoint), Without<RigidBody>>,
+    mut broken_joints: EventWriter<JointBroken>,
+) {
+    for (entity, joint, thresholds) in &joints {
+        if thresholds.is_exceeded(joint.force, Torque::ZERO) {
+            commands.entity(entity).remove::<TargetJoint>();
+
+            broken_joints.send(JointBroken {
+                joint: entity,
+                entity1: joint.entity,
+                entity2: joint.entity,
+                force: joint.force,
+                torque: Torque::ZERO,
+            });
+        }
+    }
+}
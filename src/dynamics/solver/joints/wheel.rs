@@ -0,0 +1,588 @@
+//! [`WheelJoint`] component.
+
+#[cfg(feature = "3d")]
+use super::impulse::{push_axis_alignment_rows, relative_angle};
+use super::impulse::{push_limit_rows, world_anchors};
+use crate::{dynamics::solver::xpbd::*, prelude::*};
+use bevy::{
+    ecs::{
+        entity::{EntityMapper, MapEntities},
+        reflect::ReflectMapEntities,
+    },
+    prelude::*,
+};
+
+/// A wheel joint attaches a wheel to a chassis, allowing the wheel to rotate freely around its `wheel_axis`
+/// and to move along a `suspension_axis` that is driven by a spring.
+///
+/// The first body is the chassis and the second body is the wheel. The axes are given in the local space
+/// of the chassis. The suspension spring pulls the attachment points together along the suspension axis,
+/// and its stiffness is configured using a frequency and a damping ratio, so it behaves the same regardless
+/// of the masses of the bodies. The travel of the suspension can be limited using [`WheelJoint::with_suspension_limits`].
+///
+/// The wheel can be driven by a [`JointMotor`] that rotates it around the wheel axis.
+///
+/// Wheel joints can be useful for things like cars, bikes and other vehicles.
+///
+/// ## Example
+///
+/// ```
+#[cfg_attr(feature = "2d", doc = "use avian2d::prelude::*;")]
+#[cfg_attr(feature = "3d", doc = "use avian3d::prelude::*;")]
+/// use bevy::prelude::*;
+///
+/// fn setup(mut commands: Commands) {
+///     let chassis = commands.spawn(RigidBody::Dynamic).id();
+///     let wheel = commands.spawn(RigidBody::Dynamic).id();
+///
+///     commands.spawn(
+///         WheelJoint::new(chassis, wheel)
+///             .with_suspension_softness(SoftnessParameters::new(0.7, 4.0))
+///             .with_suspension_limits(-0.2, 0.2)
+///             .with_motor(JointMotor::new_velocity(-10.0, 100.0).with_max_force(50.0)),
+///     );
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Component, MapEntities, PartialEq)]
+pub struct WheelJoint {
+    /// First entity constrained by the joint. This is normally the chassis.
+    pub entity1: Entity,
+    /// Second entity constrained by the joint. This is normally the wheel.
+    pub entity2: Entity,
+    /// Attachment point on the first body.
+    pub local_anchor1: Vector,
+    /// Attachment point on the second body.
+    pub local_anchor2: Vector,
+    /// If `true`, the bodies connected by the joint don't collide with each other.
+    pub collisions_disabled: bool,
+    /// The axis that the wheel can move along relative to the chassis, in the local space of the first body.
+    pub suspension_axis: Vector,
+    /// The axis that the wheel rotates around, in the local space of the first body.
+    ///
+    /// In 2D this should always be the Z axis.
+    #[cfg(feature = "2d")]
+    pub(crate) wheel_axis: Vector3,
+    /// The axis that the wheel rotates around, in the local space of the first body.
+    #[cfg(feature = "3d")]
+    pub wheel_axis: Vector,
+    /// The extents of the allowed relative translation along the suspension axis.
+    pub suspension_limit: Option<DistanceLimit>,
+    /// The stiffness and damping of the suspension spring, given as a damping ratio and a frequency.
+    ///
+    /// Default: A damping ratio of `0.7` and a frequency of `4.0` Hz
+    pub suspension_softness: SoftnessParameters,
+    /// A motor that drives the rotation of the wheel around the `wheel_axis`.
+    pub motor: Option<JointMotor>,
+    /// Linear damping applied by the joint.
+    pub damping_linear: Scalar,
+    /// Angular damping applied by the joint.
+    pub damping_angular: Scalar,
+    /// Lagrange multiplier for the positional correction.
+    pub position_lagrange: Scalar,
+    /// Lagrange multiplier for the positional correction caused by the suspension spring.
+    pub suspension_lagrange: Scalar,
+    /// Lagrange multiplier for the angular correction caused by the alignment of the wheel axes.
+    pub align_lagrange: Scalar,
+    /// Lagrange multiplier for the angular correction caused by the motor.
+    pub motor_lagrange: Scalar,
+    /// The joint's compliance, the inverse of stiffness, has the unit meters / Newton.
+    pub compliance: Scalar,
+    /// The softness of the joint, given as a damping ratio and a frequency.
+    ///
+    /// If `Some`, the softness overrides the compliance of the joint.
+    /// This does not affect the suspension spring.
+    pub softness: Option<SoftnessParameters>,
+    /// The force exerted by the joint.
+    pub force: Vector,
+    /// The force exerted by the suspension spring.
+    pub suspension_force: Vector,
+    /// The torque exerted by the joint when aligning the wheel axes.
+    pub align_torque: Torque,
+    /// The torque exerted by the joint's motor around the `wheel_axis`.
+    pub motor_torque: Torque,
+    /// The impulses accumulated for each [`JointRow`] when the joint is solved as an [`ImpulseJoint`].
+    pub impulses: JointImpulses,
+}
+
+impl XpbdConstraint<2> for WheelJoint {
+    fn entities(&self) -> [Entity; 2] {
+        [self.entity1, self.entity2]
+    }
+
+    fn softness(&self) -> Option<SoftnessParameters> {
+        self.softness
+    }
+
+    fn clear_lagrange_multipliers(&mut self) {
+        self.position_lagrange = 0.0;
+        self.suspension_lagrange = 0.0;
+        self.align_lagrange = 0.0;
+        self.motor_lagrange = 0.0;
+    }
+
+    fn solve(&mut self, bodies: [&mut RigidBodyQueryItem; 2], dt: Scalar) {
+        let [body1, body2] = bodies;
+
+        #[cfg(feature = "3d")]
+        {
+            // Align the wheel axes, only allowing rotation around the wheel axis
+            let difference = self.get_rotation_difference(&body1.rotation, &body2.rotation);
+            let mut lagrange = self.align_lagrange;
+            self.align_torque = self.align_orientation(
+                body1,
+                body2,
+                difference,
+                &mut lagrange,
+                self.compliance,
+                dt,
+            );
+            self.align_lagrange = lagrange;
+        }
+
+        // Drive the rotation of the wheel using the motor
+        self.motor_torque = self.apply_motor(body1, body2, dt);
+
+        // Pull the wheel towards the rest position using the suspension spring
+        self.suspension_force = self.apply_suspension(body1, body2, dt);
+
+        // Constrain the relative positions of the bodies, only allowing translation along the suspension axis
+        self.force = self.constrain_positions(body1, body2, dt);
+    }
+}
+
+impl Joint for WheelJoint {
+    fn new(entity1: Entity, entity2: Entity) -> Self {
+        Self {
+            entity1,
+            entity2,
+            local_anchor1: Vector::ZERO,
+            local_anchor2: Vector::ZERO,
+            collisions_disabled: true,
+            suspension_axis: Vector::Y,
+            wheel_axis: Vector3::Z,
+            suspension_limit: None,
+            suspension_softness: SoftnessParameters::new(0.7, 4.0),
+            motor: None,
+            damping_linear: 0.0,
+            damping_angular: 0.0,
+            position_lagrange: 0.0,
+            suspension_lagrange: 0.0,
+            align_lagrange: 0.0,
+            motor_lagrange: 0.0,
+            compliance: 0.0,
+            softness: None,
+            force: Vector::ZERO,
+            suspension_force: Vector::ZERO,
+            #[cfg(feature = "2d")]
+            align_torque: 0.0,
+            #[cfg(feature = "3d")]
+            align_torque: Vector::ZERO,
+            #[cfg(feature = "2d")]
+            motor_torque: 0.0,
+            #[cfg(feature = "3d")]
+            motor_torque: Vector::ZERO,
+            impulses: JointImpulses::default(),
+        }
+    }
+
+    fn with_compliance(self, compliance: Scalar) -> Self {
+        Self { compliance, ..self }
+    }
+
+    fn with_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            softness: Some(softness),
+            ..self
+        }
+    }
+
+    fn with_local_anchor_1(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor1: anchor,
+            ..self
+        }
+    }
+
+    fn with_local_anchor_2(self, anchor: Vector) -> Self {
+        Self {
+            local_anchor2: anchor,
+            ..self
+        }
+    }
+
+    fn with_linear_velocity_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_linear: damping,
+            ..self
+        }
+    }
+
+    fn with_angular_velocity_damping(self, damping: Scalar) -> Self {
+        Self {
+            damping_angular: damping,
+            ..self
+        }
+    }
+
+    fn with_collisions_disabled(self, disabled: bool) -> Self {
+        Self {
+            collisions_disabled: disabled,
+            ..self
+        }
+    }
+
+    fn local_anchor_1(&self) -> Vector {
+        self.local_anchor1
+    }
+
+    fn local_anchor_2(&self) -> Vector {
+        self.local_anchor2
+    }
+
+    fn damping_linear(&self) -> Scalar {
+        self.damping_linear
+    }
+
+    fn damping_angular(&self) -> Scalar {
+        self.damping_angular
+    }
+
+    fn collisions_disabled(&self) -> bool {
+        self.collisions_disabled
+    }
+
+    fn force(&self) -> Vector {
+        self.force + self.suspension_force
+    }
+
+    fn torque(&self) -> Torque {
+        self.align_torque + self.motor_torque
+    }
+}
+
+impl WheelJoint {
+    /// Sets the axis that the wheel can move along relative to the chassis.
+    pub fn with_suspension_axis(self, axis: Vector) -> Self {
+        Self {
+            suspension_axis: axis,
+            ..self
+        }
+    }
+
+    /// Sets the axis that the wheel rotates around.
+    #[cfg(feature = "3d")]
+    pub fn with_wheel_axis(self, axis: Vector) -> Self {
+        Self {
+            wheel_axis: axis,
+            ..self
+        }
+    }
+
+    /// Sets the limits of the allowed relative translation along the suspension axis.
+    pub fn with_suspension_limits(self, min: Scalar, max: Scalar) -> Self {
+        Self {
+            suspension_limit: Some(DistanceLimit::new(min, max)),
+            ..self
+        }
+    }
+
+    /// Sets the stiffness and damping of the suspension spring using a damping ratio and a frequency.
+    pub fn with_suspension_softness(self, softness: SoftnessParameters) -> Self {
+        Self {
+            suspension_softness: softness,
+            ..self
+        }
+    }
+
+    /// Sets the motor that drives the rotation of the wheel around the `wheel_axis`.
+    pub fn with_motor(self, motor: JointMotor) -> Self {
+        Self {
+            motor: Some(motor),
+            ..self
+        }
+    }
+
+    /// Constrains the relative positions of the bodies, only allowing translation along the suspension axis.
+    ///
+    /// Returns the force exerted by this constraint.
+    fn constrain_positions(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Vector {
+        let world_r1 = *body1.rotation * self.local_anchor1;
+        let world_r2 = *body2.rotation * self.local_anchor2;
+        let p1 = body1.current_position() + world_r1;
+        let p2 = body2.current_position() + world_r2;
+
+        let mut delta_x = Vector::ZERO;
+
+        let axis1 = *body1.rotation * self.suspension_axis;
+        if let Some(limits) = self.suspension_limit {
+            delta_x += limits.compute_correction_along_axis(p1, p2, axis1);
+        }
+
+        let zero_distance_limit = DistanceLimit::ZERO;
+
+        #[cfg(feature = "2d")]
+        {
+            let axis2 = Vector::new(axis1.y, -axis1.x);
+            delta_x += zero_distance_limit.compute_correction_along_axis(p1, p2, axis2);
+        }
+        #[cfg(feature = "3d")]
+        {
+            let axis2 = axis1.any_orthogonal_vector();
+            let axis3 = axis1.cross(axis2);
+
+            delta_x += zero_distance_limit.compute_correction_along_axis(p1, p2, axis2);
+            delta_x += zero_distance_limit.compute_correction_along_axis(p1, p2, axis3);
+        }
+
+        let magnitude = delta_x.length();
+
+        if magnitude <= Scalar::EPSILON {
+            return Vector::ZERO;
+        }
+
+        let dir = delta_x / magnitude;
+
+        // Compute generalized inverse masses
+        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, dir);
+        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, dir);
+
+        // Compute Lagrange multiplier update
+        let delta_lagrange = self.compute_lagrange_update(
+            self.position_lagrange,
+            magnitude,
+            &[w1, w2],
+            self.compliance,
+            dt,
+        );
+        self.position_lagrange += delta_lagrange;
+
+        // Apply positional correction to align the positions of the bodies
+        self.apply_positional_lagrange_update(
+            body1,
+            body2,
+            delta_lagrange,
+            dir,
+            world_r1,
+            world_r2,
+        );
+
+        // Return constraint force
+        self.compute_force(self.position_lagrange, dir, dt)
+    }
+
+    /// Applies the suspension spring that pulls the attachment points together along the suspension axis.
+    ///
+    /// Returns the force exerted by the spring.
+    fn apply_suspension(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Vector {
+        let world_r1 = *body1.rotation * self.local_anchor1;
+        let world_r2 = *body2.rotation * self.local_anchor2;
+        let axis = *body1.rotation * self.suspension_axis;
+
+        // The relative position and velocity of the attachment points along the suspension axis
+        let offset = body2.current_position() + world_r2 - body1.current_position() - world_r1;
+        let position = offset.dot(axis);
+        let velocity =
+            (body2.velocity_at_point(world_r2) - body1.velocity_at_point(world_r1)).dot(axis);
+
+        // Compute generalized inverse masses
+        let w1 = PositionConstraint::compute_generalized_inverse_mass(self, body1, world_r1, axis);
+        let w2 = PositionConstraint::compute_generalized_inverse_mass(self, body2, world_r2, axis);
+        let w_sum = w1 + w2;
+
+        if w_sum <= Scalar::EPSILON {
+            return Vector::ZERO;
+        }
+
+        // The spring is a position motor with a target position of zero. The stiffness and damping
+        // are scaled by the effective mass so that the frequency and damping ratio are independent of the masses.
+        let effective_mass = 1.0 / w_sum;
+        let angular_frequency = self.suspension_softness.angular_frequency();
+        let spring = JointMotor::new_position(
+            0.0,
+            effective_mass * angular_frequency.powi(2),
+            2.0 * effective_mass * self.suspension_softness.damping_ratio() * angular_frequency,
+        );
+
+        let delta_lagrange =
+            self.compute_motor_lagrange_update(&spring, position, velocity, w_sum, dt);
+        self.suspension_lagrange += delta_lagrange;
+
+        // Positional corrections push the bodies together along the given direction,
+        // so the axis is negated to increase the relative position instead.
+        let dir = -axis;
+        self.apply_positional_lagrange_update(
+            body1,
+            body2,
+            delta_lagrange,
+            dir,
+            world_r1,
+            world_r2,
+        );
+
+        // Return spring force
+        self.compute_force(self.suspension_lagrange, dir, dt)
+    }
+
+    /// Applies the joint's motor to drive the rotation of the wheel around the `wheel_axis`.
+    fn apply_motor(
+        &mut self,
+        body1: &mut RigidBodyQueryItem,
+        body2: &mut RigidBodyQueryItem,
+        dt: Scalar,
+    ) -> Torque {
+        let Some(motor) = self.motor else {
+            return Torque::ZERO;
+        };
+
+        #[cfg(feature = "2d")]
+        {
+            let angle = body1.rotation.angle_between(*body2.rotation);
+            let angular_velocity = body2.angular_velocity.0 - body1.angular_velocity.0;
+            let w = body1.effective_world_inv_inertia() + body2.effective_world_inv_inertia();
+
+            let delta_lagrange =
+                self.compute_motor_lagrange_update(&motor, angle, angular_velocity, w, dt);
+            self.motor_lagrange += delta_lagrange;
+            self.apply_angular_lagrange_update(body1, body2, delta_lagrange);
+
+            self.compute_torque(self.motor_lagrange, dt)
+        }
+        #[cfg(feature = "3d")]
+        {
+            // Compute the relative angle around the wheel axis using perpendicular reference axes.
+            let axis = *body1.rotation * self.wheel_axis;
+            let b = self.wheel_axis.any_orthonormal_vector();
+            let b1 = *body1.rotation * b;
+            let b2 = *body2.rotation * b;
+            let angle = b1.cross(b2).dot(axis).atan2(b1.dot(b2));
+            let angular_velocity = (body2.angular_velocity.0 - body1.angular_velocity.0).dot(axis);
+
+            let w1 = AngularConstraint::compute_generalized_inverse_mass(self, body1, axis);
+            let w2 = AngularConstraint::compute_generalized_inverse_mass(self, body2, axis);
+
+            let delta_lagrange =
+                self.compute_motor_lagrange_update(&motor, angle, angular_velocity, w1 + w2, dt);
+            self.motor_lagrange += delta_lagrange;
+            self.apply_angular_lagrange_update(body1, body2, delta_lagrange, axis);
+
+            self.compute_torque(self.motor_lagrange, axis, dt)
+        }
+    }
+
+    #[cfg(feature = "3d")]
+    fn get_rotation_difference(&self, rot1: &Rotation, rot2: &Rotation) -> Vector3 {
+        let a1 = rot1 * self.wheel_axis;
+        let a2 = rot2 * self.wheel_axis;
+        a1.cross(a2)
+    }
+}
+
+impl ImpulseJoint for WheelJoint {
+    fn impulses(&self) -> &JointImpulses {
+        &self.impulses
+    }
+
+    fn impulses_mut(&mut self) -> &mut JointImpulses {
+        &mut self.impulses
+    }
+
+    fn compute_rows(
+        &mut self,
+        body1: &RigidBodyQueryItem,
+        body2: &RigidBodyQueryItem,
+        delta_secs: Scalar,
+        rows: &mut Vec<JointRow>,
+    ) {
+        let softness = JointRowSoftness::from_joint(self.softness, self.compliance);
+        let [r1, r2, p1, p2] = world_anchors(body1, body2, self.local_anchor1, self.local_anchor2);
+        let offset = p2 - p1;
+        let axis = *body1.rotation * self.suspension_axis;
+
+        // The axes are attached to the chassis, so its lever arm extends to the second attachment point
+        let r1 = r1 + offset;
+
+        // Only allow translation along the suspension axis
+        #[cfg(feature = "2d")]
+        let perpendicular_axes = [Vector::new(axis.y, -axis.x)];
+        #[cfg(feature = "3d")]
+        let perpendicular_axes: [Vector; 2] = axis.any_orthonormal_pair().into();
+
+        for (i, perpendicular) in perpendicular_axes.into_iter().enumerate() {
+            let error = offset.dot(perpendicular);
+            rows.push(JointRow::linear(i, r1, r2, perpendicular, error).with_softness(softness));
+        }
+
+        // Align the wheel axes, only allowing the wheel to rotate around its axis
+        #[cfg(feature = "3d")]
+        push_axis_alignment_rows(
+            rows,
+            *body1.rotation * self.wheel_axis,
+            *body2.rotation * self.wheel_axis,
+            DIM - 1,
+            softness,
+        );
+
+        // The suspension, its limits and the motor use the slots after the perpendicular and alignment rows
+        let slot = DIM + ANGULAR_DIM - 2;
+        let position = offset.dot(axis);
+        let row = JointRow::linear(slot, r1, r2, axis, position);
+
+        // The suspension spring pulls the attachment points together along the suspension axis
+        rows.push(row.with_softness(JointRowSoftness::Soft(self.suspension_softness)));
+
+        if let Some(limits) = self.suspension_limit {
+            let row = row.with_softness(softness);
+            push_limit_rows(
+                rows,
+                row,
+                [slot + 1, slot + 2],
+                position,
+                limits.min,
+                limits.max,
+            );
+        }
+
+        if let Some(motor) = self.motor {
+            #[cfg(feature = "2d")]
+            let row = JointRow::angular(slot + 3, body1.rotation.angle_between(*body2.rotation));
+            #[cfg(feature = "3d")]
+            let row = {
+                let (axis, angle) =
+                    relative_angle(&body1.rotation, &body2.rotation, self.wheel_axis);
+                JointRow::angular(slot + 3, axis, angle)
+            };
+            rows.push(row.with_motor(&motor, delta_secs));
+        }
+    }
+
+    fn set_force_and_torque(&mut self, force: Vector, torque: Torque) {
+        self.force = force;
+        self.suspension_force = Vector::ZERO;
+        self.align_torque = torque;
+        self.motor_torque = Torque::ZERO;
+    }
+}
+
+impl PositionConstraint for WheelJoint {}
+
+impl AngularConstraint for WheelJoint {}
+
+impl MapEntities for WheelJoint {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.entity1 = entity_mapper.map_entity(self.entity1);
+        self.entity2 = entity_mapper.map_entity(self.entity2);
+    }
+}
@@ -2,17 +2,23 @@
 //!
 //! See [`SolverPlugin`].
 
+pub mod articulations;
 pub mod contact;
+pub mod graph_coloring;
 pub mod joints;
 pub mod softness_parameters;
 pub mod xpbd;
 
 use crate::prelude::*;
-use bevy::prelude::*;
+use bevy::{
+    ecs::schedule::{ExecutorKind, LogLevel, ScheduleBuildSettings, ScheduleLabel},
+    prelude::*,
+};
 
 use self::{
     contact::ContactConstraint,
     dynamics::integrator::IntegrationSet,
+    graph_coloring::{ContactGraphColoring, JointGraphColoring},
     softness_parameters::{SoftnessCoefficients, SoftnessParameters},
 };
 
@@ -25,11 +31,15 @@ use self::{
 /// The solver primarily uses TGS Soft, an impulse-based solver with substepping and [soft constraints](softness_parameters).
 /// Warm starting is used to improve convergence, along with a relaxation pass to reduce overshooting.
 ///
+/// Contacts and joints are grouped into batches of independent constraints using [graph coloring](graph_coloring).
+/// With the `parallel` feature, the constraints in each batch are solved in parallel.
+///
 /// [Speculative collision](dynamics::ccd#speculative-collision) is used by default to prevent tunneling.
 /// Optional [sweep-based Continuous Collision Detection (CCD)](dynamics::ccd#swept-ccd) is handled by the [`CcdPlugin`].
 ///
-/// [Joints](joints) and user constraints are currently solved using [Extended Position-Based Dynamics (XPBD)](xpbd).
-/// In the future, they may transition to an impulse-based approach as well.
+/// [Joints](joints) are solved with the same impulse-based approach by default, but they can optionally be solved
+/// using [Extended Position-Based Dynamics (XPBD)](xpbd) by configuring the [`JointSolver`].
+/// User constraints are currently solved using XPBD.
 ///
 /// ## Steps
 ///
@@ -45,6 +55,7 @@ use self::{
 ///     6. [Solve XPBD constraints (joints)](SubstepSolverSet::SolveXpbdConstraints)
 ///     7. [Solve user-defined constraints](SubstepSolverSet::SolveUserConstraints)
 ///     8. [Update velocities after XPBD constraint solving.](SubstepSolverSet::XpbdVelocityProjection)
+///     9. [Solve articulations](SubstepSolverSet::SolveArticulations)
 /// 3. [Apply restitution](SolverSet::Restitution)
 /// 4. [Finalize positions by applying](SolverSet::ApplyTranslation) [`AccumulatedTranslation`]
 /// 5. [Store contact impulses for next frame's warm starting](SolverSet::StoreContactImpulses)
@@ -72,7 +83,22 @@ impl Plugin for SolverPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SolverConfig>()
             .init_resource::<ContactSoftnessCoefficients>()
-            .init_resource::<ContactConstraints>();
+            .init_resource::<ContactConstraints>()
+            .init_resource::<ContactGraphColoring>()
+            .init_resource::<JointCollisionPairs>()
+            .init_resource::<JointGraph>()
+            .add_event::<JointBroken>();
+
+        // Each joint type is colored separately.
+        app.init_resource::<JointGraphColoring<FixedJoint>>()
+            .init_resource::<JointGraphColoring<RevoluteJoint>>()
+            .init_resource::<JointGraphColoring<PrismaticJoint>>()
+            .init_resource::<JointGraphColoring<DistanceJoint>>()
+            .init_resource::<JointGraphColoring<GenericJoint>>()
+            .init_resource::<JointGraphColoring<PulleyJoint>>()
+            .init_resource::<JointGraphColoring<WheelJoint>>();
+        #[cfg(feature = "3d")]
+        app.init_resource::<JointGraphColoring<SphericalJoint>>();
 
         if !app.world().contains_resource::<PhysicsLengthUnit>() {
             app.insert_resource(PhysicsLengthUnit(self.length_unit));
@@ -85,6 +111,43 @@ impl Plugin for SolverPlugin {
 
         physics.add_systems(update_contact_softness.before(PhysicsStepSet::NarrowPhase));
 
+        // Keep track of joints and articulation links that disable collisions between the bodies they connect.
+        physics.add_systems(
+            (
+                update_joint_collision_pairs::<FixedJoint>,
+                update_joint_collision_pairs::<RevoluteJoint>,
+                #[cfg(feature = "3d")]
+                update_joint_collision_pairs::<SphericalJoint>,
+                update_joint_collision_pairs::<PrismaticJoint>,
+                update_joint_collision_pairs::<DistanceJoint>,
+                update_joint_collision_pairs::<GenericJoint>,
+                update_joint_collision_pairs::<PulleyJoint>,
+                update_joint_collision_pairs::<WheelJoint>,
+                articulations::update_articulation_collision_pairs,
+            )
+                .chain()
+                .before(PhysicsStepSet::BroadPhase),
+        );
+
+        // Keep track of the bodies connected by joints.
+        physics.add_systems(
+            (
+                update_joint_graph::<FixedJoint>,
+                update_joint_graph::<RevoluteJoint>,
+                #[cfg(feature = "3d")]
+                update_joint_graph::<SphericalJoint>,
+                update_joint_graph::<PrismaticJoint>,
+                update_joint_graph::<DistanceJoint>,
+                update_joint_graph::<GenericJoint>,
+                update_joint_graph::<PulleyJoint>,
+                update_joint_graph::<WheelJoint>,
+                joints::update_gear_joint_graph,
+                update_joint_graph_body_types,
+            )
+                .chain()
+                .before(PhysicsStepSet::BroadPhase),
+        );
+
         // See `SolverSet` for what each system set is responsible for.
         physics.configure_sets(
             (
@@ -117,6 +180,29 @@ impl Plugin for SolverPlugin {
                 .in_set(SolverSet::ApplyTranslation),
         );
 
+        // Group contacts and joints into batches of independent constraints.
+        physics.add_systems(
+            (
+                graph_coloring::color_contact_constraints,
+                (
+                    graph_coloring::color_joints::<FixedJoint>,
+                    graph_coloring::color_joints::<RevoluteJoint>,
+                    #[cfg(feature = "3d")]
+                    graph_coloring::color_joints::<SphericalJoint>,
+                    graph_coloring::color_joints::<PrismaticJoint>,
+                    graph_coloring::color_joints::<DistanceJoint>,
+                    graph_coloring::color_joints::<GenericJoint>,
+                    graph_coloring::color_joints::<PulleyJoint>,
+                    graph_coloring::color_joints::<WheelJoint>,
+                )
+                    .run_if(uses_joint_solver(JointSolver::Impulse)),
+            )
+                .in_set(SolverSet::PreSubstep),
+        );
+
+        // Reset the joint forces accumulated during the previous physics step.
+        physics.add_systems(clear_joint_forces.in_set(SolverSet::PreSubstep));
+
         // Apply restitution.
         physics.add_systems(solve_restitution.in_set(SolverSet::Restitution));
 
@@ -139,6 +225,7 @@ impl Plugin for SolverPlugin {
                 SubstepSolverSet::SolveXpbdConstraints,
                 SubstepSolverSet::SolveUserConstraints,
                 SubstepSolverSet::XpbdVelocityProjection,
+                SubstepSolverSet::SolveArticulations,
             )
                 .chain(),
         );
@@ -146,52 +233,40 @@ impl Plugin for SolverPlugin {
         // Warm start the impulses.
         // This applies the impulses stored from the previous substep,
         // which improves convergence.
-        substeps.add_systems(warm_start.in_set(SubstepSolverSet::WarmStart));
-
-        // Solve velocities using a position bias.
         substeps.add_systems(
             (
-                |mut bodies: Query<RigidBodyQuery>,
-                 mut constraints: ResMut<ContactConstraints>,
-                 solver_config: Res<SolverConfig>,
-                 length_unit: Res<PhysicsLengthUnit>,
-                 time: Res<Time>| {
-                    solve_contacts(
-                        &mut bodies,
-                        &mut constraints.0,
-                        time.delta_seconds_adjusted(),
-                        1,
-                        true,
-                        solver_config.max_overlap_solve_speed * length_unit.0,
-                    );
-                },
+                (
+                    warm_start_joints::<FixedJoint>,
+                    warm_start_joints::<RevoluteJoint>,
+                    #[cfg(feature = "3d")]
+                    warm_start_joints::<SphericalJoint>,
+                    warm_start_joints::<PrismaticJoint>,
+                    warm_start_joints::<DistanceJoint>,
+                    warm_start_joints::<GenericJoint>,
+                    warm_start_joints::<PulleyJoint>,
+                    warm_start_joints::<WheelJoint>,
+                    joints::warm_start_gear_joints,
+                    joints::warm_start_target_joints,
+                )
+                    .chain()
+                    .run_if(uses_joint_solver(JointSolver::Impulse)),
+                warm_start,
             )
-                .in_set(SubstepSolverSet::SolveConstraints),
+                .chain()
+                .in_set(SubstepSolverSet::WarmStart),
         );
 
+        // Solve velocities using a position bias.
+        // The `SolveConstraintsSchedule` is run once per iteration, so contacts and joints converge together.
+        substeps
+            .add_systems(run_solve_constraints_schedule.in_set(SubstepSolverSet::SolveConstraints));
+
         // Relax biased velocities and impulses.
         // This reduces overshooting caused by warm starting.
-        substeps.add_systems(
-            (
-                |mut bodies: Query<RigidBodyQuery>,
-                 mut constraints: ResMut<ContactConstraints>,
-                 solver_config: Res<SolverConfig>,
-                 length_unit: Res<PhysicsLengthUnit>,
-                 time: Res<Time>| {
-                    solve_contacts(
-                        &mut bodies,
-                        &mut constraints.0,
-                        time.delta_seconds_adjusted(),
-                        1,
-                        false,
-                        solver_config.max_overlap_solve_speed * length_unit.0,
-                    );
-                },
-            )
-                .in_set(SubstepSolverSet::Relax),
-        );
+        // The `RelaxSchedule` is run once per iteration.
+        substeps.add_systems(run_relax_schedule.in_set(SubstepSolverSet::Relax));
 
-        // Solve joints with XPBD.
+        // Solve joints with XPBD if it is the configured joint solver.
         substeps.add_systems(
             (
                 |mut query: Query<(
@@ -207,12 +282,21 @@ impl Plugin for SolverPlugin {
                         previous_rotation.0 = *rotation;
                     }
                 },
-                xpbd::solve_constraint::<FixedJoint, 2>,
-                xpbd::solve_constraint::<RevoluteJoint, 2>,
-                #[cfg(feature = "3d")]
-                xpbd::solve_constraint::<SphericalJoint, 2>,
-                xpbd::solve_constraint::<PrismaticJoint, 2>,
-                xpbd::solve_constraint::<DistanceJoint, 2>,
+                (
+                    xpbd::solve_constraint::<FixedJoint, 2>,
+                    xpbd::solve_constraint::<RevoluteJoint, 2>,
+                    #[cfg(feature = "3d")]
+                    xpbd::solve_constraint::<SphericalJoint, 2>,
+                    xpbd::solve_constraint::<PrismaticJoint, 2>,
+                    xpbd::solve_constraint::<DistanceJoint, 2>,
+                    xpbd::solve_constraint::<GenericJoint, 2>,
+                    xpbd::solve_constraint::<PulleyJoint, 2>,
+                    xpbd::solve_constraint::<WheelJoint, 2>,
+                    joints::solve_gear_joints,
+                    xpbd::solve_constraint::<TargetJoint, 1>,
+                )
+                    .chain()
+                    .run_if(uses_joint_solver(JointSolver::Xpbd)),
             )
                 .chain()
                 .in_set(SubstepSolverSet::SolveXpbdConstraints),
@@ -229,10 +313,119 @@ impl Plugin for SolverPlugin {
                 joint_damping::<SphericalJoint>,
                 joint_damping::<PrismaticJoint>,
                 joint_damping::<DistanceJoint>,
+                joint_damping::<GenericJoint>,
+                joint_damping::<PulleyJoint>,
+                joint_damping::<WheelJoint>,
             )
                 .chain()
                 .in_set(SubstepSolverSet::XpbdVelocityProjection),
         );
+
+        // Accumulate the forces and torques exerted by joints with the `JointForces` component.
+        substeps.add_systems(
+            (
+                accumulate_joint_forces::<FixedJoint>,
+                accumulate_joint_forces::<RevoluteJoint>,
+                #[cfg(feature = "3d")]
+                accumulate_joint_forces::<SphericalJoint>,
+                accumulate_joint_forces::<PrismaticJoint>,
+                accumulate_joint_forces::<DistanceJoint>,
+                accumulate_joint_forces::<GenericJoint>,
+                accumulate_joint_forces::<PulleyJoint>,
+                accumulate_joint_forces::<WheelJoint>,
+                joints::accumulate_gear_joint_forces,
+                joints::accumulate_target_joint_forces,
+            )
+                .chain()
+                .in_set(SubstepSolverSet::XpbdVelocityProjection),
+        );
+
+        // Break joints that exceed the force or torque thresholds of their `BreakableJoint` component.
+        substeps.add_systems(
+            (
+                break_joints::<FixedJoint>,
+                break_joints::<RevoluteJoint>,
+                #[cfg(feature = "3d")]
+                break_joints::<SphericalJoint>,
+                break_joints::<PrismaticJoint>,
+                break_joints::<DistanceJoint>,
+                break_joints::<GenericJoint>,
+                break_joints::<PulleyJoint>,
+                break_joints::<WheelJoint>,
+                joints::break_gear_joints,
+                joints::break_target_joints,
+            )
+                .chain()
+                .in_set(SubstepSolverSet::XpbdVelocityProjection),
+        );
+
+        // Simulate articulations in reduced coordinates.
+        substeps.add_systems(
+            articulations::solve_articulations.in_set(SubstepSolverSet::SolveArticulations),
+        );
+
+        // Set up the schedules that are run for each solver iteration.
+        // They are single-threaded like the `SubstepSchedule`, because their systems access the same bodies.
+        for schedule in [SolveConstraintsSchedule.intern(), RelaxSchedule.intern()] {
+            app.edit_schedule(schedule, |schedule| {
+                schedule
+                    .set_executor_kind(ExecutorKind::SingleThreaded)
+                    .set_build_settings(ScheduleBuildSettings {
+                        ambiguity_detection: LogLevel::Error,
+                        ..default()
+                    });
+            });
+        }
+
+        // Solve contacts and joints using a position bias in each iteration of `SubstepSolverSet::SolveConstraints`.
+        // The `SolveConstraintsSchedule` and `RelaxSchedule` are separate from the `SubstepSchedule`,
+        // so their systems are not in any `SubstepSolverSet`.
+        app.add_systems(
+            SolveConstraintsSchedule,
+            (
+                (
+                    solve_joints::<FixedJoint, true>,
+                    solve_joints::<RevoluteJoint, true>,
+                    #[cfg(feature = "3d")]
+                    solve_joints::<SphericalJoint, true>,
+                    solve_joints::<PrismaticJoint, true>,
+                    solve_joints::<DistanceJoint, true>,
+                    solve_joints::<GenericJoint, true>,
+                    solve_joints::<PulleyJoint, true>,
+                    solve_joints::<WheelJoint, true>,
+                    joints::solve_gear_joints_impulse::<true>,
+                    joints::solve_target_joints::<true>,
+                )
+                    .chain()
+                    .run_if(uses_joint_solver(JointSolver::Impulse)),
+                solve_contacts_system::<true>,
+            )
+                .chain(),
+        );
+
+        // Relax contacts and joints in each iteration of `SubstepSolverSet::Relax`.
+        app.add_systems(
+            RelaxSchedule,
+            (
+                (
+                    solve_joints::<FixedJoint, false>,
+                    solve_joints::<RevoluteJoint, false>,
+                    #[cfg(feature = "3d")]
+                    solve_joints::<SphericalJoint, false>,
+                    solve_joints::<PrismaticJoint, false>,
+                    solve_joints::<DistanceJoint, false>,
+                    solve_joints::<GenericJoint, false>,
+                    solve_joints::<PulleyJoint, false>,
+                    solve_joints::<WheelJoint, false>,
+                    joints::solve_gear_joints_impulse::<false>,
+                    joints::solve_target_joints::<false>,
+                )
+                    .chain()
+                    .run_if(uses_joint_solver(JointSolver::Impulse)),
+                solve_contacts_system::<false>,
+            )
+                .chain(),
+        );
     }
 }
 
@@ -329,6 +522,7 @@ pub enum SolverSet {
 /// 6. Solve joints using Extended Position-Based Dynamics (XPBD). ([`SubstepSolverSet::SolveXpbdConstraints`])
 /// 7. Solve user-defined constraints. ([`SubstepSolverSet::SolveUserConstraints`])
 /// 8. Update velocities after XPBD constraint solving. ([`SubstepSolverSet::XpbdVelocityProjection`])
+/// 9. Solve articulations. ([`SubstepSolverSet::SolveArticulations`])
 #[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum SubstepSolverSet {
     /// Warm starts the solver by applying the impulses from the previous frame or substep.
@@ -339,18 +533,54 @@ pub enum SubstepSolverSet {
     WarmStart,
     /// Solves velocity constraints using a position bias that boosts the response
     /// to account for the constraint error.
+    ///
+    /// The constraints are solved by running the [`SolveConstraintsSchedule`]
+    /// [`SolverConfig::velocity_iterations`] times.
     SolveConstraints,
     /// Solves velocity constraints without a position bias to relax the biased velocities
     /// and impulses. This reduces overshooting caused by [warm starting](SubstepSolverSet::WarmStart).
+    ///
+    /// The constraints are solved by running the [`RelaxSchedule`]
+    /// [`SolverConfig::relaxation_iterations`] times.
     Relax,
     /// Solves joints using Extended Position-Based Dynamics (XPBD).
+    ///
+    /// The number of iterations is configured with [`SolverConfig::position_iterations`].
     SolveXpbdConstraints,
     /// A system set for user constraints.
     SolveUserConstraints,
     /// Performs velocity updates after XPBD constraint solving.
+    ///
+    /// [`JointForces`] are also accumulated here, and [breakable joints](BreakableJoint)
+    /// that exceed their force or torque thresholds are broken.
     XpbdVelocityProjection,
+    /// Simulates [articulations](articulations) in reduced coordinates and writes
+    /// the resulting poses and velocities to the links.
+    SolveArticulations,
 }
 
+/// The schedule that solves contacts and joints using a position bias, run in [`SubstepSolverSet::SolveConstraints`].
+///
+/// The schedule is run [`SolverConfig::velocity_iterations`] times per substep, so each iteration
+/// solves all contacts and joints before the next one starts.
+///
+/// Systems that should run once per iteration, like custom velocity constraints, should be added
+/// to this schedule instead of [`SubstepSolverSet::SolveConstraints`], which only runs once per substep.
+/// The schedule is single-threaded, and its systems are not in any [`SubstepSolverSet`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, ScheduleLabel)]
+pub struct SolveConstraintsSchedule;
+
+/// The schedule that relaxes the velocities of contacts and joints, run in [`SubstepSolverSet::Relax`].
+///
+/// The schedule is run [`SolverConfig::relaxation_iterations`] times per substep, so each iteration
+/// solves all contacts and joints before the next one starts.
+///
+/// Systems that should run once per relaxation iteration should be added to this schedule
+/// instead of [`SubstepSolverSet::Relax`], which only runs once per substep.
+/// The schedule is single-threaded, and its systems are not in any [`SubstepSolverSet`].
+#[derive(Debug, Hash, PartialEq, Eq, Clone, ScheduleLabel)]
+pub struct RelaxSchedule;
+
 /// Configuration parameters for the constraint solver that handles
 /// things like contacts and joints.
 ///
@@ -431,6 +661,56 @@ pub struct SolverConfig {
     ///
     /// Default: `1`
     pub restitution_iterations: usize,
+
+    /// The number of iterations used for solving contacts and joints with a position bias
+    /// in [`SubstepSolverSet::SolveConstraints`] during each substep.
+    ///
+    /// More iterations make the solver converge better, which improves the stiffness of joint chains
+    /// and the stability of stacks. Unlike increasing the [`SubstepCount`], this does not re-run
+    /// integration and the other parts of the substepping loop, so it is cheaper, but less effective.
+    ///
+    /// Default: `1`
+    pub velocity_iterations: usize,
+
+    /// The number of iterations used for [relaxing](SubstepSolverSet::Relax) the biased velocities
+    /// of contacts and joints during each substep.
+    ///
+    /// More iterations reduce overshooting caused by the position bias and [warm starting](SubstepSolverSet::WarmStart),
+    /// which can help reduce jitter.
+    ///
+    /// Default: `1`
+    pub relaxation_iterations: usize,
+
+    /// The number of iterations used for correcting position errors of [joints](joints)
+    /// solved with [`JointSolver::Xpbd`] in [`SubstepSolverSet::SolveXpbdConstraints`] during each substep.
+    ///
+    /// This also applies to [user constraints](xpbd#user-constraints) that are solved
+    /// with [`xpbd::solve_constraint`].
+    ///
+    /// Default: `1`
+    pub position_iterations: usize,
+
+    /// If `true`, the normal impulses of all points in a contact manifold are solved simultaneously
+    /// using a block solver, instead of solving the points one by one.
+    ///
+    /// This makes bodies resting on each other with multiple contact points more stable,
+    /// which can make stacks stable with fewer substeps. The block solver supports manifolds with up to
+    /// [`MAX_BLOCK_POINTS`](contact::MAX_BLOCK_POINTS) points: two in 2D, and three in 3D.
+    /// Other manifolds, and manifolds for which no solution is found, are solved sequentially.
+    ///
+    /// In 3D, the four points between a box resting on another box are redundant,
+    /// so they are still solved sequentially. See [`MAX_BLOCK_POINTS`](contact::MAX_BLOCK_POINTS) for details.
+    ///
+    /// Default: `false`
+    pub use_block_solver: bool,
+
+    /// The solver used for [joints](joints).
+    ///
+    /// By default, joints are solved with the same impulse-based solver as contacts,
+    /// which makes them interact consistently with contacts. See [`JointSolver`] for more details.
+    ///
+    /// Default: [`JointSolver::Impulse`]
+    pub joint_solver: JointSolver,
 }
 
 impl Default for SolverConfig {
@@ -442,10 +722,39 @@ impl Default for SolverConfig {
             warm_start_coefficient: 1.0,
             restitution_threshold: 1.0,
             restitution_iterations: 1,
+            velocity_iterations: 1,
+            relaxation_iterations: 1,
+            position_iterations: 1,
+            use_block_solver: false,
+            joint_solver: JointSolver::Impulse,
         }
     }
 }
 
+/// The solver used for [joints](joints), configured with [`SolverConfig::joint_solver`].
+#[derive(Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
+#[reflect(Debug, Default, PartialEq)]
+pub enum JointSolver {
+    /// Joints are solved with the same impulse-based soft step solver as contacts.
+    ///
+    /// The [`JointRow`]s of each joint are [warm started](SubstepSolverSet::WarmStart),
+    /// [solved with a position bias](SubstepSolverSet::SolveConstraints), and
+    /// [relaxed](SubstepSolverSet::Relax) together with contacts. Rigid rows use
+    /// the same [`ContactSoftnessCoefficients`] as contacts.
+    #[default]
+    Impulse,
+    /// Joints are solved using [Extended Position-Based Dynamics (XPBD)](xpbd)
+    /// after the contact solver has finished.
+    Xpbd,
+}
+
+/// Returns a run condition that is `true` if the [`SolverConfig`] uses the given [`JointSolver`].
+fn uses_joint_solver(solver: JointSolver) -> impl Fn(Res<SolverConfig>) -> bool + Clone {
+    move |config: Res<SolverConfig>| config.joint_solver == solver
+}
+
 /// The [`SoftnessCoefficients`] used for contacts.
 ///
 /// **Note**: This resource is updated automatically and not intended to be modified manually.
@@ -504,70 +813,90 @@ pub struct ContactConstraints(pub Vec<ContactConstraint>);
 fn warm_start(
     mut bodies: Query<RigidBodyQuery>,
     mut constraints: ResMut<ContactConstraints>,
+    coloring: Res<ContactGraphColoring>,
     solver_config: Res<SolverConfig>,
 ) {
-    for constraint in constraints.iter_mut() {
-        debug_assert!(!constraint.points.is_empty());
-
-        let Ok([mut body1, mut body2]) =
-            bodies.get_many_mut([constraint.entity1, constraint.entity2])
-        else {
-            continue;
-        };
-
-        let normal = constraint.normal;
-        let tangent_directions =
-            constraint.tangent_directions(body1.linear_velocity.0, body2.linear_velocity.0);
-
-        constraint.warm_start(
-            &mut body1,
-            &mut body2,
-            normal,
-            tangent_directions,
-            solver_config.warm_start_coefficient,
-        );
-    }
+    let warm_start_coefficient = solver_config.warm_start_coefficient;
+
+    graph_coloring::solve_colored_contacts(
+        &mut constraints.0,
+        &coloring,
+        &mut bodies,
+        |constraint, body1, body2| {
+            debug_assert!(!constraint.points.is_empty());
+
+            let normal = constraint.normal;
+            let tangent_directions = constraint.tangent_directions();
+
+            constraint.warm_start(
+                body1,
+                body2,
+                normal,
+                tangent_directions,
+                warm_start_coefficient,
+            );
+        },
+    );
 }
 
-/// Solves contacts by iterating through the given contact constraints
+/// Solves contacts by iterating through the contact constraints
 /// and applying impulses to colliding rigid bodies.
 ///
-/// This solve is done `iterations` times. With a substepped solver,
-/// `iterations` should typically be `1`, as substeps will handle the iteration.
+/// The constraints are solved one color of the [`ContactGraphColoring`] at a time.
 ///
-/// If `use_bias` is `true`, the impulses will be boosted to account for overlap.
+/// If `USE_BIAS` is `true`, the impulses will be boosted to account for overlap.
 /// The solver should often be run twice per frame or substep: first with the bias,
 /// and then without it to *relax* the velocities and reduce overshooting caused by
 /// [warm starting](SubstepSolverSet::WarmStart).
 ///
+/// This is run once per iteration in the [`SolveConstraintsSchedule`] and [`RelaxSchedule`].
 /// See [`SubstepSolverSet::SolveConstraints`] and [`SubstepSolverSet::Relax`] for more information.
-#[allow(clippy::too_many_arguments)]
-#[allow(clippy::type_complexity)]
-fn solve_contacts(
-    bodies: &mut Query<RigidBodyQuery>,
-    constraints: &mut [ContactConstraint],
-    delta_secs: Scalar,
-    iterations: usize,
-    use_bias: bool,
-    max_overlap_solve_speed: Scalar,
+fn solve_contacts_system<const USE_BIAS: bool>(
+    mut bodies: Query<RigidBodyQuery>,
+    mut constraints: ResMut<ContactConstraints>,
+    coloring: Res<ContactGraphColoring>,
+    solver_config: Res<SolverConfig>,
+    length_unit: Res<PhysicsLengthUnit>,
+    time: Res<Time>,
 ) {
-    for _ in 0..iterations {
-        for constraint in &mut *constraints {
-            let Ok([mut body1, mut body2]) =
-                bodies.get_many_mut([constraint.entity1, constraint.entity2])
-            else {
-                continue;
-            };
+    let delta_secs = time.delta_seconds_adjusted();
+    let max_overlap_solve_speed = solver_config.max_overlap_solve_speed * length_unit.0;
 
+    graph_coloring::solve_colored_contacts(
+        &mut constraints.0,
+        &coloring,
+        &mut bodies,
+        |constraint, body1, body2| {
             constraint.solve(
-                &mut body1,
-                &mut body2,
+                body1,
+                body2,
                 delta_secs,
-                use_bias,
+                USE_BIAS,
                 max_overlap_solve_speed,
+                solver_config.use_block_solver,
             );
+        },
+    );
+}
+
+/// Runs the [`SolveConstraintsSchedule`] [`SolverConfig::velocity_iterations`] times.
+fn run_solve_constraints_schedule(world: &mut World) {
+    let iterations = world.resource::<SolverConfig>().velocity_iterations;
+    let _ = world.try_schedule_scope(SolveConstraintsSchedule, |world, schedule| {
+        for _ in 0..iterations {
+            schedule.run(world);
         }
-    }
+    });
+}
+
+/// Runs the [`RelaxSchedule`] [`SolverConfig::relaxation_iterations`] times.
+fn run_relax_schedule(world: &mut World) {
+    let iterations = world.resource::<SolverConfig>().relaxation_iterations;
+    let _ = world.try_schedule_scope(RelaxSchedule, |world, schedule| {
+        for _ in 0..iterations {
+            schedule.run(world);
+        }
+    });
 }
 
 /// Iterates through contact constraints and applies impulses to account for [`Restitution`].
@@ -582,38 +911,39 @@ fn solve_contacts(
 fn solve_restitution(
     mut bodies: Query<RigidBodyQuery>,
     mut constraints: ResMut<ContactConstraints>,
+    coloring: Res<ContactGraphColoring>,
     solver_config: Res<SolverConfig>,
     length_unit: Res<PhysicsLengthUnit>,
 ) {
     // TODO: This could be configurable.
     // The restitution threshold determining the speed required for restitution to be applied.
     let threshold = solver_config.restitution_threshold * length_unit.0;
+    let iterations = solver_config.restitution_iterations;
 
-    for constraint in constraints.iter_mut() {
-        let restitution = constraint.restitution.coefficient;
+    graph_coloring::solve_colored_contacts(
+        &mut constraints.0,
+        &coloring,
+        &mut bodies,
+        |constraint, body1, body2| {
+            let restitution = constraint.restitution.coefficient;
 
-        if restitution == 0.0 {
-            continue;
-        }
-
-        let Ok([mut body1, mut body2]) =
-            bodies.get_many_mut([constraint.entity1, constraint.entity2])
-        else {
-            continue;
-        };
+            if restitution == 0.0 {
+                return;
+            }
 
-        // Performing multiple iterations can result in more accurate restitution,
-        // but only if there are more than one contact point.
-        let restitution_iterations = if constraint.points.len() > 1 {
-            solver_config.restitution_iterations
-        } else {
-            1
-        };
+            // Performing multiple iterations can result in more accurate restitution,
+            // but only if there are more than one contact point.
+            let restitution_iterations = if constraint.points.len() > 1 {
+                iterations
+            } else {
+                1
+            };
 
-        for _ in 0..restitution_iterations {
-            constraint.apply_restitution(&mut body1, &mut body2, threshold);
-        }
-    }
+            for _ in 0..restitution_iterations {
+                constraint.apply_restitution(body1, body2, threshold);
+            }
+        },
+    );
 }
 
 /// Copies contact impulses from [`ContactConstraints`] to the contacts in [`Collisions`].
@@ -631,9 +961,8 @@ fn store_contact_impulses(
 
         let manifold = &mut contacts.manifolds[constraint.manifold_index];
 
-        for (contact, constraint_point) in
-            manifold.contacts.iter_mut().zip(constraint.points.iter())
-        {
+        for constraint_point in constraint.points.iter() {
+            let contact = &mut manifold.contacts[constraint_point.contact_index];
             contact.normal_impulse = constraint_point.normal_part.impulse;
             contact.tangent_impulse = constraint_point
                 .tangent_part
@@ -670,6 +999,222 @@ fn apply_translation(
     }
 }
 
+/// Resets the [`JointForces`] accumulated during the previous physics step.
+fn clear_joint_forces(mut query: Query<&mut JointForces>) {
+    for mut forces in &mut query {
+        *forces = JointForces::default();
+    }
+}
+
+/// Adds the force and torque exerted by joints during the current substep to their [`JointForces`].
+pub fn accumulate_joint_forces<T: Joint>(
+    mut joints: Query<(&T, &mut JointForces), Without<RigidBody>>,
+) {
+    for (joint, mut forces) in &mut joints {
+        forces.force += joint.force();
+        forces.torque += joint.torque();
+    }
+}
+
+/// Removes joints whose force or torque exceeds the thresholds of their [`BreakableJoint`] component,
+/// and sends a [`JointBroken`] event for each broken joint.
+pub fn break_joints<T: Joint>(
+    mut commands: Commands,
+    joints: Query<(Entity, &T, &BreakableJoint), Without<RigidBody>>,
+    mut broken_joints: EventWriter<JointBroken>,
+) {
+    for (entity, joint, thresholds) in &joints {
+        let force = joint.force();
+        let torque = joint.torque();
+
+        if thresholds.is_exceeded(force, torque) {
+            let [entity1, entity2] = joint.entities();
+
+            commands.entity(entity).remove::<T>();
+
+            broken_joints.send(JointBroken {
+                joint: entity,
+                entity1,
+                entity2,
+                force,
+                torque,
+            });
+        }
+    }
+}
+
+/// Updates the [`JointCollisionPairs`] for joints of type `T` that were added, changed or removed.
+///
+/// The solver writes to joints every substep, so a changed joint is only updated
+/// if its bodies or [`Joint::collisions_disabled`] actually changed.
+pub fn update_joint_collision_pairs<T: Joint>(
+    joints: Query<(Entity, &T), Changed<T>>,
+    mut removed_joints: RemovedComponents<T>,
+    mut collision_pairs: ResMut<JointCollisionPairs>,
+) {
+    for entity in removed_joints.read() {
+        collision_pairs.remove(entity);
+    }
+
+    for (entity, joint) in &joints {
+        let [entity1, entity2] = joint.entities();
+        if joint.collisions_disabled() {
+            if !collision_pairs.contains_joint(entity, entity1, entity2) {
+                collision_pairs.insert(entity, entity1, entity2);
+            }
+        } else if collision_pairs.has_joint(entity) {
+            collision_pairs.remove(entity);
+        }
+    }
+}
+
+/// Updates the [`JointGraph`] for joints of type `T` that were added, changed or removed.
+///
+/// The solver writes to joints every substep, so a changed joint is only updated
+/// if its bodies actually changed.
+pub fn update_joint_graph<T: Joint>(
+    joints: Query<(Entity, &T), Changed<T>>,
+    mut removed_joints: RemovedComponents<T>,
+    mut joint_graph: ResMut<JointGraph>,
+) {
+    for entity in removed_joints.read() {
+        joint_graph.remove(entity);
+    }
+
+    for (entity, joint) in &joints {
+        let [entity1, entity2] = joint.entities();
+        if joint_graph.bodies(entity) != Some((entity1, entity2)) {
+            joint_graph.insert(entity, entity1, entity2);
+        }
+    }
+}
+
+/// Updates which bodies in the [`JointGraph`] are dynamic, so that connected components
+/// are not traversed through static and kinematic bodies.
+fn update_joint_graph_body_types(bodies: Query<&RigidBody>, mut joint_graph: ResMut<JointGraph>) {
+    joint_graph.update_body_types(|body| bodies.get(body).is_ok_and(RigidBody::is_dynamic));
+}
+
+/// Returns `true` if a joint between the given bodies should be solved.
+///
+/// Joints are not solved if neither of the bodies is dynamic, or if both of them are static or sleeping.
+fn is_joint_active(body1: &RigidBodyQueryItem, body2: &RigidBodyQueryItem) -> bool {
+    let none_dynamic = !body1.rb.is_dynamic() && !body2.rb.is_dynamic();
+    let all_inactive =
+        (body1.rb.is_static() || body1.is_sleeping) && (body2.rb.is_static() || body2.is_sleeping);
+    !(none_dynamic || all_inactive)
+}
+
+/// Warm starts joints of type `T` by applying the impulses accumulated for their [`JointRow`]s
+/// during the previous substep.
+///
+/// Only used with [`JointSolver::Impulse`].
+pub fn warm_start_joints<T: ImpulseJoint>(
+    mut bodies: Query<RigidBodyQuery>,
+    mut joints: Query<&mut T, Without<RigidBody>>,
+    coloring: Res<JointGraphColoring<T>>,
+    solver_config: Res<SolverConfig>,
+    time: Res<Time>,
+    mut rows: Local<Vec<JointRow>>,
+) {
+    let delta_secs = time.delta_seconds_adjusted();
+    let warm_start_coefficient = solver_config.warm_start_coefficient;
+
+    graph_coloring::solve_colored_joints(
+        &coloring,
+        &mut joints,
+        &mut bodies,
+        &mut rows,
+        |joint, body1, body2, rows| {
+            if !is_joint_active(body1, body2) {
+                return;
+            }
+
+            rows.clear();
+            joint.compute_rows(body1, body2, delta_secs, rows);
+
+            // Reset the impulses of rows that are no longer active, like limits that are not violated.
+            let impulses = joint.impulses_mut();
+            impulses.retain_rows(rows);
+            let impulses = *impulses;
+
+            for row in rows.iter() {
+                let impulse = warm_start_coefficient * impulses.get(row.slot);
+                row.apply_impulse(body1, body2, impulse);
+            }
+        },
+    );
+}
+
+/// Solves joints of type `T` by solving the velocity constraints of their [`JointRow`]s.
+///
+/// If `USE_BIAS` is `true`, the impulses are boosted to correct position errors, and sleeping bodies
+/// are woken up when active bodies interact with them through a joint. Otherwise, the biased velocities
+/// are relaxed, and the force and torque of the joint are updated.
+///
+/// This is run once per iteration in the [`SolveConstraintsSchedule`] and [`RelaxSchedule`].
+///
+/// Only used with [`JointSolver::Impulse`].
+#[allow(clippy::too_many_arguments)]
+pub fn solve_joints<T: ImpulseJoint, const USE_BIAS: bool>(
+    commands: ParallelCommands,
+    mut bodies: Query<RigidBodyQuery>,
+    mut joints: Query<&mut T, Without<RigidBody>>,
+    coloring: Res<JointGraphColoring<T>>,
+    contact_softness: Res<ContactSoftnessCoefficients>,
+    time: Res<Time>,
+    mut rows: Local<Vec<JointRow>>,
+) {
+    let delta_secs = time.delta_seconds_adjusted();
+
+    graph_coloring::solve_colored_joints(
+        &coloring,
+        &mut joints,
+        &mut bodies,
+        &mut rows,
+        |joint, body1, body2, rows| {
+            if !is_joint_active(body1, body2) {
+                return;
+            }
+
+            // At least one of the participating bodies is active, so wake up any sleeping bodies
+            if USE_BIAS {
+                for body in [&mut *body1, &mut *body2] {
+                    if body.is_sleeping {
+                        body.time_sleeping.0 = 0.0;
+                        commands.command_scope(|mut commands| {
+                            commands.entity(body.entity).remove::<Sleeping>();
+                        });
+                    }
+                }
+            }
+
+            rows.clear();
+            joint.compute_rows(body1, body2, delta_secs, rows);
+
+            let rigid_coefficients = joints::rigid_coefficients(&contact_softness, body1, body2);
+            let impulses = joint.impulses_mut();
+            impulses.retain_rows(rows);
+
+            for row in rows.iter() {
+                row.solve(
+                    &mut impulses.0[row.slot],
+                    body1,
+                    body2,
+                    rigid_coefficients,
+                    USE_BIAS,
+                    delta_secs,
+                );
+            }
+
+            if !USE_BIAS {
+                let (force, torque) = joint.compute_force_and_torque(rows, delta_secs);
+                joint.set_force_and_torque(force, torque);
+            }
+        },
+    );
+}
+
 /// Applies velocity corrections caused by joint damping.
 #[allow(clippy::type_complexity)]
 pub fn joint_damping<T: Joint>(
@@ -684,6 +1229,7 @@ pub fn joint_damping<T: Joint>(
         Without<Sleeping>,
     >,
     joints: Query<&T, Without<RigidBody>>,
+    solver_config: Res<SolverConfig>,
     time: Res<Time>,
 ) {
     let delta_secs = time.delta_seconds_adjusted();
@@ -693,8 +1239,33 @@ pub fn joint_damping<T: Joint>(
             [(rb1, mut lin_vel1, mut ang_vel1, inv_mass1, dominance1), (rb2, mut lin_vel2, mut ang_vel2, inv_mass2, dominance2)],
         ) = bodies.get_many_mut(joint.entities())
         {
-            let delta_omega =
+            let mut delta_omega =
                 (ang_vel2.0 - ang_vel1.0) * (joint.damping_angular() * delta_secs).min(1.0);
+            let mut delta_v =
+                (lin_vel2.0 - lin_vel1.0) * (joint.damping_linear() * delta_secs).min(1.0);
+
+            // With XPBD, soft joints are damped along the directions of the joint force and torque
+            // using an implicit damper with a damping coefficient of `2 * zeta * omega`.
+            // The impulse-based solver handles softness damping in the joint rows instead.
+            if let (Some(softness), JointSolver::Xpbd) =
+                (joint.softness(), solver_config.joint_solver)
+            {
+                let damping = 2.0 * softness.damping_ratio() * softness.angular_frequency();
+                let factor = damping * delta_secs / (1.0 + damping * delta_secs);
+
+                let force_dir = joint.force().normalize_or_zero();
+                delta_v += (lin_vel2.0 - lin_vel1.0).dot(force_dir) * factor * force_dir;
+
+                #[cfg(feature = "2d")]
+                if joint.torque().abs() > Scalar::EPSILON {
+                    delta_omega += (ang_vel2.0 - ang_vel1.0) * factor;
+                }
+                #[cfg(feature = "3d")]
+                {
+                    let torque_dir = joint.torque().normalize_or_zero();
+                    delta_omega += (ang_vel2.0 - ang_vel1.0).dot(torque_dir) * factor * torque_dir;
+                }
+            }
 
             if rb1.is_dynamic() {
                 ang_vel1.0 += delta_omega;
@@ -703,9 +1274,6 @@ pub fn joint_damping<T: Joint>(
                 ang_vel2.0 -= delta_omega;
             }
 
-            let delta_v =
-                (lin_vel2.0 - lin_vel1.0) * (joint.damping_linear() * delta_secs).min(1.0);
-
             let w1 = if rb1.is_dynamic() { inv_mass1.0 } else { 0.0 };
             let w2 = if rb2.is_dynamic() { inv_mass2.0 } else { 0.0 };
 
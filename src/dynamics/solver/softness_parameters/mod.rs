@@ -7,12 +7,16 @@
 #![doc = include_str!("README.md")]
 
 use bevy::reflect::Reflect;
+#[cfg(feature = "serialize")]
+use bevy::reflect::{ReflectDeserialize, ReflectSerialize};
 
 use crate::{Scalar, TAU};
 
 /// Soft constraint tuning parameters used for dampening
 /// constraint response and controlling stiffness.
 #[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serialize", reflect(Serialize, Deserialize))]
 pub struct SoftnessParameters {
     /// 2x the damping ratio (zeta ζ). Controls the amount of oscillation.
     ///
@@ -75,6 +79,17 @@ impl SoftnessParameters {
             mass_scale: a2 * a3,
         }
     }
+
+    /// Computes the XPBD compliance that matches the frequency in `self` for a constraint
+    /// with the given generalized inverse mass.
+    ///
+    /// The compliance corresponds to a spring with a stiffness of `m * omega^2`, where `m` is
+    /// the effective mass of the constraint. This keeps the frequency independent of the masses of the bodies.
+    /// The damping ratio is not taken into account, as XPBD constraints are damped separately.
+    #[inline]
+    pub fn compute_compliance(self, inverse_mass: Scalar) -> Scalar {
+        inverse_mass / self.angular_frequency.powi(2)
+    }
 }
 
 /// Coefficients used by soft constraints.
@@ -1,8 +1,10 @@
 //! Extended Position-Based Dynamics (XPBD) constraint functionality.
 //!
 //! XPBD is a simulation method that solves constraints at the position-level.
-//! Avian currently uses it for [joints](dynamics::solver::joints),
-//! while contacts use an impulse-based approach.
+//! Avian can optionally use it for [joints](dynamics::solver::joints) by setting
+//! [`SolverConfig::joint_solver`](dynamics::solver::SolverConfig::joint_solver) to
+//! [`JointSolver::Xpbd`](dynamics::solver::JointSolver::Xpbd), while contacts
+//! and joints use an impulse-based approach by default.
 //!
 //! This module contains traits and systems for XPBD functionality.
 //! The actual joint implementations are in [`dynamics::solver::joints`],
@@ -32,6 +34,11 @@
 #![cfg_attr(feature = "3d", doc = "    - [`SphericalJoint`]")]
 //!     - [`RevoluteJoint`]
 //!     - [`PrismaticJoint`]
+//!     - [`GenericJoint`]
+//!     - [`PulleyJoint`]
+//!     - [`WheelJoint`]
+//!     - [`GearJoint`]
+//!     - [`TargetJoint`]
 //!
 //! Avian's [`ContactConstraint`](dynamics::solver::contact::ContactConstraint)
 //! is impulse-based instead.
@@ -162,6 +169,9 @@
 //! `α` is the constraint's compliance (inverse of stiffness) and `h` is the substep size. Using `α = 0`
 //! corresponds to infinite stiffness.
 //!
+//! Constraints can also be configured with [`SoftnessParameters`] using [`XpbdConstraint::softness`],
+//! in which case the compliance is computed from the frequency and the generalized inverse masses.
+//!
 //! The minus sign is there because the gradients point in the direction in which `C` increases the most,
 //! and we instead want to minimize `C`.
 //!
@@ -225,6 +235,7 @@ mod positional_constraint;
 pub use angular_constraint::AngularConstraint;
 pub use positional_constraint::PositionConstraint;
 
+use super::SolverConfig;
 use crate::prelude::*;
 use bevy::{ecs::entity::MapEntities, prelude::*};
 
@@ -233,6 +244,15 @@ pub trait XpbdConstraint<const ENTITY_COUNT: usize>: MapEntities {
     /// The entities participating in the constraint.
     fn entities(&self) -> [Entity; ENTITY_COUNT];
 
+    /// Returns the [`SoftnessParameters`] of the constraint, if any.
+    ///
+    /// When softness is used, it overrides the `compliance` passed to
+    /// [`compute_lagrange_update`](XpbdConstraint::compute_lagrange_update)
+    /// and [`compute_lagrange_update_with_gradients`](XpbdConstraint::compute_lagrange_update_with_gradients).
+    fn softness(&self) -> Option<SoftnessParameters> {
+        None
+    }
+
     /// Solves the constraint.
     ///
     /// There are two main steps to solving a constraint:
@@ -283,6 +303,10 @@ pub trait XpbdConstraint<const ENTITY_COUNT: usize>: MapEntities {
             return 0.0;
         }
 
+        let compliance = self
+            .softness()
+            .map_or(compliance, |softness| softness.compute_compliance(w_sum));
+
         // tilde_a = a/h^2
         let tilde_compliance = compliance / dt.powi(2);
 
@@ -313,6 +337,10 @@ pub trait XpbdConstraint<const ENTITY_COUNT: usize>: MapEntities {
             return 0.0;
         }
 
+        let compliance = self
+            .softness()
+            .map_or(compliance, |softness| softness.compute_compliance(w_sum));
+
         // tilde_a = a/h^2
         let tilde_compliance = compliance / dt.powi(2);
 
@@ -327,6 +355,8 @@ pub trait XpbdConstraint<const ENTITY_COUNT: usize>: MapEntities {
 /// Iterates through the XPBD constraints of a given type and solves them. Sleeping bodies are woken up when
 /// active bodies interact with them in a constraint.
 ///
+/// The constraints are solved [`SolverConfig::position_iterations`] times.
+///
 /// Note that this system only works for constraints that are modeled as entities.
 /// If you store constraints in a resource, you must create your own system for solving them.
 ///
@@ -352,6 +382,7 @@ pub fn solve_constraint<C: XpbdConstraint<ENTITY_COUNT> + Component, const ENTIT
     mut commands: Commands,
     mut bodies: Query<RigidBodyQuery>,
     mut constraints: Query<&mut C, Without<RigidBody>>,
+    solver_config: Res<SolverConfig>,
     time: Res<Time>,
 ) {
     let delta_secs = time.delta_seconds_adjusted();
@@ -361,36 +392,37 @@ pub fn solve_constraint<C: XpbdConstraint<ENTITY_COUNT> + Component, const ENTIT
         .iter_mut()
         .for_each(|mut c| c.clear_lagrange_multipliers());
 
-    for mut constraint in &mut constraints {
-        // Get components for entities
-        if let Ok(mut bodies) = bodies.get_many_mut(constraint.entities()) {
-            let none_dynamic = bodies.iter().all(|body| !body.rb.is_dynamic());
-            let all_inactive = bodies
-                .iter()
-                .all(|body| body.rb.is_static() || body.is_sleeping);
-
-            // No constraint solving if none of the bodies is dynamic,
-            // or if all of the bodies are either static or sleeping
-            if none_dynamic || all_inactive {
-                continue;
-            }
+    for _ in 0..solver_config.position_iterations {
+        for mut constraint in &mut constraints {
+            // Get components for entities
+            if let Ok(mut bodies) = bodies.get_many_mut(constraint.entities()) {
+                let none_dynamic = bodies.iter().all(|body| !body.rb.is_dynamic());
+                let all_inactive = bodies
+                    .iter()
+                    .all(|body| body.rb.is_static() || body.is_sleeping);
 
-            // At least one of the participating bodies is active, so wake up any sleeping bodies
-            for body in &mut bodies {
-                body.time_sleeping.0 = 0.0;
+                // No constraint solving if none of the bodies is dynamic,
+                // or if all of the bodies are either static or sleeping
+                if none_dynamic || all_inactive {
+                    continue;
+                }
 
-                if body.is_sleeping {
-                    commands.entity(body.entity).remove::<Sleeping>();
+                // At least one of the participating bodies is active, so wake up any sleeping bodies
+                for body in &mut bodies {
+                    if body.is_sleeping {
+                        body.time_sleeping.0 = 0.0;
+                        commands.entity(body.entity).remove::<Sleeping>();
+                    }
                 }
-            }
 
-            // Get the bodies as an array and solve the constraint
-            if let Ok(bodies) = bodies
-                .iter_mut()
-                .collect::<Vec<&mut RigidBodyQueryItem>>()
-                .try_into()
-            {
-                constraint.solve(bodies, delta_secs);
+                // Get the bodies as an array and solve the constraint
+                if let Ok(bodies) = bodies
+                    .iter_mut()
+                    .collect::<Vec<&mut RigidBodyQueryItem>>()
+                    .try_into()
+                {
+                    constraint.solve(bodies, delta_secs);
+                }
             }
         }
     }
@@ -140,6 +140,7 @@
 //!     - [Creation](Collider#creation)
 //!     - [Density](ColliderDensity)
 //!     - [Friction] and [restitution](Restitution) (bounciness)
+//!     - [Physics materials](PhysicsMaterial) loaded from asset files
 //!     - [Collision layers](CollisionLayers)
 //!     - [Sensors](Sensor)
 #![cfg_attr(
@@ -161,9 +162,15 @@
 //!     - [Prismatic joint](PrismaticJoint)
 //!     - [Revolute joint](RevoluteJoint)
 #![cfg_attr(feature = "3d", doc = "    - [Spherical joint](SphericalJoint)")]
+//!     - [Generic joint](GenericJoint)
+//!     - [Pulley joint](PulleyJoint)
+//!     - [Wheel joint](WheelJoint)
+//!     - [Gear joint](GearJoint)
+//!     - [Target joint](TargetJoint)
 //! - [Custom XPBD constraints](dynamics::solver::xpbd#constraints) (advanced)
 //!
-//! Joint motors and articulations are not supported yet, but they will be implemented in a future release.
+//! [Revolute](RevoluteJoint) and [prismatic](PrismaticJoint) joints can be driven by [joint motors](JointMotor).
+//! Trees of bodies like robotic arms can also be simulated in reduced coordinates using [articulations](dynamics::solver::articulations).
 //!
 //! ### Spatial queries
 //!
@@ -190,6 +197,7 @@
 //!     - [`PhysicsSchedule`] and [`PhysicsStepSet`]
 //!     - [`SubstepSchedule`]
 //!     - [`SolverSet`] and [`SubstepSolverSet`](dynamics::solver::SubstepSolverSet)
+//!     - [`SolveConstraintsSchedule`](dynamics::solver::SolveConstraintsSchedule) and [`RelaxSchedule`](dynamics::solver::RelaxSchedule)
 //!     - [`PostProcessCollisions`] schedule
 //!     - [`PrepareSet`](prepare::PrepareSet)
 //!     - Many more internal system sets
@@ -477,8 +485,10 @@ pub mod prelude {
             self,
             broad_phase::{BroadCollisionPairs, BroadPhasePlugin},
             collider::{ColliderBackendPlugin, ColliderHierarchyPlugin},
+            contact_modification::{ContactModificationHooks, ContactModificationPlugin},
             contact_reporting::{
-                Collision, CollisionEnded, CollisionStarted, ContactReportingPlugin,
+                Collision, CollisionEnded, CollisionStarted, ContactForceEvent,
+                ContactForceEventThreshold, ContactReportingPlugin,
             },
             narrow_phase::{NarrowPhaseConfig, NarrowPhasePlugin},
             *,
@@ -521,6 +531,7 @@ use prelude::*;
 /// | [`PhysicsSchedulePlugin`]         | Sets up the physics engine by initializing the necessary schedules, sets and resources.                                                                    |
 /// | [`PhysicsTypeRegistrationPlugin`] | Registers physics types to the `TypeRegistry` resource in `bevy_reflect`.                                                                                  |
 /// | [`PreparePlugin`]                 | Runs systems at the start of each physics frame. Initializes [rigid bodies](RigidBody) and updates components.                                             |
+/// | [`PhysicsMaterialPlugin`]         | Handles [`PhysicsMaterial`] assets and keeps the friction, restitution and density of entities in sync with their materials.                               |
 /// | [`ColliderBackendPlugin`]         | Handles generic collider backend logic, like initializing colliders and AABBs and updating related components.                                             |
 /// | [`ColliderHierarchyPlugin`]       | Handles transform propagation and [`ColliderParent`] updates for colliders.                                                                                |
 /// | [`BroadPhasePlugin`]              | Collects pairs of potentially colliding entities into [`BroadCollisionPairs`] using [AABB](ColliderAabb) intersection checks.                              |
@@ -720,6 +731,7 @@ impl PluginGroup for PhysicsPlugins {
             .add(PhysicsSchedulePlugin::new(self.schedule))
             .add(PhysicsTypeRegistrationPlugin)
             .add(PreparePlugin::new(self.schedule))
+            .add(PhysicsMaterialPlugin::new(self.schedule))
             .add(ColliderHierarchyPlugin::new(self.schedule));
 
         #[cfg(all(
@@ -21,6 +21,13 @@ pub const DIM: usize = 2;
 #[cfg(feature = "3d")]
 pub const DIM: usize = 3;
 
+/// The number of rotational degrees of freedom.
+#[cfg(feature = "2d")]
+pub const ANGULAR_DIM: usize = 1;
+/// The number of rotational degrees of freedom.
+#[cfg(feature = "3d")]
+pub const ANGULAR_DIM: usize = 3;
+
 /// The ray type chosen based on the dimension.
 #[cfg(feature = "2d")]
 pub(crate) type Ray = Ray2d;
@@ -189,6 +189,2423 @@ fn body_with_velocity_moves() {
     }
 }
 
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn revolute_joint_motor_reaches_target_velocity() {
+    let mut app = create_app();
+
+    app.insert_resource(Gravity::ZERO);
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let anchor = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let wheel = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                #[cfg(feature = "2d")]
+                MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                #[cfg(feature = "3d")]
+                MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+            ))
+            .id();
+        commands.spawn(
+            RevoluteJoint::new(anchor, wheel)
+                .with_angular_velocity_damping(0.0)
+                .with_motor(JointMotor::new_velocity(2.0, 1000.0)),
+        );
+    });
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut app_query = app.world_mut().query::<(&AngularVelocity, &RigidBody)>();
+
+    let (ang_vel, _) = app_query
+        .iter(app.world())
+        .find(|(_, rb)| rb.is_dynamic())
+        .unwrap();
+
+    #[cfg(feature = "2d")]
+    assert_relative_eq!(ang_vel.0, 2.0, epsilon = 0.05);
+    #[cfg(feature = "3d")]
+    assert_relative_eq!(ang_vel.z, 2.0, epsilon = 0.05);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn breakable_joint_breaks_when_overloaded() {
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let anchor = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let body = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Position(Vector::NEG_Y),
+                #[cfg(feature = "2d")]
+                MassPropertiesBundle::new_computed(&Collider::circle(0.5), 10.0),
+                #[cfg(feature = "3d")]
+                MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 10.0),
+            ))
+            .id();
+
+        // The weight of the body is much larger than the break force.
+        commands.spawn((
+            DistanceJoint::new(anchor, body).with_rest_length(1.0),
+            BreakableJoint::default().with_break_force(1.0),
+        ));
+    });
+
+    tick_60_fps(&mut app);
+
+    let events = app.world().resource::<Events<JointBroken>>();
+    assert_eq!(events.len(), 1);
+
+    let mut joints = app.world_mut().query::<&DistanceJoint>();
+    assert_eq!(joints.iter(app.world()).count(), 0);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn joint_forces_match_weight_of_hanging_body() {
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let anchor = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let body = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Position(Vector::NEG_Y),
+                #[cfg(feature = "2d")]
+                MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                #[cfg(feature = "3d")]
+                MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+            ))
+            .id();
+
+        commands.spawn((
+            DistanceJoint::new(anchor, body).with_rest_length(1.0),
+            JointForces::default(),
+        ));
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut bodies = app.world_mut().query::<(&Mass, &RigidBody)>();
+    let (mass, _) = bodies
+        .iter(app.world())
+        .find(|(_, rb)| rb.is_dynamic())
+        .unwrap();
+    let weight = mass.0 * app.world().resource::<Gravity>().0.length();
+
+    let mut joint_forces = app.world_mut().query::<&JointForces>();
+    let forces = joint_forces.single(app.world());
+
+    // The force is summed over the substeps.
+    let substeps = app.world().resource::<SubstepCount>().0 as Scalar;
+
+    assert_relative_eq!(
+        forces.force.length(),
+        weight * substeps,
+        max_relative = 0.05
+    );
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn generic_joint_only_allows_motion_along_free_axes() {
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let anchor = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let body = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                LinearVelocity(Vector::X),
+                #[cfg(feature = "2d")]
+                MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                #[cfg(feature = "3d")]
+                MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+            ))
+            .id();
+
+        // Only allow the body to fall along the Y axis.
+        commands.spawn(GenericJoint::new(anchor, body).with_linear_motion(
+            1,
+            AxisMotion::Limited {
+                min: -1.0,
+                max: 0.0,
+            },
+        ));
+    });
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut bodies = app.world_mut().query::<(&Position, &RigidBody)>();
+    let (position, _) = bodies
+        .iter(app.world())
+        .find(|(_, rb)| rb.is_dynamic())
+        .unwrap();
+
+    assert_relative_eq!(position.x, 0.0, epsilon = 0.01);
+    assert_relative_eq!(position.y, -1.0, epsilon = 0.01);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn pulley_joint_lifts_lighter_body() {
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let mut spawn_body = |position: Vector, density: Scalar| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(position),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.5), density),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.5), density),
+                ))
+                .id()
+        };
+        let light = spawn_body(Vector::NEG_X + Vector::NEG_Y, 1.0);
+        let heavy = spawn_body(Vector::X + Vector::NEG_Y, 2.0);
+
+        commands.spawn(
+            PulleyJoint::new(light, heavy)
+                .with_ground_anchors(Vector::NEG_X, Vector::X)
+                .with_max_lengths(2.0, 1.5),
+        );
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut bodies = app.world_mut().query::<(&Position, &Mass)>();
+    let mut bodies = bodies.iter(app.world()).collect::<Vec<_>>();
+    bodies.sort_by(|(_, a), (_, b)| a.0.total_cmp(&b.0));
+    let [(light_position, _), (heavy_position, _)] = bodies[..] else {
+        panic!("expected two bodies");
+    };
+
+    // The heavier body falls until the maximum length of its side is reached,
+    // pulling the lighter body up.
+    assert_relative_eq!(light_position.distance(Vector::NEG_X), 0.5, epsilon = 0.01);
+    assert_relative_eq!(heavy_position.distance(Vector::X), 1.5, epsilon = 0.01);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn gear_joint_couples_revolute_joints() {
+    let mut app = create_app();
+
+    app.insert_resource(Gravity::ZERO);
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let ground = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let mut spawn_gear = |position: Vector, density: Scalar, angular_velocity: Scalar| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(position),
+                    #[cfg(feature = "2d")]
+                    AngularVelocity(angular_velocity),
+                    #[cfg(feature = "3d")]
+                    AngularVelocity(Vector::Z * angular_velocity),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.5), density),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.5), density),
+                ))
+                .id()
+        };
+        let small_gear = spawn_gear(Vector::NEG_X, 1.0, 1.0);
+        let large_gear = spawn_gear(Vector::X, 2.0, 0.0);
+
+        let joint1 = commands
+            .spawn(RevoluteJoint::new(ground, small_gear).with_local_anchor_1(Vector::NEG_X))
+            .id();
+        let joint2 = commands
+            .spawn(RevoluteJoint::new(ground, large_gear).with_local_anchor_1(Vector::X))
+            .id();
+        commands.spawn(GearJoint::new(joint1, joint2).with_ratio(2.0));
+    });
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut bodies = app.world_mut().query::<(&Rotation, &Mass)>();
+    let mut bodies = bodies
+        .iter(app.world())
+        .filter(|(_, mass)| mass.0 > 0.0)
+        .collect::<Vec<_>>();
+    bodies.sort_by(|(_, a), (_, b)| a.0.total_cmp(&b.0));
+    let [(small_rotation, _), (large_rotation, _)] = bodies[..] else {
+        panic!("expected two bodies");
+    };
+
+    #[cfg(feature = "2d")]
+    let (small_angle, large_angle) = (small_rotation.as_radians(), large_rotation.as_radians());
+    #[cfg(feature = "3d")]
+    let (small_angle, large_angle) = (
+        small_rotation.to_euler(EulerRot::XYZ).2,
+        large_rotation.to_euler(EulerRot::XYZ).2,
+    );
+
+    // The large gear turns at half the speed of the small gear, in the opposite direction.
+    assert!(small_angle > 0.1);
+    assert_relative_eq!(small_angle + 2.0 * large_angle, 0.0, epsilon = 0.01);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn gear_joint_reports_forces_and_breaks_when_overloaded() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct GearJoints {
+        unbreakable: Entity,
+        breakable: Entity,
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let ground = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+
+        // Two bodies that slide along the Y axis, coupled so that one can only fall
+        // if the other one rises. Gravity pulls both down, so the gear holds up their weight.
+        let mut spawn_rack = |x: Scalar| {
+            let body = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    // The bodies are at rest, but the gear joint should keep holding them up.
+                    SleepingDisabled,
+                    Position(Vector::X * x),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+                ))
+                .id();
+            commands
+                .spawn(
+                    PrismaticJoint::new(ground, body)
+                        .with_local_anchor_1(Vector::X * x)
+                        .with_free_axis(Vector::Y),
+                )
+                .id()
+        };
+
+        let racks = [0.0, 0.0, 5.0, 5.0].map(&mut spawn_rack);
+
+        let unbreakable = commands
+            .spawn((GearJoint::new(racks[0], racks[1]), JointForces::default()))
+            .id();
+        let breakable = commands
+            .spawn((
+                GearJoint::new(racks[2], racks[3]),
+                BreakableJoint::default().with_break_force(1.0),
+            ))
+            .id();
+
+        commands.insert_resource(GearJoints {
+            unbreakable,
+            breakable,
+        });
+    });
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut bodies = app.world_mut().query::<(&Mass, &RigidBody)>();
+    let (mass, _) = bodies
+        .iter(app.world())
+        .find(|(_, rb)| rb.is_dynamic())
+        .unwrap();
+    let weight = mass.0 * app.world().resource::<Gravity>().0.length();
+    let substeps = app.world().resource::<SubstepCount>().0 as Scalar;
+
+    let gear_joints = app.world().resource::<GearJoints>();
+
+    // The gear joint holds up the weight of the body of the first coupled joint.
+    let forces = app
+        .world()
+        .get::<JointForces>(gear_joints.unbreakable)
+        .unwrap();
+    assert_relative_eq!(forces.force.y, weight * substeps, max_relative = 0.05);
+
+    // The weight of the bodies is larger than the break force.
+    assert!(app
+        .world()
+        .get::<GearJoint>(gear_joints.breakable)
+        .is_none());
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn joint_disables_collisions_between_connected_bodies() {
+    let mut app = create_app();
+
+    app.insert_resource(Gravity::ZERO);
+
+    #[derive(Resource)]
+    struct JointedPairs {
+        disabled: (Entity, Entity),
+        enabled: (Entity, Entity),
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let mut spawn_pair = |offset: Vector, collisions_disabled: bool| {
+            let mut spawn_body = || {
+                commands
+                    .spawn((
+                        SpatialBundle::default(),
+                        RigidBody::Dynamic,
+                        Position(offset),
+                        #[cfg(feature = "2d")]
+                        Collider::circle(0.5),
+                        #[cfg(feature = "3d")]
+                        Collider::sphere(0.5),
+                    ))
+                    .id()
+            };
+            let body1 = spawn_body();
+            let body2 = spawn_body();
+            commands.spawn(
+                RevoluteJoint::new(body1, body2).with_collisions_disabled(collisions_disabled),
+            );
+            (body1, body2)
+        };
+        let disabled = spawn_pair(Vector::ZERO, true);
+        let enabled = spawn_pair(Vector::X * 10.0, false);
+        commands.insert_resource(JointedPairs { disabled, enabled });
+    });
+
+    for _ in 0..10 {
+        tick_60_fps(&mut app);
+    }
+
+    let pairs = app.world().resource::<JointedPairs>();
+    let collisions = app.world().resource::<Collisions>();
+
+    assert!(!collisions.contains(pairs.disabled.0, pairs.disabled.1));
+    assert!(collisions.contains(pairs.enabled.0, pairs.enabled.1));
+}
+
+#[test]
+fn joint_collision_pairs_only_change_when_joints_change() {
+    let mut app = create_app();
+
+    let world = app.world_mut();
+    let body1 = world
+        .spawn((SpatialBundle::default(), RigidBody::Dynamic))
+        .id();
+    let body2 = world
+        .spawn((SpatialBundle::default(), RigidBody::Dynamic))
+        .id();
+    let joint = world.spawn(RevoluteJoint::new(body1, body2)).id();
+
+    tick_60_fps(&mut app);
+
+    let last_changed = |app: &App| {
+        app.world()
+            .resource_ref::<JointCollisionPairs>()
+            .last_changed()
+    };
+    let added = last_changed(&app);
+
+    // The solver writes to the joint every substep, but that doesn't change the pair.
+    tick_60_fps(&mut app);
+    assert_eq!(last_changed(&app), added);
+
+    app.world_mut()
+        .get_mut::<RevoluteJoint>(joint)
+        .unwrap()
+        .collisions_disabled = false;
+
+    tick_60_fps(&mut app);
+
+    assert_ne!(last_changed(&app), added);
+    assert!(!app
+        .world()
+        .resource::<JointCollisionPairs>()
+        .contains(body1, body2));
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn soft_joint_stretch_is_independent_of_mass_and_substeps() {
+    let mut stretches = Vec::new();
+
+    for substeps in [6, 12] {
+        let mut app = create_app();
+
+        app.insert_resource(SubstepCount(substeps));
+
+        app.add_systems(Startup, |mut commands: Commands| {
+            let anchor = commands
+                .spawn((SpatialBundle::default(), RigidBody::Static))
+                .id();
+            for (x, density) in [(0.0, 1.0), (5.0, 10.0)] {
+                let body = commands
+                    .spawn((
+                        SpatialBundle::default(),
+                        RigidBody::Dynamic,
+                        Position(Vector::X * x + Vector::NEG_Y),
+                        #[cfg(feature = "2d")]
+                        MassPropertiesBundle::new_computed(&Collider::circle(0.5), density),
+                        #[cfg(feature = "3d")]
+                        MassPropertiesBundle::new_computed(&Collider::sphere(0.5), density),
+                    ))
+                    .id();
+                commands.spawn(
+                    DistanceJoint::new(anchor, body)
+                        .with_local_anchor_1(Vector::X * x)
+                        .with_rest_length(1.0)
+                        .with_softness(SoftnessParameters::new(1.0, 2.0)),
+                );
+            }
+        });
+
+        for _ in 0..180 {
+            tick_60_fps(&mut app);
+        }
+
+        let mut bodies = app.world_mut().query::<(&Position, &RigidBody)>();
+        stretches.extend(
+            bodies
+                .iter(app.world())
+                .filter(|(_, rb)| rb.is_dynamic())
+                .map(|(position, _)| -position.y - 1.0),
+        );
+    }
+
+    // A spring with a stiffness of `m * omega^2` is stretched by `g / omega^2` under gravity.
+    let omega = crate::TAU * 2.0;
+    let expected_stretch = 9.81 / omega.powi(2);
+
+    for stretch in stretches.iter() {
+        assert_relative_eq!(*stretch, stretches[0], max_relative = 0.02);
+        assert_relative_eq!(*stretch, expected_stretch, max_relative = 0.05);
+    }
+}
+
+#[test]
+#[cfg(all(
+    feature = "3d",
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn spherical_joint_elliptical_swing_limits() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Bodies {
+        hard: Entity,
+        soft: Entity,
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let anchor = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let mut spawn_body = |z: Scalar, soft: bool| {
+            let body = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::new(1.0, 0.0, z)),
+                    MassPropertiesBundle::new_computed(&Collider::cuboid(0.5, 0.5, 0.5), 1.0),
+                ))
+                .id();
+            // The bodies swing down around the z-axis, which has the smaller limit.
+            let mut joint = SphericalJoint::new(anchor, body)
+                .with_local_anchor_1(Vector::Z * z)
+                .with_local_anchor_2(Vector::NEG_X)
+                .with_elliptical_swing_limits(1.0, 0.3);
+            if soft {
+                joint = joint.with_limit_softness(SoftnessParameters::new(1.0, 5.0));
+            }
+            commands.spawn(joint);
+            body
+        };
+        let hard = spawn_body(0.0, false);
+        let soft = spawn_body(5.0, true);
+        commands.insert_resource(Bodies { hard, soft });
+    });
+
+    for _ in 0..180 {
+        tick_60_fps(&mut app);
+    }
+
+    let bodies = app.world().resource::<Bodies>();
+    let swing_angle = |entity: Entity| {
+        let rotation = app.world().get::<Rotation>(entity).unwrap();
+        (rotation.0 * Vector::X).angle_between(Vector::X)
+    };
+    let hard_angle = swing_angle(bodies.hard);
+    let soft_angle = swing_angle(bodies.soft);
+
+    // The hard limit stops the body at the limit, while the soft limit lets it sag past it.
+    assert_relative_eq!(hard_angle, 0.3, epsilon = 0.02);
+    assert!(soft_angle > hard_angle + 0.05);
+    assert!(soft_angle < 1.0);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn target_joint_pulls_body_to_target() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Bodies {
+        unlimited: Entity,
+        limited: Entity,
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let mut spawn_body = |x: Scalar, max_force: Scalar| {
+            let body = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+                ))
+                .id();
+            commands.spawn(
+                TargetJoint::new(body)
+                    .with_target(Vector::X * x + Vector::Y * 2.0)
+                    .with_max_force(max_force),
+            );
+            body
+        };
+        let unlimited = spawn_body(0.0, Scalar::MAX);
+        // The maximum force is smaller than the weight of the body, so it can't be lifted.
+        let limited = spawn_body(5.0, 1.0);
+        commands.insert_resource(Bodies { unlimited, limited });
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let bodies = app.world().resource::<Bodies>();
+    let unlimited = app.world().get::<Position>(bodies.unlimited).unwrap();
+    let limited = app.world().get::<Position>(bodies.limited).unwrap();
+
+    assert_relative_eq!(unlimited.y, 2.0, epsilon = 0.05);
+    assert!(limited.y < 0.0);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn target_joint_reports_forces_and_breaks_when_overloaded() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct TargetJoints {
+        unbreakable: Entity,
+        breakable: Entity,
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let mut spawn_joint = |x: Scalar| {
+            let body = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+                ))
+                .id();
+            commands
+                .spawn(TargetJoint::new(body).with_target(Vector::X * x))
+                .id()
+        };
+
+        let unbreakable = spawn_joint(0.0);
+        let breakable = spawn_joint(5.0);
+
+        commands.entity(unbreakable).insert(JointForces::default());
+        // The weight of the body is larger than the break force.
+        commands
+            .entity(breakable)
+            .insert(BreakableJoint::default().with_break_force(1.0));
+
+        commands.insert_resource(TargetJoints {
+            unbreakable,
+            breakable,
+        });
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let mut bodies = app.world_mut().query::<(&Mass, &RigidBody)>();
+    let (mass, _) = bodies
+        .iter(app.world())
+        .find(|(_, rb)| rb.is_dynamic())
+        .unwrap();
+    let weight = mass.0 * app.world().resource::<Gravity>().0.length();
+    let substeps = app.world().resource::<SubstepCount>().0 as Scalar;
+
+    let target_joints = app.world().resource::<TargetJoints>();
+
+    // The joint holds up the weight of the body.
+    let forces = app
+        .world()
+        .get::<JointForces>(target_joints.unbreakable)
+        .unwrap();
+    assert_relative_eq!(forces.force.y, weight * substeps, max_relative = 0.05);
+
+    assert!(app
+        .world()
+        .get::<TargetJoint>(target_joints.breakable)
+        .is_none());
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn wheel_joint_suspension_and_motor() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Wheels {
+        free: Entity,
+        limited: Entity,
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let chassis = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let mut spawn_wheel = |x: Scalar, limited: bool| {
+            let wheel = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+                ))
+                .id();
+            let mut joint = WheelJoint::new(chassis, wheel)
+                .with_local_anchor_1(Vector::X * x)
+                .with_suspension_softness(SoftnessParameters::new(1.0, 2.0))
+                .with_motor(JointMotor::new_velocity(3.0, 1000.0));
+            if limited {
+                joint = joint.with_suspension_limits(-0.02, 0.02);
+            }
+            commands.spawn(joint);
+            wheel
+        };
+        let free = spawn_wheel(0.0, false);
+        let limited = spawn_wheel(5.0, true);
+        commands.insert_resource(Wheels { free, limited });
+    });
+
+    for _ in 0..180 {
+        tick_60_fps(&mut app);
+    }
+
+    let wheels = app.world().resource::<Wheels>();
+    let free_position = app.world().get::<Position>(wheels.free).unwrap();
+    let limited_position = app.world().get::<Position>(wheels.limited).unwrap();
+    let angular_velocity = app.world().get::<AngularVelocity>(wheels.free).unwrap();
+
+    // The suspension spring with a stiffness of `m * omega^2` is compressed by `g / omega^2` under gravity.
+    let omega = crate::TAU * 2.0;
+    assert_relative_eq!(free_position.y, -9.81 / omega.powi(2), max_relative = 0.05);
+    assert_relative_eq!(free_position.x, 0.0, epsilon = 0.001);
+
+    // The suspension travel is limited.
+    assert_relative_eq!(limited_position.y, -0.02, epsilon = 0.002);
+
+    // The motor spins the wheel at the target velocity.
+    #[cfg(feature = "2d")]
+    assert_relative_eq!(angular_velocity.0, 3.0, epsilon = 0.05);
+    #[cfg(feature = "3d")]
+    assert_relative_eq!(angular_velocity.z, 3.0, epsilon = 0.05);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn target_joint_pulls_offset_center_of_mass_with_both_solvers() {
+    use crate::dynamics::solver::{JointSolver, SolverConfig};
+
+    for joint_solver in [JointSolver::Impulse, JointSolver::Xpbd] {
+        let mut app = create_app();
+
+        app.insert_resource(Gravity::ZERO);
+        app.insert_resource(SolverConfig {
+            joint_solver,
+            ..default()
+        });
+
+        app.add_systems(Startup, |mut commands: Commands| {
+            #[cfg(feature = "2d")]
+            let collider = Collider::circle(0.5);
+            #[cfg(feature = "3d")]
+            let collider = Collider::sphere(0.5);
+
+            let body = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    MassPropertiesBundle {
+                        center_of_mass: CenterOfMass(Vector::X),
+                        ..MassPropertiesBundle::new_computed(&collider, 1.0)
+                    },
+                ))
+                .id();
+
+            // The attachment point is at the center of mass, so the joint shouldn't rotate the body.
+            commands.spawn(
+                TargetJoint::new(body)
+                    .with_local_anchor(Vector::X)
+                    .with_target(Vector::X + Vector::Y * 2.0),
+            );
+        });
+
+        for _ in 0..120 {
+            tick_60_fps(&mut app);
+        }
+
+        let mut bodies = app
+            .world_mut()
+            .query::<(&Position, &Rotation, &RigidBody)>();
+        let (position, rotation, _) = bodies
+            .iter(app.world())
+            .find(|(_, _, rb)| rb.is_dynamic())
+            .unwrap();
+
+        assert_relative_eq!(position.0, Vector::Y * 2.0, epsilon = 0.05);
+        #[cfg(feature = "2d")]
+        assert_relative_eq!(rotation.as_radians(), 0.0, epsilon = 0.001);
+        #[cfg(feature = "3d")]
+        assert_relative_eq!(
+            rotation.0.angle_between(Quaternion::IDENTITY),
+            0.0,
+            epsilon = 0.001
+        );
+    }
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn revolute_joint_chain_stays_connected_with_both_solvers() {
+    use crate::dynamics::solver::{JointSolver, SolverConfig};
+
+    #[derive(Resource)]
+    struct Chain(Vec<Entity>);
+
+    for joint_solver in [JointSolver::Impulse, JointSolver::Xpbd] {
+        let mut app = create_app();
+
+        app.insert_resource(SolverConfig {
+            joint_solver,
+            ..default()
+        });
+
+        app.add_systems(Startup, |mut commands: Commands| {
+            let mut previous = commands
+                .spawn((SpatialBundle::default(), RigidBody::Static))
+                .id();
+            let mut links = vec![previous];
+
+            // A horizontal chain that swings down from a static anchor.
+            for i in 0..6 {
+                let link = commands
+                    .spawn((
+                        SpatialBundle::default(),
+                        RigidBody::Dynamic,
+                        Position(Vector::X * (0.5 + i as Scalar)),
+                        #[cfg(feature = "2d")]
+                        MassPropertiesBundle::new_computed(&Collider::rectangle(1.0, 0.2), 1.0),
+                        #[cfg(feature = "3d")]
+                        MassPropertiesBundle::new_computed(&Collider::cuboid(1.0, 0.2, 0.2), 1.0),
+                    ))
+                    .id();
+                let anchor = if i == 0 {
+                    Vector::ZERO
+                } else {
+                    Vector::X * 0.5
+                };
+                commands.spawn(
+                    RevoluteJoint::new(previous, link)
+                        .with_local_anchor_1(anchor)
+                        .with_local_anchor_2(Vector::X * -0.5),
+                );
+                links.push(link);
+                previous = link;
+            }
+
+            commands.insert_resource(Chain(links));
+        });
+
+        for _ in 0..120 {
+            tick_60_fps(&mut app);
+        }
+
+        let world = app.world();
+        let links = &world.resource::<Chain>().0;
+
+        for (i, pair) in links.windows(2).enumerate() {
+            let anchor1 = if i == 0 {
+                Vector::ZERO
+            } else {
+                Vector::X * 0.5
+            };
+            let position1 = world.get::<Position>(pair[0]).unwrap().0;
+            let rotation1 = *world.get::<Rotation>(pair[0]).unwrap();
+            let position2 = world.get::<Position>(pair[1]).unwrap().0;
+            let rotation2 = *world.get::<Rotation>(pair[1]).unwrap();
+
+            let separation = (position2 + rotation2 * (Vector::X * -0.5))
+                .distance(position1 + rotation1 * anchor1);
+            assert!(
+                separation < 0.02,
+                "{joint_solver:?}: joint {i} separated by {separation}"
+            );
+        }
+
+        // The chain has swung away from its initial horizontal pose.
+        let last = world.get::<Position>(*links.last().unwrap()).unwrap().0;
+        assert!(
+            last.distance(Vector::X * 5.5) > 1.0,
+            "{joint_solver:?}: last link at {last}"
+        );
+    }
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn velocity_iterations_reduce_joint_error() {
+    use crate::dynamics::solver::SolverConfig;
+
+    #[derive(Resource)]
+    struct Chain(Vec<Entity>);
+
+    let mut errors = Vec::new();
+
+    for velocity_iterations in [1, 8] {
+        let mut app = create_app();
+
+        // Use a single substep so that only the iterations affect convergence.
+        app.insert_resource(SubstepCount(1));
+        app.insert_resource(SolverConfig {
+            velocity_iterations,
+            relaxation_iterations: velocity_iterations,
+            ..default()
+        });
+
+        app.add_systems(Startup, |mut commands: Commands| {
+            let mut previous = commands
+                .spawn((SpatialBundle::default(), RigidBody::Static))
+                .id();
+            let mut links = vec![previous];
+
+            // A vertical chain of light links with a heavy body at the end.
+            for i in 0..6 {
+                let density = if i == 5 { 100.0 } else { 1.0 };
+                let link = commands
+                    .spawn((
+                        SpatialBundle::default(),
+                        RigidBody::Dynamic,
+                        Position(Vector::NEG_Y * (0.5 + i as Scalar)),
+                        #[cfg(feature = "2d")]
+                        MassPropertiesBundle::new_computed(&Collider::rectangle(0.2, 1.0), density),
+                        #[cfg(feature = "3d")]
+                        MassPropertiesBundle::new_computed(
+                            &Collider::cuboid(0.2, 1.0, 0.2),
+                            density,
+                        ),
+                    ))
+                    .id();
+                let anchor = if i == 0 {
+                    Vector::ZERO
+                } else {
+                    Vector::NEG_Y * 0.5
+                };
+                commands.spawn(
+                    RevoluteJoint::new(previous, link)
+                        .with_local_anchor_1(anchor)
+                        .with_local_anchor_2(Vector::Y * 0.5),
+                );
+                links.push(link);
+                previous = link;
+            }
+
+            commands.insert_resource(Chain(links));
+        });
+
+        for _ in 0..60 {
+            tick_60_fps(&mut app);
+        }
+
+        // The distance between the ends of the chain, which is 6.0 when all joints are satisfied.
+        let world = app.world();
+        let links = &world.resource::<Chain>().0;
+        let last = *links.last().unwrap();
+        let end = world.get::<Position>(last).unwrap().0
+            + *world.get::<Rotation>(last).unwrap() * (Vector::NEG_Y * 0.5);
+        errors.push(end.length() - 6.0);
+    }
+
+    assert!(
+        errors[1].abs() < 0.75 * errors[0].abs(),
+        "joint errors with 1 and 8 iterations: {errors:?}"
+    );
+}
+
+// In 3D, the four contact points between boxes are redundant and solved sequentially.
+#[test]
+#[cfg(all(
+    feature = "2d",
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn block_solver_keeps_box_stack_at_rest() {
+    use crate::dynamics::solver::SolverConfig;
+
+    #[derive(Resource)]
+    struct Stack(Vec<Entity>);
+
+    let mut drifts = Vec::new();
+
+    for use_block_solver in [false, true] {
+        let mut app = create_app();
+
+        // Use few substeps, which makes the stack rock with the sequential solver.
+        app.insert_resource(SubstepCount(2));
+        app.insert_resource(SolverConfig {
+            use_block_solver,
+            ..default()
+        });
+
+        app.add_systems(Startup, |mut commands: Commands| {
+            commands.spawn((
+                SpatialBundle::default(),
+                RigidBody::Static,
+                Collider::rectangle(20.0, 1.0),
+            ));
+
+            let boxes = (1..=6)
+                .map(|i| {
+                    commands
+                        .spawn((
+                            SpatialBundle::default(),
+                            RigidBody::Dynamic,
+                            Position(Vector::Y * i as Scalar),
+                            Collider::rectangle(1.0, 1.0),
+                            SleepingDisabled,
+                        ))
+                        .id()
+                })
+                .collect();
+
+            commands.insert_resource(Stack(boxes));
+        });
+
+        for _ in 0..180 {
+            tick_60_fps(&mut app);
+        }
+
+        // The horizontal distance that the top of the stack has drifted due to rocking.
+        let world = app.world();
+        let top = *world.resource::<Stack>().0.last().unwrap();
+        let position = world.get::<Position>(top).unwrap().0;
+        assert!((position.y - 6.0).abs() < 0.1);
+        drifts.push(position.x.abs());
+    }
+
+    assert!(
+        drifts[1] < 0.005 && drifts[1] < drifts[0],
+        "drifts without and with block solver: {drifts:?}"
+    );
+}
+
+// The block solver can't couple points whose normal response is scaled, so they are solved sequentially.
+#[test]
+#[cfg(all(
+    feature = "2d",
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn block_solver_solves_scaled_contacts_sequentially() {
+    use crate::dynamics::solver::SolverConfig;
+    use bevy::ecs::system::SystemParam;
+
+    #[derive(SystemParam)]
+    struct SoftContactHooks;
+
+    impl ContactModificationHooks for SoftContactHooks {
+        fn modify_contacts(&self, contacts: &mut Contacts) {
+            for contact in contacts
+                .manifolds
+                .iter_mut()
+                .flat_map(|manifold| manifold.contacts.iter_mut())
+            {
+                contact.normal_impulse_scale = 0.5;
+            }
+        }
+    }
+
+    #[derive(Resource)]
+    struct Body(Entity);
+
+    let mut positions = Vec::new();
+
+    for use_block_solver in [false, true] {
+        let mut app = create_app();
+
+        app.insert_resource(SolverConfig {
+            use_block_solver,
+            ..default()
+        });
+        app.add_plugins(ContactModificationPlugin::<SoftContactHooks>::default());
+
+        app.add_systems(Startup, |mut commands: Commands| {
+            commands.spawn((
+                SpatialBundle::default(),
+                RigidBody::Static,
+                Collider::rectangle(20.0, 1.0),
+            ));
+            let body = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::Y),
+                    Rotation::radians(0.1),
+                    Collider::rectangle(1.0, 1.0),
+                ))
+                .id();
+            commands.insert_resource(Body(body));
+        });
+
+        for _ in 0..60 {
+            tick_60_fps(&mut app);
+        }
+
+        let world = app.world();
+        let body = world.resource::<Body>().0;
+        positions.push((
+            world.get::<Position>(body).unwrap().0,
+            world.get::<Rotation>(body).unwrap().as_radians(),
+        ));
+    }
+
+    assert_eq!(positions[0], positions[1]);
+}
+
+#[test]
+fn solver_iteration_schedules_run_once_per_iteration() {
+    use crate::dynamics::solver::{RelaxSchedule, SolveConstraintsSchedule, SolverConfig};
+
+    #[derive(Resource, Default)]
+    struct Runs {
+        solve: usize,
+        relax: usize,
+    }
+
+    let mut app = create_app();
+
+    app.insert_resource(SubstepCount(2));
+    app.insert_resource(SolverConfig {
+        velocity_iterations: 3,
+        relaxation_iterations: 2,
+        ..default()
+    });
+    app.init_resource::<Runs>();
+
+    // Joints and contacts are solved in these schedules, so custom constraints
+    // added to them are iterated together with the built-in ones.
+    app.add_systems(SolveConstraintsSchedule, |mut runs: ResMut<Runs>| {
+        runs.solve += 1;
+    });
+    app.add_systems(RelaxSchedule, |mut runs: ResMut<Runs>| {
+        runs.relax += 1;
+    });
+
+    tick_60_fps(&mut app);
+
+    let runs = app.world().resource::<Runs>();
+    assert_eq!(runs.solve, 2 * 3);
+    assert_eq!(runs.relax, 2 * 2);
+}
+
+#[test]
+fn joint_graph_tracks_connected_bodies() {
+    let mut app = create_app();
+
+    #[derive(Resource, Clone, Copy)]
+    struct Bodies {
+        anchor: Entity,
+        bodies: [Entity; 5],
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let anchor = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let bodies = [(); 5].map(|_| {
+            commands
+                .spawn((SpatialBundle::default(), RigidBody::Dynamic))
+                .id()
+        });
+
+        // A chain of three bodies, and a separate pair of bodies, both hanging from the same static anchor
+        commands.spawn(RevoluteJoint::new(anchor, bodies[0]));
+        commands.spawn(RevoluteJoint::new(bodies[0], bodies[1]));
+        commands.spawn(RevoluteJoint::new(bodies[1], bodies[2]));
+        commands.spawn(DistanceJoint::new(anchor, bodies[3]));
+        commands.spawn(DistanceJoint::new(bodies[3], bodies[4]));
+
+        commands.insert_resource(Bodies { anchor, bodies });
+    });
+
+    tick_60_fps(&mut app);
+
+    let Bodies { anchor, bodies } = *app.world().resource::<Bodies>();
+    let graph = app.world().resource::<JointGraph>();
+
+    assert_eq!(graph.joints().count(), 5);
+    assert_eq!(graph.joints_of(bodies[1]).count(), 2);
+    assert!(graph.are_connected(bodies[0], bodies[1]));
+    assert!(!graph.are_connected(bodies[0], bodies[2]));
+
+    // The traversal doesn't continue through the static anchor
+    let mut chain = graph.connected_component(bodies[0]);
+    chain.bodies.sort();
+    let mut expected = vec![anchor, bodies[0], bodies[1], bodies[2]];
+    expected.sort();
+    assert_eq!(chain.bodies, expected);
+    assert_eq!(chain.joints.len(), 3);
+    assert_eq!(graph.connected_components().len(), 2);
+
+    // Despawn the chain, leaving the anchor and the separate pair untouched
+    app.world_mut()
+        .commands()
+        .entity(bodies[2])
+        .add(DespawnJointAssembly);
+
+    tick_60_fps(&mut app);
+
+    for body in &bodies[..3] {
+        assert!(app.world().get_entity(*body).is_none());
+    }
+    for body in bodies[3..].iter().chain([&anchor]) {
+        assert!(app.world().get_entity(*body).is_some());
+    }
+
+    let graph = app.world().resource::<JointGraph>();
+    assert_eq!(graph.joints().count(), 2);
+    assert!(graph.are_connected(anchor, bodies[3]));
+    assert!(graph.are_connected(bodies[3], bodies[4]));
+}
+
+#[test]
+fn gear_joint_connects_coupled_bodies_in_joint_graph() {
+    let mut app = create_app();
+
+    let world = app.world_mut();
+    let ground = world
+        .spawn((SpatialBundle::default(), RigidBody::Static))
+        .id();
+    let gear1 = world
+        .spawn((SpatialBundle::default(), RigidBody::Dynamic))
+        .id();
+    let gear2 = world
+        .spawn((SpatialBundle::default(), RigidBody::Dynamic))
+        .id();
+    let joint1 = world.spawn(RevoluteJoint::new(ground, gear1)).id();
+    let joint2 = world.spawn(RevoluteJoint::new(ground, gear2)).id();
+    let gear_joint = world.spawn(GearJoint::new(joint1, joint2)).id();
+
+    tick_60_fps(&mut app);
+
+    // The gear joint connects the gears, even though the ground between them is static.
+    let graph = app.world().resource::<JointGraph>();
+    assert_eq!(graph.bodies(gear_joint), Some((gear1, gear2)));
+    assert!(graph.are_connected(gear1, gear2));
+
+    // Removing a coupled joint removes the gear joint from the graph.
+    app.world_mut().entity_mut(joint2).remove::<RevoluteJoint>();
+
+    tick_60_fps(&mut app);
+
+    let graph = app.world().resource::<JointGraph>();
+    assert_eq!(graph.bodies(gear_joint), None);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn joints_whose_bodies_change_mid_step_are_solved() {
+    use crate::dynamics::solver::SubstepSolverSet;
+
+    let mut app = create_app();
+
+    app.insert_resource(Gravity::ZERO);
+
+    #[derive(Resource, Clone, Copy)]
+    struct Retargeted {
+        joint: Entity,
+        colored_body: Entity,
+        solved_body: Entity,
+    }
+
+    let world = app.world_mut();
+    let mut spawn_body = |position: Vector| {
+        world
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Position(position),
+                #[cfg(feature = "2d")]
+                MassPropertiesBundle::new_computed(&Collider::circle(0.5), 1.0),
+                #[cfg(feature = "3d")]
+                MassPropertiesBundle::new_computed(&Collider::sphere(0.5), 1.0),
+            ))
+            .id()
+    };
+
+    // Pairs of bodies connected by joints that don't share any bodies, so they all get the same color.
+    let pairs = (0..8)
+        .map(|i| {
+            let x = 3.0 * i as Scalar;
+            [
+                spawn_body(Vector::X * x),
+                spawn_body(Vector::X * x + Vector::Y * 2.0),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let joints = pairs
+        .iter()
+        .map(|[body1, body2]| {
+            world
+                .spawn(DistanceJoint::new(*body1, *body2).with_rest_length(1.0))
+                .id()
+        })
+        .collect::<Vec<_>>();
+
+    // During the substeps, the second joint uses the second body of the first joint instead of its own,
+    // so it shares a body with another joint of the same color.
+    let retargeted = Retargeted {
+        joint: joints[1],
+        colored_body: pairs[1][1],
+        solved_body: pairs[0][1],
+    };
+    world.insert_resource(retargeted);
+
+    app.add_systems(
+        PhysicsSchedule,
+        (|mut joints: Query<&mut DistanceJoint>, retargeted: Res<Retargeted>| {
+            joints.get_mut(retargeted.joint).unwrap().entity2 = retargeted.colored_body;
+        })
+        .after(PhysicsStepSet::NarrowPhase)
+        .before(PhysicsStepSet::Solver),
+    );
+    app.add_systems(
+        SubstepSchedule,
+        (|mut joints: Query<&mut DistanceJoint>, retargeted: Res<Retargeted>| {
+            joints.get_mut(retargeted.joint).unwrap().entity2 = retargeted.solved_body;
+        })
+        .before(SubstepSolverSet::WarmStart),
+    );
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    // The retargeted joint is solved with the bodies it had during the substeps.
+    let world = app.world();
+    let distance = |body1: Entity, body2: Entity| {
+        world
+            .get::<Position>(body1)
+            .unwrap()
+            .distance(world.get::<Position>(body2).unwrap().0)
+    };
+    assert_relative_eq!(
+        distance(pairs[1][0], retargeted.solved_body),
+        1.0,
+        epsilon = 0.01
+    );
+    assert_relative_eq!(distance(pairs[0][0], pairs[0][1]), 1.0, epsilon = 0.01);
+
+    // The body that the joint had when it was colored is not affected.
+    assert_eq!(
+        world.get::<Position>(retargeted.colored_body).unwrap().0,
+        Vector::X * 3.0 + Vector::Y * 2.0
+    );
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn islands_sleep_and_wake_together() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Stacks([[Entity; 2]; 2]);
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Static,
+            #[cfg(feature = "2d")]
+            Collider::rectangle(20.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(20.0, 1.0, 20.0),
+        ));
+
+        // Two stacks of two boxes. The top box of the second stack can never sleep.
+        let stacks = [0.0, 5.0].map(|x| {
+            [1.0, 2.0].map(|y| {
+                commands
+                    .spawn((
+                        SpatialBundle::default(),
+                        RigidBody::Dynamic,
+                        Position(Vector::X * x + Vector::Y * y),
+                        #[cfg(feature = "2d")]
+                        Collider::rectangle(1.0, 1.0),
+                        #[cfg(feature = "3d")]
+                        Collider::cuboid(1.0, 1.0, 1.0),
+                    ))
+                    .id()
+            })
+        });
+        commands.entity(stacks[1][1]).insert(SleepingDisabled);
+
+        commands.insert_resource(Stacks(stacks));
+    });
+
+    for _ in 0..180 {
+        tick_60_fps(&mut app);
+    }
+
+    let stacks = app.world().resource::<Stacks>().0;
+    let islands = app.world().resource::<PhysicsIslands>();
+
+    // Bodies resting on the same static body are not in the same island.
+    assert!(islands.in_same_island(stacks[0][0], stacks[0][1]));
+    assert!(islands.in_same_island(stacks[1][0], stacks[1][1]));
+    assert!(!islands.in_same_island(stacks[0][0], stacks[1][0]));
+
+    // The first stack sleeps as a whole, while the second stack is kept awake by its top box.
+    assert!(islands.island_of(stacks[0][0]).unwrap().is_sleeping());
+    assert!(app.world().get::<Sleeping>(stacks[0][0]).is_some());
+    assert!(app.world().get::<Sleeping>(stacks[0][1]).is_some());
+    assert!(app.world().get::<Sleeping>(stacks[1][0]).is_none());
+
+    // Waking up the top box of the first stack wakes up the bottom box too.
+    app.world_mut()
+        .entity_mut(stacks[0][1])
+        .insert(LinearVelocity(Vector::X * 0.5));
+
+    tick_60_fps(&mut app);
+
+    assert!(app.world().get::<Sleeping>(stacks[0][0]).is_none());
+    assert!(app.world().get::<Sleeping>(stacks[0][1]).is_none());
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn articulation_pendulum_swings_with_expected_period() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Bob(Entity);
+
+    const INITIAL_ANGLE: Scalar = 0.1;
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let base = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let offset = Vector::X * INITIAL_ANGLE.sin() - Vector::Y * INITIAL_ANGLE.cos();
+        let bob = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Position(offset),
+                #[cfg(feature = "2d")]
+                MassPropertiesBundle::new_computed(&Collider::circle(0.1), 1.0),
+                #[cfg(feature = "3d")]
+                MassPropertiesBundle::new_computed(&Collider::sphere(0.1), 1.0),
+                ArticulationLink::revolute(base).with_local_anchor_2(-offset),
+            ))
+            .id();
+        commands.insert_resource(Bob(bob));
+    });
+
+    // The period of the pendulum is about 2.01 seconds, so it reaches
+    // the opposite side after about 60 frames.
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let bob = app.world().resource::<Bob>().0;
+    let link = app.world().get::<ArticulationLink>(bob).unwrap();
+    let position = app.world().get::<Position>(bob).unwrap();
+
+    assert_relative_eq!(link.position, -2.0 * INITIAL_ANGLE, epsilon = 0.005);
+    assert_relative_eq!(position.length(), 1.0, epsilon = 1e-4);
+    assert_relative_eq!(position.x, -INITIAL_ANGLE.sin(), epsilon = 0.005);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn articulation_chain_stays_connected_and_conserves_energy() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Chain(Vec<Entity>);
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let mut parent = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let mut links = vec![];
+
+        // A horizontal chain where every other link is a hundred times heavier.
+        for i in 0..6 {
+            let density = if i % 2 == 0 { 1.0 } else { 100.0 };
+            let anchor = if i == 0 {
+                Vector::ZERO
+            } else {
+                Vector::X * 0.5
+            };
+            let link = commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * (0.5 + i as Scalar)),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.1), density),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.1), density),
+                    ArticulationLink::revolute(parent)
+                        .with_local_anchor_1(anchor)
+                        .with_local_anchor_2(Vector::X * -0.5),
+                ))
+                .id();
+            links.push(link);
+            parent = link;
+        }
+
+        commands.insert_resource(Chain(links));
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let links = &app.world().resource::<Chain>().0;
+    let mut energy = 0.0;
+    let mut max_potential_energy: Scalar = 0.0;
+
+    for (i, &entity) in links.iter().enumerate() {
+        let world = app.world();
+        let position = world.get::<Position>(entity).unwrap().0;
+        let rotation = *world.get::<Rotation>(entity).unwrap();
+        let mass = world.get::<Mass>(entity).unwrap().0;
+        let inertia = world.get::<Inertia>(entity).unwrap();
+        let linear_velocity = world.get::<LinearVelocity>(entity).unwrap().0;
+        let angular_velocity = world.get::<AngularVelocity>(entity).unwrap().0;
+
+        // The links are still attached to each other.
+        if i > 0 {
+            let parent = links[i - 1];
+            let parent_position = world.get::<Position>(parent).unwrap().0;
+            let parent_rotation = *world.get::<Rotation>(parent).unwrap();
+            let anchor1 = parent_position + parent_rotation * (Vector::X * 0.5);
+            let anchor2 = position + rotation * (Vector::X * -0.5);
+            assert!(anchor1.distance(anchor2) < 1e-3);
+        }
+
+        #[cfg(feature = "2d")]
+        let angular_energy = 0.5 * inertia.0 * angular_velocity.powi(2);
+        #[cfg(feature = "3d")]
+        let angular_energy =
+            0.5 * angular_velocity.dot(inertia.rotated(&rotation).0 * angular_velocity);
+
+        energy += 0.5 * mass * linear_velocity.length_squared() + angular_energy;
+        energy += mass * 9.81 * position.y;
+        max_potential_energy += mass * 9.81 * (1.0 + i as Scalar);
+    }
+
+    // The chain started at rest with zero potential energy.
+    assert!(
+        energy.abs() < 0.02 * max_potential_energy,
+        "energy: {energy}, max potential energy: {max_potential_energy}"
+    );
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn articulation_link_motors_and_limits() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Links {
+        prismatic: Entity,
+        revolute: Entity,
+        #[cfg(feature = "3d")]
+        spherical: Entity,
+    }
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let base = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let mut spawn_link = |x: Scalar, link: ArticulationLink| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x),
+                    #[cfg(feature = "2d")]
+                    MassPropertiesBundle::new_computed(&Collider::circle(0.1), 1.0),
+                    #[cfg(feature = "3d")]
+                    MassPropertiesBundle::new_computed(&Collider::sphere(0.1), 1.0),
+                    link,
+                ))
+                .id()
+        };
+
+        let prismatic = spawn_link(
+            -3.0,
+            ArticulationLink::prismatic(base)
+                .with_local_anchor_1(Vector::X * -3.0)
+                .with_axis(Vector::Y)
+                .with_distance_limits(-0.5, 0.0),
+        );
+        let revolute = spawn_link(
+            3.5,
+            ArticulationLink::revolute(base)
+                .with_local_anchor_1(Vector::X * 3.0)
+                .with_local_anchor_2(Vector::X * -0.5)
+                .with_motor(JointMotor::new_position(0.5, 1000.0, 100.0)),
+        );
+        #[cfg(feature = "3d")]
+        let spherical = spawn_link(
+            6.5,
+            ArticulationLink::spherical(base)
+                .with_local_anchor_1(Vector::X * 6.0)
+                .with_local_anchor_2(Vector::X * -0.5)
+                .with_angle_limits(0.0, 0.3),
+        );
+
+        commands.insert_resource(Links {
+            prismatic,
+            revolute,
+            #[cfg(feature = "3d")]
+            spherical,
+        });
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let links = app.world().resource::<Links>();
+
+    // The prismatic link falls until it reaches the lower limit.
+    let prismatic = app
+        .world()
+        .get::<ArticulationLink>(links.prismatic)
+        .unwrap();
+    let prismatic_position = app.world().get::<Position>(links.prismatic).unwrap();
+    assert_relative_eq!(prismatic.position, -0.5, epsilon = 0.01);
+    assert_relative_eq!(prismatic_position.y, -0.5, epsilon = 0.01);
+    assert_relative_eq!(prismatic_position.x, -3.0, epsilon = 1e-4);
+
+    // The motor holds the revolute link at the target angle.
+    let revolute = app.world().get::<ArticulationLink>(links.revolute).unwrap();
+    assert_relative_eq!(revolute.position, 0.5, epsilon = 0.02);
+
+    // The spherical link swings down until it reaches the limit.
+    #[cfg(feature = "3d")]
+    {
+        let spherical = app
+            .world()
+            .get::<ArticulationLink>(links.spherical)
+            .unwrap();
+        let angle = spherical
+            .spherical_rotation
+            .angle_between(Quaternion::IDENTITY);
+        assert!((0.25..0.32).contains(&angle), "angle: {angle}");
+    }
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn articulation_links_collide_with_other_bodies() {
+    let mut app = create_app();
+
+    #[derive(Resource)]
+    struct Arm(Entity);
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let base = commands
+            .spawn((SpatialBundle::default(), RigidBody::Static))
+            .id();
+        let arm = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Position(Vector::X),
+                #[cfg(feature = "2d")]
+                Collider::rectangle(2.0, 0.2),
+                #[cfg(feature = "3d")]
+                Collider::cuboid(2.0, 0.2, 0.2),
+                ArticulationLink::revolute(base).with_local_anchor_2(Vector::NEG_X),
+            ))
+            .id();
+
+        // A box under the end of the arm
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Static,
+            #[cfg(feature = "2d")]
+            Position(Vector::new(1.5, -1.0)),
+            #[cfg(feature = "3d")]
+            Position(Vector::new(1.5, -1.0, 0.0)),
+            #[cfg(feature = "2d")]
+            Collider::rectangle(1.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(1.0, 1.0, 1.0),
+        ));
+
+        commands.insert_resource(Arm(arm));
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    // The end of the arm rests on the box at an angle of about -0.2 radians
+    // instead of swinging through it.
+    let arm = app.world().resource::<Arm>().0;
+    let link = app.world().get::<ArticulationLink>(arm).unwrap();
+    assert!(
+        (-0.25..-0.15).contains(&link.position),
+        "angle: {}",
+        link.position
+    );
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn contact_modification_hooks_modify_contact_points() {
+    use bevy::ecs::system::SystemParam;
+
+    #[derive(Component)]
+    struct ConveyorBelt;
+
+    #[derive(Component)]
+    struct Ghost;
+
+    #[derive(SystemParam)]
+    struct TestHooks<'w, 's> {
+        conveyor_belts: Query<'w, 's, (), With<ConveyorBelt>>,
+        ghosts: Query<'w, 's, (), With<Ghost>>,
+    }
+
+    impl ContactModificationHooks for TestHooks<'_, '_> {
+        fn modify_contacts(&self, contacts: &mut Contacts) {
+            let is_ghost =
+                self.ghosts.contains(contacts.entity1) || self.ghosts.contains(contacts.entity2);
+
+            // The target velocity is relative to the surface of the first entity.
+            let belt_velocity = if self.conveyor_belts.contains(contacts.entity1) {
+                Vector::X * 2.0
+            } else if self.conveyor_belts.contains(contacts.entity2) {
+                Vector::NEG_X * 2.0
+            } else {
+                Vector::ZERO
+            };
+
+            for contact in contacts
+                .manifolds
+                .iter_mut()
+                .flat_map(|manifold| manifold.contacts.iter_mut())
+            {
+                contact.enabled = !is_ghost;
+                contact.tangent_velocity = belt_velocity;
+            }
+        }
+    }
+
+    #[derive(Resource)]
+    struct Bodies {
+        box_on_belt: Entity,
+        ghost: Entity,
+    }
+
+    let mut app = create_app();
+
+    app.add_plugins(ContactModificationPlugin::<TestHooks>::default());
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Static,
+            ConveyorBelt,
+            #[cfg(feature = "2d")]
+            Collider::rectangle(100.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(100.0, 1.0, 100.0),
+        ));
+
+        let mut spawn_box = |x: Scalar| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x + Vector::Y),
+                    #[cfg(feature = "2d")]
+                    Collider::rectangle(1.0, 1.0),
+                    #[cfg(feature = "3d")]
+                    Collider::cuboid(1.0, 1.0, 1.0),
+                ))
+                .id()
+        };
+        let box_on_belt = spawn_box(0.0);
+        let ghost = spawn_box(10.0);
+        commands.entity(ghost).insert(Ghost);
+
+        commands.insert_resource(Bodies { box_on_belt, ghost });
+    });
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let world = app.world();
+    let bodies = world.resource::<Bodies>();
+
+    // The box is carried along by the conveyor belt.
+    let box_position = world.get::<Position>(bodies.box_on_belt).unwrap().0;
+    let box_velocity = world.get::<LinearVelocity>(bodies.box_on_belt).unwrap().0;
+    assert_relative_eq!(box_velocity.x, 2.0, epsilon = 0.05);
+    assert_relative_eq!(box_position.y, 1.0, epsilon = 0.05);
+
+    // The contacts of the ghost are disabled, so it falls through the belt.
+    let ghost_position = world.get::<Position>(bodies.ghost).unwrap().0;
+    assert!(ghost_position.y < 0.0);
+}
+
+// The 3D friction basis only depends on the contact normal. If it followed the relative velocity,
+// the warm-started friction impulse would be applied along a different direction every substep
+// as the tiny residual velocity changes direction, and a box resting on a slope would creep.
+#[test]
+#[cfg(all(
+    feature = "3d",
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn box_rests_on_slope_with_static_friction() {
+    #[derive(Resource)]
+    struct Body(Entity);
+
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let slope = Quaternion::from_rotation_z(0.3);
+
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Static,
+            Rotation(slope),
+            Collider::cuboid(20.0, 1.0, 20.0),
+            Friction::new(1.0),
+        ));
+        let body = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Position(slope * Vector::Y),
+                Rotation(slope),
+                Collider::cuboid(1.0, 1.0, 1.0),
+                Friction::new(1.0),
+                SleepingDisabled,
+            ))
+            .id();
+        commands.insert_resource(Body(body));
+    });
+
+    // Let the box settle on the slope.
+    for _ in 0..30 {
+        tick_60_fps(&mut app);
+    }
+
+    let body = app.world().resource::<Body>().0;
+    let start = app.world().get::<Position>(body).unwrap().0;
+
+    for _ in 0..300 {
+        tick_60_fps(&mut app);
+    }
+
+    let end = app.world().get::<Position>(body).unwrap().0;
+    let distance = start.distance(end);
+    assert!(distance < 0.05, "box crept {distance} down the slope");
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn surface_velocity_drags_touching_bodies() {
+    #[derive(Resource)]
+    struct Bodies {
+        box_on_belt: Entity,
+        treadmill_box: Entity,
+    }
+
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let mut spawn_ground = |x: Scalar| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Static,
+                    Position(Vector::X * x),
+                    #[cfg(feature = "2d")]
+                    Collider::rectangle(20.0, 1.0),
+                    #[cfg(feature = "3d")]
+                    Collider::cuboid(20.0, 1.0, 20.0),
+                ))
+                .id()
+        };
+
+        let belt = spawn_ground(-20.0);
+        spawn_ground(20.0);
+
+        // A conveyor belt that is rotated upside down, so its surface moves along the negative X axis.
+        commands.entity(belt).insert((
+            SurfaceVelocity::new(Vector::X * 2.0),
+            #[cfg(feature = "2d")]
+            Rotation::degrees(180.0),
+            #[cfg(feature = "3d")]
+            Rotation(Quaternion::from_rotation_z(crate::math::PI)),
+        ));
+
+        let mut spawn_box = |x: Scalar| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x + Vector::Y),
+                    #[cfg(feature = "2d")]
+                    Collider::rectangle(1.0, 1.0),
+                    #[cfg(feature = "3d")]
+                    Collider::cuboid(1.0, 1.0, 1.0),
+                ))
+                .id()
+        };
+        let box_on_belt = spawn_box(-20.0);
+
+        // A box whose own surface moves, like a treadmill turned upside down.
+        // It pushes itself along the ground in the opposite direction.
+        let treadmill_box = spawn_box(20.0);
+        commands
+            .entity(treadmill_box)
+            .insert(SurfaceVelocity::new(Vector::X * 2.0));
+
+        commands.insert_resource(Bodies {
+            box_on_belt,
+            treadmill_box,
+        });
+    });
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let world = app.world();
+    let bodies = world.resource::<Bodies>();
+
+    let velocity = world.get::<LinearVelocity>(bodies.box_on_belt).unwrap().0;
+    assert_relative_eq!(velocity.x, -2.0, epsilon = 0.05);
+
+    let velocity = world.get::<LinearVelocity>(bodies.treadmill_box).unwrap().0;
+    assert_relative_eq!(velocity.x, -2.0, epsilon = 0.05);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn rolling_and_spinning_friction_slow_down_balls() {
+    #[derive(Resource)]
+    struct Balls {
+        rolling: Entity,
+        resisted: Entity,
+        #[cfg(feature = "3d")]
+        spinning: Entity,
+    }
+
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Static,
+            #[cfg(feature = "2d")]
+            Collider::rectangle(100.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(100.0, 1.0, 100.0),
+            Friction::new(0.5)
+                .with_rolling_coefficient(0.1)
+                .with_spinning_coefficient(0.1)
+                .with_combine_rule(CoefficientCombine::Min),
+        ));
+
+        // Balls rolling along the X axis without slipping.
+        let mut spawn_ball = |x: Scalar, friction: Friction| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    #[cfg(feature = "2d")]
+                    Collider::circle(0.5),
+                    #[cfg(feature = "3d")]
+                    Collider::sphere(0.5),
+                    friction,
+                    Position(Vector::X * x + Vector::Y),
+                    LinearVelocity(Vector::X * 2.0),
+                    #[cfg(feature = "2d")]
+                    AngularVelocity(-4.0),
+                    #[cfg(feature = "3d")]
+                    AngularVelocity(Vector::Z * -4.0),
+                ))
+                .id()
+        };
+
+        let rolling = spawn_ball(-20.0, Friction::new(0.5));
+        let resisted = spawn_ball(10.0, Friction::new(0.5).with_rolling_coefficient(0.1));
+
+        // A ball spinning around the contact normal.
+        #[cfg(feature = "3d")]
+        let spinning = commands
+            .spawn((
+                SpatialBundle::default(),
+                RigidBody::Dynamic,
+                Collider::sphere(0.5),
+                Friction::new(0.5).with_spinning_coefficient(0.1),
+                Position(Vector::new(30.0, 1.0, 0.0)),
+                AngularVelocity(Vector::Y * 10.0),
+            ))
+            .id();
+
+        commands.insert_resource(Balls {
+            rolling,
+            resisted,
+            #[cfg(feature = "3d")]
+            spinning,
+        });
+    });
+
+    for _ in 0..180 {
+        tick_60_fps(&mut app);
+    }
+
+    let world = app.world();
+    let balls = world.resource::<Balls>();
+
+    // Without rolling friction, the ball keeps rolling.
+    let velocity = world.get::<LinearVelocity>(balls.rolling).unwrap().0;
+    assert_relative_eq!(velocity.x, 2.0, epsilon = 0.1);
+
+    // With rolling friction, the ball comes to a stop.
+    let velocity = world.get::<LinearVelocity>(balls.resisted).unwrap().0;
+    assert_relative_eq!(velocity.x, 0.0, epsilon = 0.05);
+
+    #[cfg(feature = "3d")]
+    {
+        let angular_velocity = world.get::<AngularVelocity>(balls.spinning).unwrap().0;
+        assert_relative_eq!(angular_velocity.y, 0.0, epsilon = 0.05);
+    }
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn anisotropic_friction_depends_on_sliding_direction() {
+    #[derive(Resource)]
+    struct Boxes {
+        along: Entity,
+        across: Entity,
+    }
+
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Static,
+            #[cfg(feature = "2d")]
+            Collider::rectangle(100.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(100.0, 1.0, 100.0),
+            Friction::new(1.0).with_combine_rule(CoefficientCombine::Min),
+        ));
+
+        // Boxes with no friction along their local X axis, like skates.
+        let mut spawn_box = |x: Scalar, velocity: Vector| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x + Vector::Y),
+                    #[cfg(feature = "2d")]
+                    Collider::rectangle(1.0, 1.0),
+                    #[cfg(feature = "3d")]
+                    Collider::cuboid(1.0, 1.0, 1.0),
+                    Friction::new(1.0),
+                    AnisotropicFriction::new(Vector::X, 0.0, 1.0),
+                    LinearVelocity(velocity),
+                ))
+                .id()
+        };
+
+        let along = spawn_box(-20.0, Vector::X * 2.0);
+
+        // In 2D, the box is rotated so that it slides across its primary direction.
+        // In 3D, it slides along the ground perpendicular to its primary direction.
+        #[cfg(feature = "2d")]
+        let across = {
+            let entity = spawn_box(20.0, Vector::X * 2.0);
+            commands.entity(entity).insert(Rotation::degrees(90.0));
+            entity
+        };
+        #[cfg(feature = "3d")]
+        let across = spawn_box(20.0, Vector::Z * 2.0);
+
+        commands.insert_resource(Boxes { along, across });
+    });
+
+    for _ in 0..60 {
+        tick_60_fps(&mut app);
+    }
+
+    let world = app.world();
+    let boxes = world.resource::<Boxes>();
+
+    // The box sliding along the primary direction keeps sliding.
+    let velocity = world.get::<LinearVelocity>(boxes.along).unwrap().0;
+    assert_relative_eq!(velocity.x, 2.0, epsilon = 0.05);
+
+    // The box sliding along the secondary direction comes to a stop.
+    let velocity = world.get::<LinearVelocity>(boxes.across).unwrap().0;
+    assert_relative_eq!(velocity.length(), 0.0, epsilon = 0.05);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn contact_softness_overrides_global_softness() {
+    #[derive(Resource)]
+    struct Balls {
+        on_ground: Entity,
+        on_pad: Entity,
+    }
+
+    let mut app = create_app();
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        let mut spawn_ball_on_ground = |x: Scalar| {
+            commands.spawn((
+                SpatialBundle::default(),
+                RigidBody::Static,
+                Position(Vector::X * x),
+                #[cfg(feature = "2d")]
+                Collider::rectangle(4.0, 1.0),
+                #[cfg(feature = "3d")]
+                Collider::cuboid(4.0, 1.0, 4.0),
+            ));
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    RigidBody::Dynamic,
+                    Position(Vector::X * x + Vector::Y),
+                    #[cfg(feature = "2d")]
+                    Collider::circle(0.5),
+                    #[cfg(feature = "3d")]
+                    Collider::sphere(0.5),
+                ))
+                .id()
+        };
+
+        let on_ground = spawn_ball_on_ground(-5.0);
+        let on_pad = spawn_ball_on_ground(5.0);
+
+        // A soft ball that behaves like a critically damped spring with a frequency of 2 Hz.
+        // It sinks slowly, so sleeping is disabled to keep it from falling asleep before settling.
+        commands
+            .entity(on_pad)
+            .insert((ContactSoftness::new(1.0, 2.0), SleepingDisabled));
+
+        commands.insert_resource(Balls { on_ground, on_pad });
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let world = app.world();
+    let balls = world.resource::<Balls>();
+
+    // The ball on the stiff ground barely penetrates it.
+    let penetration = 1.0 - world.get::<Position>(balls.on_ground).unwrap().y;
+    assert!(penetration < 0.01);
+
+    // The soft ball sinks noticeably into the ground. For an ideal spring, the penetration would be
+    // g / omega^2 = 9.81 / (2 * PI * 2)^2 ~= 0.062, but the relaxation step makes the contact a bit stiffer.
+    let penetration = 1.0 - world.get::<Position>(balls.on_pad).unwrap().y;
+    assert!(penetration > 0.02 && penetration < 0.1);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn physics_material_updates_components_when_modified() {
+    let mut app = create_app();
+
+    // The material asset is registered when the plugins are finished.
+    app.finish();
+    app.cleanup();
+
+    let material = app
+        .world_mut()
+        .resource_mut::<Assets<PhysicsMaterial>>()
+        .add(PhysicsMaterial::new(
+            Friction::new(0.1),
+            Restitution::new(0.5),
+            2.0,
+        ));
+
+    let entity = app
+        .world_mut()
+        .spawn((
+            SpatialBundle::default(),
+            RigidBody::Dynamic,
+            #[cfg(feature = "2d")]
+            Collider::rectangle(1.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(1.0, 1.0, 1.0),
+            material.clone(),
+        ))
+        .id();
+
+    tick_60_fps(&mut app);
+
+    let world = app.world();
+    assert_eq!(*world.get::<Friction>(entity).unwrap(), Friction::new(0.1));
+    assert_eq!(
+        *world.get::<Restitution>(entity).unwrap(),
+        Restitution::new(0.5)
+    );
+    assert_eq!(world.get::<ColliderDensity>(entity).unwrap().0, 2.0);
+    assert_eq!(world.get::<Mass>(entity).unwrap().0, 2.0);
+
+    // Modify the material like a reloaded asset would.
+    let mut materials = app.world_mut().resource_mut::<Assets<PhysicsMaterial>>();
+    *materials.get_mut(&material).unwrap() = PhysicsMaterial::new(
+        Friction::new(0.8).with_combine_rule(CoefficientCombine::Max),
+        Restitution::ZERO,
+        4.0,
+    );
+
+    tick_60_fps(&mut app);
+
+    let world = app.world();
+    assert_eq!(
+        *world.get::<Friction>(entity).unwrap(),
+        Friction::new(0.8).with_combine_rule(CoefficientCombine::Max)
+    );
+    assert_eq!(
+        *world.get::<Restitution>(entity).unwrap(),
+        Restitution::ZERO
+    );
+    assert_eq!(world.get::<Mass>(entity).unwrap().0, 4.0);
+}
+
+#[test]
+#[cfg(all(
+    feature = "default-collider",
+    any(feature = "parry-f32", feature = "parry-f64")
+))]
+fn contact_force_events_respect_threshold() {
+    #[derive(Resource, Default)]
+    struct ContactForceEvents(Vec<ContactForceEvent>);
+
+    let mut app = create_app();
+
+    app.init_resource::<ContactForceEvents>().add_systems(
+        Update,
+        |mut reader: EventReader<ContactForceEvent>, mut events: ResMut<ContactForceEvents>| {
+            events.0.extend(reader.read().cloned());
+        },
+    );
+
+    app.add_systems(Startup, |mut commands: Commands| {
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Static,
+            #[cfg(feature = "2d")]
+            Collider::rectangle(10.0, 1.0),
+            #[cfg(feature = "3d")]
+            Collider::cuboid(10.0, 1.0, 10.0),
+        ));
+
+        // The weight of the ball is well below the threshold, but the impact is not.
+        commands.spawn((
+            SpatialBundle::default(),
+            RigidBody::Dynamic,
+            Position(Vector::Y * 5.0),
+            #[cfg(feature = "2d")]
+            Collider::circle(0.5),
+            #[cfg(feature = "3d")]
+            Collider::sphere(0.5),
+            Restitution::ZERO,
+            ContactForceEventThreshold(50.0),
+        ));
+    });
+
+    for _ in 0..120 {
+        tick_60_fps(&mut app);
+    }
+
+    let events = std::mem::take(&mut app.world_mut().resource_mut::<ContactForceEvents>().0);
+
+    // The impact should send an event with a vertical force.
+    assert!(!events.is_empty());
+    for event in events {
+        assert!(event.total_force_magnitude > 50.0);
+        assert!(event.max_force_magnitude <= event.total_force_magnitude);
+        assert_relative_eq!(event.max_force_direction.y.abs(), 1.0, epsilon = 0.01);
+    }
+
+    for _ in 0..30 {
+        tick_60_fps(&mut app);
+    }
+
+    // The ball is resting on the ground, so no more events should be sent.
+    assert!(app.world().resource::<ContactForceEvents>().0.is_empty());
+}
+
 #[derive(Component, Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Ord)]
 #[cfg(feature = "3d")]
 struct Id(usize);
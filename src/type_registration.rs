@@ -4,7 +4,7 @@ use crate::{
 };
 use bevy::prelude::*;
 use broad_phase::AabbIntersections;
-use dynamics::solver::SolverConfig;
+use dynamics::solver::{JointSolver, SolverConfig};
 
 /// Registers physics types to the `TypeRegistry` resource in `bevy_reflect`.
 pub struct PhysicsTypeRegistrationPlugin;
@@ -35,6 +35,7 @@ impl Plugin for PhysicsTypeRegistrationPlugin {
             .register_type::<PreSolveAngularVelocity>()
             .register_type::<Restitution>()
             .register_type::<Friction>()
+            .register_type::<AnisotropicFriction>()
             .register_type::<LinearDamping>()
             .register_type::<AngularDamping>()
             .register_type::<ExternalForce>()
@@ -62,8 +63,12 @@ impl Plugin for PhysicsTypeRegistrationPlugin {
             .register_type::<SpeculativeMargin>()
             .register_type::<SweptCcd>()
             .register_type::<CollisionMargin>()
+            .register_type::<SurfaceVelocity>()
+            .register_type::<ContactSoftness>()
+            .register_type::<ContactForceEventThreshold>()
             .register_type::<NarrowPhaseConfig>()
             .register_type::<SolverConfig>()
+            .register_type::<JointSolver>()
             .register_type::<SyncConfig>()
             .register_type::<ColliderConstructor>()
             .register_type::<ColliderConstructorHierarchy>()
@@ -75,9 +80,22 @@ impl Plugin for PhysicsTypeRegistrationPlugin {
             .register_type::<DistanceJoint>()
             .register_type::<FixedJoint>()
             .register_type::<PrismaticJoint>()
-            .register_type::<RevoluteJoint>();
+            .register_type::<RevoluteJoint>()
+            .register_type::<GenericJoint>()
+            .register_type::<AxisMotion>()
+            .register_type::<PulleyJoint>()
+            .register_type::<WheelJoint>()
+            .register_type::<GearJoint>()
+            .register_type::<TargetJoint>()
+            .register_type::<JointMotor>()
+            .register_type::<BreakableJoint>()
+            .register_type::<JointForces>()
+            .register_type::<JointImpulses>()
+            .register_type::<ArticulationLink>()
+            .register_type::<LinkJointType>();
 
         #[cfg(feature = "3d")]
-        app.register_type::<SphericalJoint>();
+        app.register_type::<SphericalJoint>()
+            .register_type::<ConeLimit>();
     }
 }